@@ -804,6 +804,7 @@ impl StatsDb {
                     })?;
                 }
             }
+            Stat::AiCommanderAction { .. } => (),
             Stat::DeployTroop { by, troop, gid } => {
                 self.pilots.with_pilot_and_aggregates(
                     by,
@@ -985,6 +986,7 @@ impl StatsDb {
                 })?;
             }
             Stat::Kill(dead) => self.record_kill(ctx, dead)?,
+            Stat::Bda { .. } => (),
             Stat::Points {
                 id,
                 points,
@@ -484,6 +484,9 @@ fn admin_spawn(ctx: &mut Context, lua: MizLua, id: Option<PlayerId>, key: String
                                 )
                                 .context("adding group")?;
                         }
+                        DeployableKind::Minefield(mcfg) => {
+                            ctx.db.add_minefield(side, ucid, &spec, *mcfg, pos, heading);
+                        }
                     }
                 }
             }
@@ -799,7 +802,9 @@ fn delete(ctx: &mut Context, id: &GroupId) -> Result<()> {
         DeployKind::Crate { .. }
         | DeployKind::Deployed { .. }
         | DeployKind::Troop { .. }
-        | DeployKind::Action { .. } => ctx.db.delete_group(id),
+        | DeployKind::Action { .. }
+        | DeployKind::Offensive { .. }
+        | DeployKind::DownedPilot { .. } => ctx.db.delete_group(id),
     }
 }
 
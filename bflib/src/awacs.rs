@@ -0,0 +1,293 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use crate::{
+    db::{
+        Db,
+        group::DeployKind,
+        player::{InstancedPlayer, Player},
+    },
+    ewr::{Ewr, EwrUnits},
+};
+use anyhow::{Result, anyhow, bail};
+use bfprotocols::{
+    cfg::{Action, ActionKind, AiPlaneCfg, AwacsCfg},
+    db::group::GroupId,
+};
+use chrono::prelude::*;
+use compact_str::{CompactString, format_compact};
+use dcso3::{
+    Vector2, azumith2d_to, coalition::Side, cvt_err, degrees_to_radians, net::Ucid,
+    pointing_towards2, radians_to_degrees, simple_enum,
+};
+use fxhash::FxHashMap;
+use mlua::{FromLua, IntoLua, Lua, Value, prelude::LuaResult};
+use serde_derive::{Deserialize, Serialize};
+use std::str::FromStr;
+
+simple_enum!(AwacsRequest, u8, [
+    Picture => 0,
+    BogeyDope => 1,
+    Tanker => 2
+]);
+
+impl FromStr for AwacsRequest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "picture" => Ok(Self::Picture),
+            "bogey" | "bogey dope" => Ok(Self::BogeyDope),
+            "tanker" | "vector to tanker" => Ok(Self::Tanker),
+            s => bail!("unknown awacs request {s}, expected picture, bogey, declare, or tanker"),
+        }
+    }
+}
+
+/// The point a player asks the awacs to declare, a bearing (degrees)
+/// and range (in the player's ewr units) from their ownship, or from
+/// bullseye when prefixed with bulls, e.g. 090/20 or bulls 270/45
+#[derive(Debug, Clone, Copy)]
+pub struct DeclarePoint {
+    bullseye: bool,
+    bearing: f64,
+    range: f64,
+}
+
+impl FromStr for DeclarePoint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (bullseye, s) = match s.strip_prefix("bulls") {
+            Some(s) => (true, s.trim()),
+            None => (false, s),
+        };
+        let (bearing, range) = s
+            .split_once('/')
+            .ok_or_else(|| anyhow!("expected [bulls] <bearing>/<range>"))?;
+        let bearing: f64 = bearing.trim().parse()?;
+        let range: f64 = range.trim().parse()?;
+        if !(0. ..=360.).contains(&bearing) || range < 0. {
+            bail!("invalid bearing or range {s}")
+        }
+        Ok(Self {
+            bullseye,
+            bearing,
+            range,
+        })
+    }
+}
+
+fn callsign(cfg: &AwacsCfg) -> &str {
+    cfg.callsign.as_ref().map(|s| s.as_str()).unwrap_or("AWACS")
+}
+
+fn frequency(cfg: &AiPlaneCfg) -> CompactString {
+    match cfg.freq {
+        Some(freq) => format_compact!(", {:.3} AM", freq as f64 / 1e6),
+        None => CompactString::default(),
+    }
+}
+
+/// The awacs providing service to a side. An awacs with no living
+/// units is off station.
+fn on_station(db: &Db, side: Side) -> Option<(GroupId, &AwacsCfg)> {
+    db.persisted.ewrs.into_iter().find_map(|gid| {
+        let group = db.persisted.groups.get(gid)?;
+        let alive = group.units.into_iter().any(|uid| {
+            db.persisted
+                .units
+                .get(uid)
+                .map(|u| !u.dead)
+                .unwrap_or(false)
+        });
+        match &group.origin {
+            DeployKind::Action {
+                spec:
+                    Action {
+                        kind: ActionKind::Awacs(cfg),
+                        ..
+                    },
+                ..
+            } if group.side == side && alive => Some((*gid, cfg)),
+            _ => None,
+        }
+    })
+}
+
+/// A vector from pos to the side's closest living tanker
+fn tanker_vector(db: &Db, side: Side, pos: Vector2) -> Option<CompactString> {
+    let (range, unit, cfg) = db
+        .persisted
+        .actions
+        .into_iter()
+        .filter_map(|gid| db.persisted.groups.get(gid))
+        .filter(|group| group.side == side)
+        .filter_map(|group| match &group.origin {
+            DeployKind::Action {
+                spec:
+                    Action {
+                        kind: ActionKind::Tanker(cfg),
+                        ..
+                    },
+                ..
+            } => {
+                let unit = group
+                    .units
+                    .into_iter()
+                    .filter_map(|uid| db.persisted.units.get(uid))
+                    .find(|u| !u.dead)?;
+                Some((na::distance(&pos.into(), &unit.pos.into()), unit, cfg))
+            }
+            _ => None,
+        })
+        .min_by(|(r0, _, _), (r1, _, _)| r0.total_cmp(r1))?;
+    let bearing = radians_to_degrees(azumith2d_to(pos, unit.pos)) as u16;
+    Some(format_compact!(
+        "tanker BRAA {bearing:03}/{}nm, {} thousand{}",
+        (range / 1852.).round() as u32,
+        (unit.position.p.y * 3.28084 / 1000.).round() as u32,
+        frequency(cfg)
+    ))
+}
+
+/// The player, their aircraft, and the callsign of the awacs serving
+/// their side
+fn awacs_for<'a>(db: &'a Db, ucid: &Ucid) -> Result<(&'a Player, &'a InstancedPlayer, &'a str)> {
+    let player = db.player(ucid).ok_or_else(|| anyhow!("unknown player"))?;
+    let (_, cfg) = match on_station(db, player.side) {
+        Some(awacs) => awacs,
+        None => bail!("no awacs is on station"),
+    };
+    let callsign = callsign(cfg);
+    match &player.current_slot {
+        Some((_, Some(inst))) => Ok((player, inst, callsign)),
+        Some((_, None)) | None => bail!("you must be flying to talk to {callsign}"),
+    }
+}
+
+/// Answer a player's request to their side's awacs
+pub fn request(
+    db: &Db,
+    ewr: &Ewr,
+    now: DateTime<Utc>,
+    ucid: &Ucid,
+    req: AwacsRequest,
+) -> Result<CompactString> {
+    let (player, inst, callsign) = awacs_for(db, ucid)?;
+    let name = &player.name;
+    let pos = Vector2::new(inst.position.p.x, inst.position.p.z);
+    let call = match req {
+        AwacsRequest::Picture => ewr
+            .side_picture(now, player.side)
+            .map(|p| format_compact!("picture, {p}")),
+        AwacsRequest::BogeyDope => ewr
+            .braa_call(now, ucid, player, inst)
+            .map(|c| format_compact!("bogey dope, {c}")),
+        AwacsRequest::Tanker => match tanker_vector(db, player.side, pos) {
+            Some(v) => Some(v),
+            None => return Ok(format_compact!("{name}, {callsign}, no tankers available")),
+        },
+    };
+    Ok(match call {
+        Some(call) => format_compact!("{name}, {callsign}, {call}"),
+        None => format_compact!("{name}, {callsign}, clean"),
+    })
+}
+
+/// Identify the track nearest the point the player asked about
+pub fn declare(
+    db: &Db,
+    ewr: &Ewr,
+    now: DateTime<Utc>,
+    ucid: &Ucid,
+    at: DeclarePoint,
+) -> Result<CompactString> {
+    let (player, inst, callsign) = awacs_for(db, ucid)?;
+    let name = &player.name;
+    let origin = if at.bullseye {
+        ewr.bullseye(player.side)
+            .ok_or_else(|| anyhow!("{callsign} has no bullseye"))?
+    } else {
+        Vector2::new(inst.position.p.x, inst.position.p.z)
+    };
+    let range = match ewr.units(ucid) {
+        EwrUnits::Imperial => at.range * 1852.,
+        EwrUnits::Metric => at.range * 1000.,
+    };
+    let point = origin + pointing_towards2(degrees_to_radians(at.bearing)) * range;
+    Ok(match ewr.declare(now, ucid, player, inst, point) {
+        Some(call) => format_compact!("{name}, {callsign}, declare, {call}"),
+        None => format_compact!("{name}, {callsign}, declare, clean"),
+    })
+}
+
+/// The awacs service of each side. It is available only while an
+/// awacs action is on station, and stops as soon as the awacs is lost.
+#[derive(Debug, Clone, Default)]
+pub struct Awacs {
+    on_station: FxHashMap<Side, (GroupId, CompactString)>,
+    last_picture: FxHashMap<Side, DateTime<Utc>>,
+}
+
+impl Awacs {
+    /// Announce awacs coming on and going off station, and broadcast
+    /// the periodic picture of each awacs on station to its side
+    pub fn update(&mut self, db: &mut Db, ewr: &Ewr, now: DateTime<Utc>) {
+        for side in [Side::Red, Side::Blue] {
+            let current = on_station(db, side).map(|(gid, cfg)| {
+                (
+                    gid,
+                    CompactString::from(callsign(cfg)),
+                    frequency(&cfg.plane),
+                    cfg.picture_interval,
+                )
+            });
+            let (gid, callsign, freq, interval) = match current {
+                Some(current) => current,
+                None => {
+                    if let Some((_, callsign)) = self.on_station.remove(&side) {
+                        self.last_picture.remove(&side);
+                        let msg = format_compact!("{callsign} is off station");
+                        db.ephemeral.msgs().panel_to_side(15, false, side, msg);
+                    }
+                    continue;
+                }
+            };
+            if self.on_station.get(&side).map(|(g, _)| *g) != Some(gid) {
+                self.last_picture.remove(&side);
+                self.on_station.insert(side, (gid, callsign.clone()));
+                let msg =
+                    format_compact!("{callsign} is on station{freq}, use -awacs or the EWR menu");
+                db.ephemeral.msgs().panel_to_side(15, false, side, msg);
+            }
+            let due = self
+                .last_picture
+                .get(&side)
+                .map(|ts| (now - *ts).num_seconds() >= interval as i64)
+                .unwrap_or(true);
+            if due {
+                self.last_picture.insert(side, now);
+                let msg = match ewr.side_picture(now, side) {
+                    Some(picture) => format_compact!("{callsign}, picture, {picture}"),
+                    None => format_compact!("{callsign}, picture clean"),
+                };
+                db.ephemeral.msgs().panel_to_side(20, false, side, msg);
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use anyhow::Result;
+use dcso3::coalition::Side;
+use fxhash::{FxHashMap, FxHashSet};
+use netidx::{
+    path::Path,
+    publisher::{Publisher, UpdateBatch, Val, Value},
+};
+
+/// A row of a side's target board, one per lasing jtac
+#[derive(Debug, Clone)]
+pub(crate) struct BoardRow {
+    pub jtac: String,
+    pub objective: String,
+    pub code: u16,
+    pub target: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub claimed_by: Option<String>,
+}
+
+fn claimed_by(row: &BoardRow) -> Value {
+    match &row.claimed_by {
+        Some(name) => Value::from(name.clone()),
+        None => Value::Null,
+    }
+}
+
+struct PubRow {
+    objective: Val,
+    code: Val,
+    target: Val,
+    lat: Val,
+    lon: Val,
+    claimed_by: Val,
+}
+
+impl PubRow {
+    fn new(publisher: &Publisher, base: &Path, row: &BoardRow) -> Result<Self> {
+        Ok(Self {
+            objective: publisher.publish(base.append("objective"), row.objective.clone())?,
+            code: publisher.publish(base.append("code"), row.code as u32)?,
+            target: publisher.publish(base.append("target"), row.target.clone())?,
+            lat: publisher.publish(base.append("lat"), row.lat)?,
+            lon: publisher.publish(base.append("lon"), row.lon)?,
+            claimed_by: publisher.publish(base.append("claimed_by"), claimed_by(row))?,
+        })
+    }
+
+    fn update(&self, batch: &mut UpdateBatch, row: &BoardRow) {
+        let Self {
+            objective,
+            code,
+            target,
+            lat,
+            lon,
+            claimed_by: claimed,
+        } = self;
+        objective.update_changed(batch, row.objective.clone());
+        code.update_changed(batch, row.code as u32);
+        target.update_changed(batch, row.target.clone());
+        lat.update_changed(batch, row.lat);
+        lon.update_changed(batch, row.lon);
+        claimed.update_changed(batch, claimed_by(row));
+    }
+}
+
+/// Publishes each side's target board as a table under
+/// base/<side>/<jtac>, rows are unpublished when the jtac stops lasing
+pub(super) struct PubBoard {
+    base: Path,
+    rows: FxHashMap<(Side, String), PubRow>,
+}
+
+impl PubBoard {
+    pub(super) fn new(base: Path) -> Self {
+        Self {
+            base,
+            rows: FxHashMap::default(),
+        }
+    }
+
+    pub(super) fn update(
+        &mut self,
+        publisher: &Publisher,
+        batch: &mut UpdateBatch,
+        side: Side,
+        board: Vec<BoardRow>,
+    ) -> Result<()> {
+        let mut present: FxHashSet<String> = FxHashSet::default();
+        for row in board {
+            let key = (side, row.jtac.clone());
+            match self.rows.get(&key) {
+                Some(pr) => pr.update(batch, &row),
+                None => {
+                    let base = self.base.append(side.to_str()).append(&row.jtac);
+                    let pr = PubRow::new(publisher, &base, &row)?;
+                    self.rows.insert(key, pr);
+                }
+            }
+            present.insert(row.jtac);
+        }
+        self.rows
+            .retain(|(s, jtac), _| *s != side || present.contains(jtac));
+        Ok(())
+    }
+}
@@ -14,11 +14,14 @@ FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
 for more details.
 */
 
+mod boardpub;
 mod logpub;
 mod perf;
 mod rpcs;
 mod statspub;
 
+pub(super) use boardpub::BoardRow;
+
 use crate::{admin::AdminCommand, db::persisted::Persisted};
 use anyhow::{Context, Result, anyhow, bail};
 use bfprotocols::{
@@ -26,11 +29,15 @@ use bfprotocols::{
     perf::{Perf, PerfStat},
     stats::Stat,
 };
+use boardpub::PubBoard;
 use bytes::{BufMut, Bytes, BytesMut};
 use chrono::prelude::*;
 use compact_str::{CompactString, format_compact};
 use crossbeam::queue::SegQueue;
-use dcso3::perf::{Perf as ApiPerf, PerfStat as ApiPerfStat};
+use dcso3::{
+    coalition::Side,
+    perf::{Perf as ApiPerf, PerfStat as ApiPerfStat},
+};
 use fxhash::FxHashMap;
 use log::error;
 use logpub::LogPublisher;
@@ -255,6 +262,10 @@ pub(super) enum Task {
     },
     Shutdown(Arc<(Mutex<bool>, Condvar)>),
     Stat(Stat),
+    TargetBoard {
+        side: Side,
+        board: Vec<BoardRow>,
+    },
 }
 
 enum Logs {
@@ -263,6 +274,7 @@ enum Logs {
         perf: PubPerf,
         stats: Statspub,
         log: LogPublisher,
+        board: PubBoard,
     },
     Files {
         log_path: PathBuf,
@@ -338,6 +350,20 @@ impl Logs {
         }
     }
 
+    async fn target_board(&mut self, side: Side, rows: Vec<BoardRow>) -> Result<()> {
+        match self {
+            Self::Files { .. } => Ok(()),
+            Self::Netidx {
+                publisher, board, ..
+            } => {
+                let mut batch = publisher.start_batch();
+                board.update(publisher, &mut batch, side, rows)?;
+                batch.commit(None).await;
+                Ok(())
+            }
+        }
+    }
+
     async fn switch_to_netidx(
         &mut self,
         publisher: Publisher,
@@ -371,11 +397,13 @@ impl Logs {
                     .context("starting stats pub")?;
                     let log = LogPublisher::new(publisher.clone(), log_path, base.append("log"))
                         .context("starting log pub")?;
+                    let board = PubBoard::new(base.append("board"));
                     Ok::<_, anyhow::Error>(Self::Netidx {
                         publisher: publisher.clone(),
                         perf,
                         stats,
                         log,
+                        board,
                     })
                 };
                 match go().await {
@@ -520,6 +548,11 @@ async fn background_loop(write_dir: PathBuf, mut rx: UnboundedReceiver<Task>) {
                     eprintln!("could not write stat {st:?} {e:?}")
                 }
             }
+            Task::TargetBoard { side, board } => {
+                if let Err(e) = logs.target_board(side, board).await {
+                    error!("could not publish the target board {e:?}")
+                }
+            }
         }
     }
 }
@@ -1,9 +1,16 @@
 use crate::{
     Context,
     admin::{self, AdminCommand, Caller},
+    awacs::{self, AwacsRequest, DeclarePoint},
     bg::Task,
-    db::{actions::ActionCmd, group::DeployKind, player::RegErr},
-    jtac::JtId,
+    db::{
+        actionqueue,
+        actions::ActionCmd,
+        group::DeployKind,
+        logistics::{RequestItem, RequestPriority},
+        player::RegErr,
+    },
+    jtac::{BriefFormat, JtId},
     lives,
     menu::{self, ArgQuad, ArgTriple, ArgTuple},
     msgq::MsgTyp,
@@ -12,7 +19,7 @@ use crate::{
 use anyhow::{Context as ErrContext, Result, anyhow, bail};
 use bfprotocols::{
     cfg::{Action, ActionKind},
-    db::group::GroupId,
+    db::{group::GroupId, objective::ObjectiveId},
     perf::PerfInner,
     stats::Stat,
 };
@@ -21,7 +28,7 @@ use compact_str::{CompactString, format_compact};
 use dcso3::{
     HooksLua, MizLua, String,
     coalition::Side,
-    net::{Net, PlayerId},
+    net::{Net, PlayerId, Ucid},
 };
 use fxhash::FxBuildHasher;
 use indexmap::IndexMap;
@@ -268,6 +275,8 @@ fn delete_command(ctx: &mut Context, id: PlayerId, s: &str) {
                     DeployKind::Objective { .. } | DeployKind::ObjectiveDeprecated => {
                         reply!("can't delete an objective group")
                     }
+                    DeployKind::Offensive { .. } => reply!("can't delete an offensive group"),
+                    DeployKind::DownedPilot { .. } => reply!("can't delete a downed pilot"),
                     DeployKind::Crate { .. } => match ctx.db.delete_group(&id) {
                         Err(e) => reply!("could not delete group {id} {e:?}"),
                         Ok(()) => reply!("deleted {id}"),
@@ -347,7 +356,182 @@ fn delete_command(ctx: &mut Context, id: PlayerId, s: &str) {
     }
 }
 
-fn action_help(ctx: &mut Context, actions: &IndexMap<String, Action, FxBuildHasher>, id: PlayerId) {
+fn request_command(ctx: &mut Context, id: PlayerId, now: DateTime<Utc>, s: &str) {
+    macro_rules! reply {
+        ($msg:expr) => {
+            ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), $msg)
+        };
+    }
+    let ucid = match ctx.connected.get(&id) {
+        Some(ifo) => ifo.ucid,
+        None => return,
+    };
+    let s = s.trim();
+    if s == "list" {
+        let side = match ctx.db.player(&ucid) {
+            Some(player) => player.side,
+            None => return reply!("you are not registered"),
+        };
+        let msgs: SmallVec<[CompactString; 8]> = ctx
+            .db
+            .supply_requests(side)
+            .map(|(obj, req)| {
+                format_compact!(
+                    " {}: {} ({} priority), expires in {}",
+                    obj.name(),
+                    req.items(),
+                    req.priority,
+                    format_duration(req.expires - now)
+                )
+            })
+            .collect();
+        if msgs.is_empty() {
+            reply!("there are no open supply requests")
+        }
+        for msg in msgs {
+            reply!(msg)
+        }
+    } else if let Some(name) = s.strip_prefix("cancel ") {
+        match admin::get_airbase(&ctx.db, name.trim()) {
+            Err(e) => reply!(format_compact!("could not find {name}, {e:?}")),
+            Ok(oid) => {
+                if let Err(e) = ctx.db.cancel_supply_request(&ucid, oid) {
+                    reply!(format_compact!("{e:?}"))
+                }
+            }
+        }
+    } else {
+        let mut parts = s.rsplitn(3, ' ');
+        let (items, priority, name) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(items), Some(priority), Some(name)) => (items, priority, name),
+            _ => return reply!("request expected <objective> <priority> <item>[,<item>...]"),
+        };
+        let priority = match priority.parse::<RequestPriority>() {
+            Ok(p) => p,
+            Err(e) => return reply!(format_compact!("{e:?}")),
+        };
+        let items = items
+            .split(',')
+            .filter(|i| !i.trim().is_empty())
+            .map(|i| match i.trim() {
+                i if i.eq_ignore_ascii_case("fuel") => RequestItem::Fuel,
+                i if i.eq_ignore_ascii_case("supplies") => RequestItem::Supplies,
+                i => RequestItem::Equipment(i.into()),
+            })
+            .collect();
+        match admin::get_airbase(&ctx.db, name.trim()) {
+            Err(e) => reply!(format_compact!("could not find {name}, {e:?}")),
+            Ok(oid) => {
+                if let Err(e) = ctx.db.add_supply_request(&ucid, oid, priority, items, now) {
+                    reply!(format_compact!("request failed {e:?}"))
+                }
+            }
+        }
+    }
+}
+
+fn upgrade_info(ctx: &Context, oid: &ObjectiveId) -> Result<CompactString> {
+    use std::fmt::Write;
+    let ucfg = match ctx.db.ephemeral.cfg.objective_upgrades.as_ref() {
+        Some(ucfg) => ucfg,
+        None => bail!("objective upgrades are not enabled"),
+    };
+    let obj = ctx.db.objective(oid)?;
+    let mut msg = format_compact!(
+        "{} is tier {}/{} with {} points",
+        obj.name(),
+        obj.tier(),
+        ucfg.tiers.len(),
+        obj.points
+    );
+    match ucfg.tiers.get(obj.tier() as usize) {
+        None => write!(msg, ", fully upgraded")?,
+        Some(tier) => {
+            write!(msg, "\nthe next tier costs {}", tier.cost)?;
+            for name in &tier.defenses {
+                write!(msg, "\n  adds defense {name}")?;
+            }
+            if tier.repair_speedup > 0 {
+                write!(msg, "\n  {}% faster repairs", tier.repair_speedup)?;
+            }
+            if tier.warehouse_capacity > 0 {
+                write!(
+                    msg,
+                    "\n  {}% more warehouse capacity",
+                    tier.warehouse_capacity
+                )?;
+            }
+            if !tier.slots.is_empty() {
+                write!(msg, "\n  unlocks {} slots", tier.slots.len())?;
+            }
+        }
+    }
+    Ok(msg)
+}
+
+fn upgrade_command(ctx: &mut Context, id: PlayerId, s: &str) {
+    macro_rules! reply {
+        ($msg:expr) => {
+            ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), $msg)
+        };
+    }
+    let s = s.trim();
+    match s.strip_prefix("info ") {
+        None => ctx.upgrade_commands.push((id, String::from(s))),
+        Some(name) => match admin::get_airbase(&ctx.db, name.trim()) {
+            Err(e) => reply!(format_compact!("could not find {name}, {e:?}")),
+            Ok(oid) => match upgrade_info(ctx, &oid) {
+                Err(e) => reply!(format_compact!("{e:?}")),
+                Ok(msg) => reply!(msg),
+            },
+        },
+    }
+}
+
+pub(super) fn run_upgrade_commands(ctx: &mut Context, lua: MizLua) -> Result<()> {
+    let spctx = SpawnCtx::new(lua).context("creating spawn ctx")?;
+    ctx.db.process_upgrade_respawns(&spctx, &ctx.idx);
+    let now = Utc::now();
+    for (id, s) in mem::take(&mut ctx.upgrade_commands) {
+        let (ucid, name) = match ctx.connected.get(&id) {
+            Some(ifo) => (ifo.ucid, ifo.name.clone()),
+            None => continue,
+        };
+        let res = match admin::get_airbase(&ctx.db, &s) {
+            Err(e) => Err(e),
+            Ok(oid) => ctx
+                .db
+                .upgrade_objective(&spctx, &ctx.idx, &ucid, oid, now)
+                .map(|tier| (oid, tier)),
+        };
+        match res {
+            Err(e) => ctx.db.ephemeral.msgs().send(
+                MsgTyp::Chat(Some(id)),
+                format_compact!("could not upgrade {s}: {e:?}"),
+            ),
+            Ok((oid, tier)) => {
+                let obj = ctx.db.objective(&oid)?;
+                let msg = format_compact!("{name} upgraded {} to tier {tier}", obj.name());
+                let side = obj.owner();
+                ctx.db.ephemeral.msgs().panel_to_side(10, false, side, msg)
+            }
+        }
+    }
+    Ok(())
+}
+
+fn action_help(
+    ctx: &mut Context,
+    actions: &IndexMap<String, Action, FxBuildHasher>,
+    id: PlayerId,
+    side: Side,
+    ucid: Ucid,
+) {
+    let now = Utc::now();
+    if let Some((available, max)) = ctx.db.command_budget(side) {
+        let msg = format_compact!("{side} command budget {available}/{max}");
+        ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg)
+    }
     for (name, action) in actions {
         let msg = match &action.kind {
             ActionKind::Attackers(_) => Some(format_compact!(
@@ -420,10 +604,27 @@ fn action_help(ctx: &mut Context, actions: &IndexMap<String, Action, FxBuildHash
                 "{name}: <from> <to> | Start a logistics transfer mission between from and to. cost {}",
                 action.cost
             )),
+            ActionKind::Airdrop(a) => Some(format_compact!(
+                "{name}: <objective|key> [packages] | Airdrop up to {} supply packages on a friendly objective, or as crates at key, a mark point. cost {} per package",
+                a.max_packages,
+                action.cost
+            )),
             ActionKind::Nuke(_) => Some(format_compact!(
                 "{name}: <key> | Nuke key, a mark point. cost {}",
                 action.cost
             )),
+            ActionKind::Jammer(_) => Some(format_compact!(
+                "{name}: <key> | Spawn a jammer at key, a mark point. It degrades enemy radars toward the enemy. cost {}",
+                action.cost
+            )),
+            ActionKind::JammerWaypoint => Some(format_compact!(
+                "{name}: <group> <key> | Move a jammer to key, a mark point. Group is the jammer group. cost {}",
+                action.cost
+            )),
+            ActionKind::Recon(_) => Some(format_compact!(
+                "{name}: <key> | Fly a recon plane over key, a mark point, marking enemy groups it sees. cost {}",
+                action.cost
+            )),
             ActionKind::Paratrooper(d) => Some(format_compact!(
                 "{name}: <key> | Drop {} troops at key, a mark point. cost {}",
                 d.name,
@@ -437,8 +638,23 @@ fn action_help(ctx: &mut Context, actions: &IndexMap<String, Action, FxBuildHash
                 "{name}: <group> <key> | Move a tanker to key. Group is the tanker group. cost {}",
                 action.cost
             )),
+            ActionKind::NavalPatrol(_) => Some(format_compact!(
+                "{name}: <key> | Sail a naval group from the nearest port or carrier to key, a mark point. cost {}",
+                action.cost
+            )),
+            ActionKind::ShoreBombardment(_) => None,
+            ActionKind::AntiShip(_) => Some(format_compact!(
+                "{name}: <key> | Spawn an anti ship strike that engages ships near key, a mark point. cost {}",
+                action.cost
+            )),
         };
-        if let Some(msg) = msg {
+        if let Some(mut msg) = msg {
+            if let Some(status) =
+                ctx.db
+                    .action_throttle_status(side, &ucid, name, &action.throttle, now)
+            {
+                msg.push_str(&format_compact!(" ({status})"));
+            }
             ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg)
         }
     }
@@ -450,10 +666,32 @@ fn action_command(ctx: &mut Context, id: PlayerId, cmd: &str) {
             if let Some(player) = ctx.db.player(&ifo.ucid) {
                 let cfg = Arc::clone(&ctx.db.ephemeral.cfg);
                 if let Some(actions) = cfg.actions.get(&player.side) {
-                    action_help(ctx, actions, id)
+                    let (side, ucid) = (player.side, ifo.ucid);
+                    action_help(ctx, actions, id, side, ucid)
                 }
             }
         }
+    } else if cmd.trim().eq_ignore_ascii_case("queue") {
+        if let Some(ifo) = ctx.connected.get(&id) {
+            if let Some(player) = ctx.db.player(&ifo.ucid) {
+                let msg = ctx.db.format_action_queue(player.side);
+                ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg)
+            }
+        }
+    } else if let Some(qid) = cmd.strip_prefix("cancel ") {
+        if let Some(ifo) = ctx.connected.get(&id) {
+            let ucid = ifo.ucid.clone();
+            let r = qid
+                .trim()
+                .trim_start_matches('#')
+                .parse::<u64>()
+                .map_err(anyhow::Error::from)
+                .and_then(|qid| ctx.db.cancel_queued_action(&ucid, qid));
+            if let Err(e) = r {
+                let msg = format_compact!("could not cancel {qid}: {e:?}");
+                ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg)
+            }
+        }
     } else {
         ctx.action_commands.push((id, String::from(cmd)))
     }
@@ -470,6 +708,14 @@ pub(super) fn run_action_commands(
             if let Some(player) = ctx.db.player(&ifo.ucid) {
                 let ucid = ifo.ucid.clone();
                 let side = player.side;
+                if let Some(q) = s.strip_prefix("queue ") {
+                    let msg = match ctx.db.queue_action(lua, side, ucid, q, Utc::now()) {
+                        Err(e) => format_compact!("could not queue action {q}: {e:?}"),
+                        Ok(qid) => format_compact!("queued action #{qid}"),
+                    };
+                    ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
+                    continue;
+                }
                 let r = match ActionCmd::parse(&mut ctx.db, lua, side, &s) {
                     Err(e) => Err(e),
                     Ok(cmd) => ctx.db.start_action(
@@ -485,7 +731,7 @@ pub(super) fn run_action_commands(
                 };
                 let msg = match r {
                     Err(e) => format_compact!("could not run action {s}: {e:?}"),
-                    Ok(()) => format_compact!("action {s} started"),
+                    Ok(_) => format_compact!("action {s} started"),
                 };
                 ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg)
             }
@@ -544,6 +790,10 @@ fn jtac_command(ctx: &mut Context, id: PlayerId, s: &str) {
             .ephemeral
             .msgs()
             .send(MsgTyp::Chat(Some(id)), " -jtac <id> status");
+        ctx.db.ephemeral.msgs().send(
+            MsgTyp::Chat(Some(id)),
+            " -jtac <id> nineline [mgrs|dms|ddm]",
+        );
         ctx.db
             .ephemeral
             .msgs()
@@ -560,6 +810,38 @@ fn jtac_command(ctx: &mut Context, id: PlayerId, s: &str) {
             .ephemeral
             .msgs()
             .send(MsgTyp::Chat(Some(id)), " -jtac <id> bomber [mission]");
+        ctx.db
+            .ephemeral
+            .msgs()
+            .send(MsgTyp::Chat(Some(id)), " -jtac <id> bombard [mission]");
+        ctx.db
+            .ephemeral
+            .msgs()
+            .send(MsgTyp::Chat(Some(id)), " -jtac <id> claim");
+        ctx.db
+            .ephemeral
+            .msgs()
+            .send(MsgTyp::Chat(Some(id)), " -jtac board");
+        ctx.db.ephemeral.msgs().send(
+            MsgTyp::Chat(Some(id)),
+            " -jtac next <tag[|tag]> <objective>",
+        );
+    } else if s.trim().eq_ignore_ascii_case("board") {
+        let side = ctx
+            .connected
+            .get(&id)
+            .and_then(|ifo| ctx.db.player(&ifo.ucid))
+            .map(|p| p.side);
+        let msg = match side {
+            None => CompactString::from("You must register first. Type red or blue in chat"),
+            Some(side) => match ctx.jtac.board_msg(&ctx.db, side) {
+                Ok(msg) => msg,
+                Err(e) => format_compact!("could not generate the target board {e:?}"),
+            },
+        };
+        ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
+    } else if let Some(s) = s.strip_prefix("next ") {
+        ctx.target_requests.push((id, s.into()));
     } else if let Some((jtid, cmd)) = s.split_once(" ") {
         if let Ok(jtid) = jtid.parse::<JtId>() {
             ctx.jtac_commands.push((id, jtid, cmd.into()));
@@ -635,6 +917,28 @@ fn run_jtac_command(
             snd: jtid,
         };
         menu::jtac::jtac_status(lua, arg)?
+    } else if let Some(s) = cmd.strip_prefix("nineline") {
+        let fmt = match s.parse::<BriefFormat>() {
+            Ok(fmt) => fmt,
+            Err(e) => error!("{e}"),
+        };
+        let panel_to_side = ctx
+            .db
+            .player(&ucid)
+            .map(|p| p.jtac_or_spectators)
+            .unwrap_or(true);
+        let arg = ArgTriple {
+            fst: (!panel_to_side).then_some(ucid),
+            snd: jtid,
+            trd: fmt,
+        };
+        menu::jtac::jtac_nineline(lua, arg)?
+    } else if let Some(_) = cmd.strip_prefix("claim") {
+        let arg = ArgTuple {
+            fst: ucid,
+            snd: jtid,
+        };
+        menu::jtac::jtac_claim_target(lua, arg)?
     } else if let Some(_) = cmd.strip_prefix("smoke") {
         let arg = ArgTuple {
             fst: ucid,
@@ -671,6 +975,30 @@ fn run_jtac_command(
                 menu::jtac::call_bomber(lua, arg)?
             }
         }
+    } else if let Some(s) = cmd.strip_prefix("bombard") {
+        let name = s.trim();
+        let name = if name != "" {
+            Some(String::from(name))
+        } else {
+            let naval_missions = ctx.db.ephemeral.cfg.actions.get(&side);
+            naval_missions.iter().find_map(|acts| {
+                acts.iter().find_map(|(n, a)| match a.kind {
+                    ActionKind::ShoreBombardment(_) => Some(n.clone()),
+                    _ => None,
+                })
+            })
+        };
+        match name {
+            None => error!("no shore bombardment mission(s)"),
+            Some(name) => {
+                let arg = ArgTriple {
+                    fst: jtid,
+                    snd: ucid,
+                    trd: name,
+                };
+                menu::jtac::call_shore_bombardment(lua, arg)?
+            }
+        }
     } else if let Some(s) = cmd.strip_prefix("code ") {
         let code = match s.parse::<u16>() {
             Ok(c) => c,
@@ -722,14 +1050,70 @@ fn run_jtac_command(
     Ok(())
 }
 
+fn run_target_request(ctx: &mut Context, lua: MizLua, id: PlayerId, s: String) -> Result<()> {
+    let ucid = ctx
+        .connected
+        .get(&id)
+        .ok_or_else(|| anyhow!("unknown player"))?
+        .ucid;
+    let r = s
+        .split_once(" ")
+        .ok_or_else(|| anyhow!("expected -jtac next <tag[|tag]> <objective>"))
+        .and_then(|(tags, obj)| {
+            let tags = actionqueue::parse_tags(tags)?;
+            let oid = admin::get_airbase(&ctx.db, obj.trim())?;
+            Ok((tags, oid))
+        });
+    match r {
+        Ok((tags, oid)) => {
+            let arg = ArgTriple {
+                fst: ucid,
+                snd: oid,
+                trd: tags.0.bits(),
+            };
+            menu::jtac::jtac_request_target(lua, arg)?
+        }
+        Err(e) => ctx.db.ephemeral.msgs().send(
+            MsgTyp::Chat(Some(id)),
+            format_compact!("could not request a target {e}"),
+        ),
+    }
+    Ok(())
+}
+
 pub(super) fn run_jtac_commands(ctx: &mut Context, lua: MizLua) -> Result<()> {
     let cmds = mem::take(&mut ctx.jtac_commands);
     for (id, jtid, cmd) in cmds {
         run_jtac_command(ctx, lua, id, jtid, cmd)?
     }
+    let requests = mem::take(&mut ctx.target_requests);
+    for (id, s) in requests {
+        run_target_request(ctx, lua, id, s)?
+    }
     Ok(())
 }
 
+fn awacs_command(ctx: &mut Context, id: PlayerId, now: DateTime<Utc>, s: &str) {
+    let msg = match ctx.connected.get(&id) {
+        None => CompactString::from("You must register first. Type red or blue in chat"),
+        Some(ifo) => {
+            let res = match s.trim().strip_prefix("declare") {
+                Some(at) => at
+                    .parse::<DeclarePoint>()
+                    .and_then(|at| awacs::declare(&ctx.db, &ctx.ewr, now, &ifo.ucid, at)),
+                None => s
+                    .parse::<AwacsRequest>()
+                    .and_then(|req| awacs::request(&ctx.db, &ctx.ewr, now, &ifo.ucid, req)),
+            };
+            match res {
+                Ok(msg) => msg,
+                Err(e) => format_compact!("{e}"),
+            }
+        }
+    };
+    ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
+}
+
 fn help_command(ctx: &mut Context, id: PlayerId) {
     let admin = match ctx.connected.get(&id) {
         None => false,
@@ -744,9 +1128,18 @@ fn help_command(ctx: &mut Context, id: PlayerId) {
         " -balance: show your points balance",
         " -transfer <amount> [<player> | objective:<objective>]: transfer points to another player or objective",
         " -delete <groupid>: delete a group you deployed for a partial refund",
+        " -request <objective> <low|normal|high> <item>[,<item>...]: request supplies, items are fuel, supplies, or an equipment name",
+        " -request [list | cancel <objective>]: list or cancel supply requests",
+        " -upgrade <objective>: buy the next upgrade tier for an objective with its points",
+        " -upgrade info <objective>: show an objective's upgrade tier and the next tier",
         " -action <name> <args>: perform an action, -action help for a list of actions",
+        " -action queue: list your side's queued actions",
+        " -action queue <at HH:MM | jtac <jtid> <tag[|tag]> | after <#id>> <name> <args>: queue an action, $ in the args is the jtac's target or the group spawned by the previous action",
+        " -action cancel <#id>: cancel a queued action and refund its cost",
         " -bind <token>: bind your ucid to the specified token (for the web gui)",
         " -jtac <jtid> <cmd>",
+        " -awacs <picture|bogey|tanker>: ask your side's awacs for a picture, bogey dope, or a vector to the nearest tanker",
+        " -awacs declare [bulls] <bearing>/<range>: ask your side's awacs to identify the track nearest a point, from you or from bullseye",
         " -help: show this help message",
     ] {
         ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), cmd)
@@ -793,12 +1186,21 @@ pub(super) fn process(
     } else if let Some(s) = msg.strip_prefix("-delete ") {
         delete_command(ctx, id, s);
         Ok("".into())
+    } else if let Some(s) = msg.strip_prefix("-request ") {
+        request_command(ctx, id, now, s);
+        Ok("".into())
+    } else if let Some(s) = msg.strip_prefix("-upgrade ") {
+        upgrade_command(ctx, id, s);
+        Ok("".into())
     } else if let Some(s) = msg.strip_prefix("-bind ") {
         bind_command(ctx, id, s);
         Ok("".into())
     } else if let Some(s) = msg.strip_prefix("-jtac ") {
         jtac_command(ctx, id, s);
         Ok("".into())
+    } else if let Some(s) = msg.strip_prefix("-awacs ") {
+        awacs_command(ctx, id, now, s);
+        Ok("".into())
     } else if msg.starts_with("-help") {
         help_command(ctx, id);
         Ok("".into())
@@ -0,0 +1,500 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{
+    Db,
+    actions::{ActionArgs, ActionCmd, ArgSubst},
+};
+use crate::{
+    jtac::{JtId, Jtacs},
+    spawnctx::SpawnCtx,
+};
+use anyhow::{Result, anyhow, bail};
+use bfprotocols::{
+    cfg::{UnitTag, UnitTags},
+    db::group::GroupId,
+    perf::PerfInner,
+};
+use chrono::{Duration, prelude::*};
+use compact_str::{CompactString, format_compact};
+use dcso3::{
+    MizLua, String, Vector2, coalition::Side, env::miz::MizIndex, net::Ucid, timer::Timer,
+    trigger::MarkId,
+};
+use enumflags2::BitFlags;
+use fxhash::FxHashMap;
+use log::info;
+use serde_derive::{Deserialize, Serialize};
+use smallvec::{SmallVec, smallvec};
+use std::fmt;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QueueTrigger {
+    /// run at the specified time
+    At(DateTime<Utc>),
+    /// run when the jtac's target has any of the specified tags
+    JtacFinds { jtac: JtId, tags: UnitTags },
+    /// run after the specified queued action has started
+    After(u64),
+}
+
+impl fmt::Display for QueueTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::At(ts) => write!(f, "in {}m", (*ts - Utc::now()).num_minutes().max(0)),
+            Self::JtacFinds { jtac, tags } => write!(f, "when jtac {jtac} finds {tags}"),
+            Self::After(id) => write!(f, "after #{id}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QueuedArgs {
+    /// arguments parsed when the action was queued
+    Parsed(ActionArgs),
+    /// arguments containing $ that are parsed when the action runs
+    Deferred(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedAction {
+    pub id: u64,
+    pub side: Side,
+    pub player: Ucid,
+    pub name: String,
+    pub cmd: String,
+    pub args: QueuedArgs,
+    pub trigger: QueueTrigger,
+    /// the points the player paid when the action was queued
+    pub cost: u32,
+    pub queued: DateTime<Utc>,
+    #[serde(default)]
+    pub subst_pos: Option<Vector2>,
+    #[serde(default)]
+    pub subst_group: Option<GroupId>,
+}
+
+impl QueuedAction {
+    fn pos(&self) -> Option<Vector2> {
+        match &self.args {
+            QueuedArgs::Parsed(args) => args.pos(),
+            QueuedArgs::Deferred(_) => self.subst_pos,
+        }
+    }
+}
+
+pub(crate) fn parse_tags(s: &str) -> Result<UnitTags> {
+    let mut tags = BitFlags::empty();
+    for name in s.split('|') {
+        let tag = BitFlags::<UnitTag>::all()
+            .iter()
+            .find(|t| format_compact!("{t:?}").eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("unknown unit tag {name}"))?;
+        tags |= tag;
+    }
+    Ok(UnitTags(tags))
+}
+
+/// parse a zulu time of day, e.g. 18:30, 1830, or 1830Z
+fn parse_zulu(s: &str) -> Result<u32> {
+    let s = s
+        .trim_end_matches(|c| c == 'Z' || c == 'z')
+        .replace(':', "");
+    if s.len() != 4 {
+        bail!("expected a time like 18:30Z")
+    }
+    let hours: u32 = s[0..2].parse()?;
+    let minutes: u32 = s[2..4].parse()?;
+    if hours > 23 || minutes > 59 {
+        bail!("invalid time {s}")
+    }
+    Ok(hours * 3600 + minutes * 60)
+}
+
+impl Db {
+    fn next_queue_id(&self) -> u64 {
+        self.persisted
+            .action_queue
+            .into_iter()
+            .map(|(id, _)| *id)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// Queue an action to run later. s is one of
+    /// - at <time> <action> <args>
+    /// - jtac <jtac> <tag[|tag]> <action> <args>
+    /// - after <queue id> <action> <args>
+    pub fn queue_action(
+        &mut self,
+        lua: MizLua,
+        side: Side,
+        ucid: Ucid,
+        s: &str,
+        now: DateTime<Utc>,
+    ) -> Result<u64> {
+        let qcfg = match self.ephemeral.cfg.action_queue.as_ref() {
+            Some(qcfg) => qcfg.clone(),
+            None => bail!("action queueing is disabled"),
+        };
+        if !self.ephemeral.cfg.rules.actions.check(&ucid) {
+            bail!("you are not authorized for actions")
+        }
+        let queued = self
+            .persisted
+            .action_queue
+            .into_iter()
+            .filter(|(_, qa)| qa.player == ucid)
+            .count() as u32;
+        if queued >= qcfg.max_queued {
+            bail!("you already have {queued} actions queued")
+        }
+        let (trigger, s) = match s.split_once(' ') {
+            Some(("at", s)) => {
+                let (time, s) = s
+                    .split_once(' ')
+                    .ok_or_else(|| anyhow!("expected <time>"))?;
+                let target = parse_zulu(time)? as i64;
+                let abs = Timer::singleton(lua)?.get_abs_time()?.0 as i64 % 86400;
+                let delay = (target - abs).rem_euclid(86400);
+                (QueueTrigger::At(now + Duration::seconds(delay)), s)
+            }
+            Some(("jtac", s)) => {
+                let (jtac, s) = s
+                    .split_once(' ')
+                    .ok_or_else(|| anyhow!("expected <jtac>"))?;
+                let (tags, s) = s.split_once(' ').ok_or_else(|| anyhow!("expected <tag>"))?;
+                let trigger = QueueTrigger::JtacFinds {
+                    jtac: jtac.parse()?,
+                    tags: parse_tags(tags)?,
+                };
+                (trigger, s)
+            }
+            Some(("after", s)) => {
+                let (id, s) = s.split_once(' ').ok_or_else(|| anyhow!("expected <id>"))?;
+                let id: u64 = id.trim_start_matches('#').parse()?;
+                match self.persisted.action_queue.get(&id) {
+                    Some(qa) if qa.side == side => (),
+                    Some(_) | None => bail!("no such queued action #{id}"),
+                }
+                (QueueTrigger::After(id), s)
+            }
+            _ => bail!("expected at, jtac, or after"),
+        };
+        let (name, args) = s
+            .split_once(' ')
+            .ok_or_else(|| anyhow!("expected <action> <args>"))?;
+        let action = self
+            .ephemeral
+            .cfg
+            .actions
+            .get(&side)
+            .and_then(|actions| actions.get(name))
+            .ok_or_else(|| anyhow!("no such action {name}"))?
+            .clone();
+        if let Some(limit) = action.limit {
+            let taken = self
+                .ephemeral
+                .actions_taken
+                .get(&side)
+                .and_then(|t| t.get(name))
+                .copied()
+                .unwrap_or(0);
+            let queued = self
+                .persisted
+                .action_queue
+                .into_iter()
+                .filter(|(_, qa)| qa.side == side && qa.name.as_str() == name)
+                .count() as u32;
+            if taken + queued >= limit {
+                bail!("{side} has no {name} actions left to queue")
+            }
+        }
+        let args = if args.split_whitespace().any(|a| a == "$") {
+            QueuedArgs::Deferred(args.into())
+        } else {
+            QueuedArgs::Parsed(ActionArgs::parse(self, &action.kind, lua, side, args)?)
+        };
+        let parsed = match &args {
+            QueuedArgs::Parsed(args) => Some(args),
+            QueuedArgs::Deferred(_) => None,
+        };
+        let cost = self.action_cost(side, &action, parsed)?;
+        match self.persisted.players.get(&ucid) {
+            None => bail!("unknown player {ucid}"),
+            Some(player) if cost > 0 && player.points < cost as i32 => bail!(
+                "this action costs {cost} points and you have {} points",
+                player.points
+            ),
+            Some(_) => (),
+        }
+        let id = self.next_queue_id();
+        self.adjust_points(
+            &ucid,
+            -(cost as i32),
+            &format_compact!("queue action #{id}"),
+        );
+        let qa = QueuedAction {
+            id,
+            side,
+            player: ucid,
+            name: name.into(),
+            cmd: s.into(),
+            args,
+            trigger,
+            cost,
+            queued: now,
+            subst_pos: None,
+            subst_group: None,
+        };
+        info!("{ucid} queued action #{id} {} {}", qa.cmd, qa.trigger);
+        self.persisted.action_queue.insert_cow(id, qa);
+        self.ephemeral.dirty();
+        Ok(id)
+    }
+
+    /// Remove a queued action and all the actions queued after it,
+    /// refunding their cost
+    fn cancel_queued(&mut self, id: u64, why: &str) {
+        let qa = match self.persisted.action_queue.remove_cow(&id) {
+            Some(qa) => qa,
+            None => return,
+        };
+        self.adjust_points(
+            &qa.player,
+            qa.cost as i32,
+            &format_compact!("refund queued action #{id}"),
+        );
+        if let Some(mark) = self.ephemeral.action_queue_marks.remove(&id) {
+            self.ephemeral.msgs.delete_mark(mark);
+        }
+        let msg = format_compact!("queued action #{id} {} canceled, {why}", qa.cmd);
+        self.ephemeral.msgs.panel_to_side(10, false, qa.side, msg);
+        self.ephemeral.dirty();
+        let children: SmallVec<[u64; 4]> = self
+            .persisted
+            .action_queue
+            .into_iter()
+            .filter_map(|(cid, c)| match c.trigger {
+                QueueTrigger::After(parent) if parent == id => Some(*cid),
+                _ => None,
+            })
+            .collect();
+        for cid in children {
+            self.cancel_queued(cid, &format_compact!("#{id} was canceled"))
+        }
+    }
+
+    pub fn cancel_queued_action(&mut self, ucid: &Ucid, id: u64) -> Result<()> {
+        match self.persisted.action_queue.get(&id) {
+            None => bail!("no such queued action #{id}"),
+            Some(qa) if &qa.player != ucid => bail!("queued action #{id} isn't yours"),
+            Some(_) => self.cancel_queued(id, "by request"),
+        }
+        Ok(())
+    }
+
+    pub fn format_action_queue(&self, side: Side) -> CompactString {
+        let mut msg = CompactString::new("");
+        for (id, qa) in &self.persisted.action_queue {
+            if qa.side == side {
+                let name = self
+                    .persisted
+                    .players
+                    .get(&qa.player)
+                    .map(|p| p.name.clone())
+                    .unwrap_or_default();
+                msg.push_str(&format_compact!(
+                    "#{id} {} by {name} {}\n",
+                    qa.cmd,
+                    qa.trigger
+                ));
+            }
+        }
+        if msg.is_empty() {
+            msg.push_str("no actions are queued");
+        }
+        msg
+    }
+
+    fn run_queued(
+        &mut self,
+        lua: MizLua,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        jtacs: &Jtacs,
+        id: u64,
+        now: DateTime<Utc>,
+    ) {
+        let qa = match self.persisted.action_queue.get(&id) {
+            Some(qa) => qa.clone(),
+            None => return,
+        };
+        let subst = ArgSubst {
+            pos: qa.subst_pos,
+            group: qa.subst_group,
+        };
+        let res = self
+            .ephemeral
+            .cfg
+            .actions
+            .get(&qa.side)
+            .and_then(|actions| actions.get(&qa.name))
+            .cloned()
+            .ok_or_else(|| anyhow!("the action no longer exists"))
+            .and_then(|action| {
+                let args = match qa.args {
+                    QueuedArgs::Parsed(args) => args,
+                    QueuedArgs::Deferred(s) => {
+                        ActionArgs::parse_with(self, &action.kind, lua, qa.side, &subst, &s)?
+                    }
+                };
+                let cmd = ActionCmd {
+                    name: qa.name.clone(),
+                    action,
+                    args,
+                };
+                // the player prepaid, start_action will charge the
+                // actual cost
+                self.adjust_points(
+                    &qa.player,
+                    qa.cost as i32,
+                    &format_compact!("run queued action #{id}"),
+                );
+                let res =
+                    self.start_action(lua, perf, spctx, idx, jtacs, qa.side, Some(qa.player), cmd);
+                if res.is_err() {
+                    self.adjust_points(
+                        &qa.player,
+                        -(qa.cost as i32),
+                        &format_compact!("queued action #{id} failed"),
+                    );
+                }
+                res
+            });
+        match res {
+            Err(e) => self.cancel_queued(id, &format_compact!("{e}")),
+            Ok(gid) => {
+                self.persisted.action_queue.remove_cow(&id);
+                if let Some(mark) = self.ephemeral.action_queue_marks.remove(&id) {
+                    self.ephemeral.msgs.delete_mark(mark);
+                }
+                for (_, c) in self.persisted.action_queue.iter_mut_cow() {
+                    if let QueueTrigger::After(parent) = c.trigger
+                        && parent == id
+                    {
+                        c.trigger = QueueTrigger::At(now);
+                        c.subst_group = gid;
+                    }
+                }
+                let msg = format_compact!("queued action #{id} {} started", qa.cmd);
+                self.ephemeral.msgs.panel_to_side(10, false, qa.side, msg);
+                self.ephemeral.dirty();
+            }
+        }
+    }
+
+    /// Run queued actions whose trigger has fired, cancel jtac
+    /// triggered actions that have waited too long, and keep their F10
+    /// marks up to date
+    pub fn run_action_queue(
+        &mut self,
+        lua: MizLua,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        jtacs: &Jtacs,
+        now: DateTime<Utc>,
+    ) {
+        let max_wait = match self.ephemeral.cfg.action_queue.as_ref() {
+            Some(qcfg) => Duration::minutes(qcfg.max_wait as i64),
+            None => return,
+        };
+        let mut ready: SmallVec<[u64; 4]> = smallvec![];
+        let mut cancel: SmallVec<[(u64, &'static str); 4]> = smallvec![];
+        let mut found: FxHashMap<u64, Vector2> = FxHashMap::default();
+        for (id, qa) in &self.persisted.action_queue {
+            match &qa.trigger {
+                QueueTrigger::At(ts) => {
+                    if now >= *ts {
+                        ready.push(*id);
+                    }
+                }
+                QueueTrigger::JtacFinds { jtac, tags } => match jtacs.get(jtac) {
+                    Err(_) => {
+                        cancel.push((*id, "the jtac is gone"));
+                        continue;
+                    }
+                    Ok(jt) => {
+                        if let Some(target) = jt.target() {
+                            let ttags = self
+                                .ephemeral
+                                .cfg
+                                .unit_classification
+                                .get(&target.typ)
+                                .map(|t| t.0)
+                                .unwrap_or(BitFlags::empty());
+                            if ttags.intersects(tags.0) {
+                                found.insert(*id, Vector2::new(target.pos.x, target.pos.z));
+                                ready.push(*id);
+                                continue;
+                            }
+                        }
+                        if now - qa.queued >= max_wait {
+                            cancel.push((*id, "it waited too long"));
+                        }
+                    }
+                },
+                QueueTrigger::After(_) => (),
+            }
+        }
+        for (id, why) in cancel {
+            self.cancel_queued(id, why)
+        }
+        for id in ready {
+            if let Some(pos) = found.get(&id)
+                && let Some(qa) = self.persisted.action_queue.get_mut_cow(&id)
+            {
+                qa.subst_pos = Some(*pos);
+            }
+            self.run_queued(lua, perf, spctx, idx, jtacs, id, now)
+        }
+        let stale: SmallVec<[u64; 4]> = self
+            .ephemeral
+            .action_queue_marks
+            .keys()
+            .filter(|id| self.persisted.action_queue.get(id).is_none())
+            .copied()
+            .collect();
+        for id in stale {
+            if let Some(mark) = self.ephemeral.action_queue_marks.remove(&id) {
+                self.ephemeral.msgs.delete_mark(mark);
+            }
+        }
+        for (id, qa) in &self.persisted.action_queue {
+            if self.ephemeral.action_queue_marks.contains_key(id) {
+                continue;
+            }
+            if let Some(pos) = qa.pos() {
+                let msg = format_compact!("queued action #{id} {} {}", qa.cmd, qa.trigger);
+                let mark: MarkId = self.ephemeral.msgs.mark_to_side(qa.side, pos, true, msg);
+                self.ephemeral.action_queue_marks.insert(*id, mark);
+            }
+        }
+    }
+}
@@ -11,8 +11,9 @@ use crate::{
 use anyhow::{Context, Ok, Result, anyhow, bail};
 use bfprotocols::{
     cfg::{
-        Action, ActionGeoLimit, ActionKind, AiPlaneCfg, AiPlaneKind, AwacsCfg, BomberCfg,
-        DeployableCfg, DeployableKind, DroneCfg, LimitEnforceTyp, MoveCfg, NukeCfg, UnitTag,
+        Action, ActionGeoLimit, ActionKind, AiPlaneCfg, AiPlaneKind, AirdropCfg, AwacsCfg,
+        BomberCfg, DeployableCfg, DeployableKind, DroneCfg, JammerCfg, LimitEnforceTyp, MoveCfg,
+        NavalGroupCfg, NukeCfg, ReconCfg, ShoreBombardmentCfg, UnitTag,
     },
     db::{
         group::GroupId,
@@ -26,11 +27,11 @@ use compact_str::format_compact;
 use dcso3::{
     LuaVec2, LuaVec3, MizLua, String, Time, Vector2, Vector3,
     attribute::Attribute,
-    centroid2d, change_heading,
+    azumith2d_to, centroid2d, change_heading,
     coalition::Side,
     controller::{
-        ActionTyp, AiOption, AlarmState, AltType, Command, GroundOption, MissionPoint,
-        OrbitPattern, PointType, Task, TurnMethod, VehicleFormation,
+        ActionTyp, AiOption, AirEcmUsing, AirOption, AlarmState, AltType, Command, GroundOption,
+        MissionPoint, OrbitPattern, PointType, Task, TurnMethod, VehicleFormation,
     },
     env::miz::MizIndex,
     group::Group,
@@ -44,42 +45,71 @@ use enumflags2::BitFlags;
 use fxhash::FxHashSet;
 use log::error;
 use rand::{Rng, thread_rng};
+use serde_derive::{Deserialize, Serialize};
 use smallvec::{SmallVec, smallvec};
-use std::{cmp::max, f64, vec};
+use std::{
+    cmp::{max, min},
+    f64, vec,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithPos<T> {
     pub cfg: T,
     pub pos: Vector2,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithObj<T> {
     pub cfg: T,
     pub oid: ObjectiveId,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithFromTo<T> {
     pub cfg: T,
     pub from: ObjectiveId,
     pub to: ObjectiveId,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithPosAndGroup<T> {
     pub cfg: T,
     pub pos: Vector2,
     pub group: GroupId,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithPosAndPackages<T> {
+    pub cfg: T,
+    pub pos: Vector2,
+    pub packages: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithJtac<T> {
     pub cfg: T,
     pub jtac: JtId,
 }
 
-#[derive(Debug, Clone)]
+/// Values substituted for $ in action arguments
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgSubst {
+    pub pos: Option<Vector2>,
+    pub group: Option<GroupId>,
+}
+
+impl ArgSubst {
+    fn group(&self, s: &str) -> Result<GroupId> {
+        if s == "$" {
+            self.group
+                .ok_or_else(|| anyhow!("there is no group to substitute for $"))
+        } else {
+            Ok(s.parse()?)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ActionArgs {
     Tanker(WithPos<AiPlaneCfg>),
     Awacs(WithPos<AwacsCfg>),
@@ -93,6 +123,9 @@ pub enum ActionArgs {
     SeadWaypoint(WithPosAndGroup<()>),
     Drone(WithPos<DroneCfg>),
     DroneWaypoint(WithPosAndGroup<()>),
+    Recon(WithPos<ReconCfg>),
+    Jammer(WithPos<JammerCfg>),
+    JammerWaypoint(WithPosAndGroup<()>),
     Nuke(WithPos<NukeCfg>),
     TankerWaypoint(WithPosAndGroup<()>),
     AwacsWaypoint(WithPosAndGroup<()>),
@@ -101,8 +134,12 @@ pub enum ActionArgs {
     Deployable(WithPos<DeployableCfg>),
     LogisticsRepair(WithObj<AiPlaneCfg>),
     LogisticsTransfer(WithFromTo<AiPlaneCfg>),
+    Airdrop(WithPosAndPackages<AirdropCfg>),
     Move(WithPosAndGroup<MoveCfg>),
     Rtb(WithPosAndGroup<()>),
+    NavalPatrol(WithPos<NavalGroupCfg>),
+    ShoreBombardment(WithJtac<ShoreBombardmentCfg>),
+    AntiShip(WithPos<AiPlaneCfg>),
 }
 
 impl ActionArgs {
@@ -113,7 +150,31 @@ impl ActionArgs {
         side: Side,
         s: &str,
     ) -> Result<Self> {
-        fn get_key_pos(db: &mut Db, lua: MizLua, side: Side, key: &str) -> Result<Vector2> {
+        Self::parse_with(db, action, lua, side, &ArgSubst::default(), s)
+    }
+
+    /// Parse action arguments, the key or group id $ is replaced by
+    /// the corresponding value from subst
+    pub fn parse_with(
+        db: &mut Db,
+        action: &ActionKind,
+        lua: MizLua,
+        side: Side,
+        subst: &ArgSubst,
+        s: &str,
+    ) -> Result<Self> {
+        fn get_key_pos(
+            db: &mut Db,
+            lua: MizLua,
+            side: Side,
+            subst: &ArgSubst,
+            key: &str,
+        ) -> Result<Vector2> {
+            if key == "$" {
+                return subst
+                    .pos
+                    .ok_or_else(|| anyhow!("there is no position to substitute for $"));
+            }
             let mut found: SmallVec<[(MarkId, Vector2); 4]> = smallvec![];
             for mk in World::singleton(lua)?.get_mark_panels()? {
                 let mk = mk?;
@@ -177,6 +238,7 @@ impl ActionArgs {
             db: &mut Db,
             lua: MizLua,
             side: Side,
+            subst: &ArgSubst,
             c: T,
             s: &str,
         ) -> Result<WithPosAndGroup<T>> {
@@ -184,8 +246,8 @@ impl ActionArgs {
                 None => Err(anyhow!("expected <gid> <key>")),
                 Some((gid, key)) => Ok(WithPosAndGroup {
                     cfg: c,
-                    pos: get_key_pos(db, lua, side, key)?,
-                    group: gid.parse()?,
+                    pos: get_key_pos(db, lua, side, subst, key)?,
+                    group: subst.group(gid)?,
                 }),
             }
         }
@@ -193,6 +255,7 @@ impl ActionArgs {
             db: &mut Db,
             lua: MizLua,
             side: Side,
+            subst: &ArgSubst,
             c: T,
             s: &str,
         ) -> Result<WithPosAndGroup<T>> {
@@ -201,12 +264,19 @@ impl ActionArgs {
                 Some((gid, key)) => Ok(WithPosAndGroup {
                     cfg: c,
                     pos: get_closest_base(db, lua, side, key)?,
-                    group: gid.parse()?,
+                    group: subst.group(gid)?,
                 }),
             }
         }
-        fn pos<T>(db: &mut Db, lua: MizLua, side: Side, cfg: T, s: &str) -> Result<WithPos<T>> {
-            let pos = get_key_pos(db, lua, side, s)?;
+        fn pos<T>(
+            db: &mut Db,
+            lua: MizLua,
+            side: Side,
+            subst: &ArgSubst,
+            cfg: T,
+            s: &str,
+        ) -> Result<WithPos<T>> {
+            let pos = get_key_pos(db, lua, side, subst, s)?;
             Ok(WithPos { cfg, pos })
         }
         fn jtac<T>(cfg: T, s: &str) -> Result<WithJtac<T>> {
@@ -231,49 +301,112 @@ impl ActionArgs {
                 }),
             }
         }
-        match action.clone() {
-            ActionKind::Tanker(c) => Ok(Self::Tanker(pos(db, lua, side, c, s)?)),
-            ActionKind::Awacs(c) => Ok(Self::Awacs(pos(db, lua, side, c, s)?)),
-            ActionKind::Fighters(c) => Ok(Self::Fighters(pos(db, lua, side, c, s)?)),
-            ActionKind::FighersWaypoint => {
-                Ok(Self::FightersWaypoint(pos_group(db, lua, side, (), s)?))
-            }
-            ActionKind::Attackers(c) => Ok(Self::Attackers(pos(db, lua, side, c, s)?)),
-            ActionKind::AttackersWaypoint => {
-                Ok(Self::AttackersWaypoint(pos_group(db, lua, side, (), s)?))
+        fn airdrop(
+            db: &mut Db,
+            lua: MizLua,
+            side: Side,
+            subst: &ArgSubst,
+            cfg: AirdropCfg,
+            s: &str,
+        ) -> Result<WithPosAndPackages<AirdropCfg>> {
+            let (target, packages) = match s.rsplit_once(" ") {
+                Some((target, n)) if n.parse::<u8>().is_ok() => (target, n.parse::<u8>()?),
+                Some(_) | None => (s, 1),
+            };
+            if packages == 0 || packages > cfg.max_packages {
+                bail!("packages must be between 1 and {}", cfg.max_packages)
             }
-            ActionKind::Sead(c) => Ok(Self::Sead(pos(db, lua, side, c, s)?)),
+            let pos = match get_key_pos(db, lua, side, subst, target) {
+                Ok(pos) => pos,
+                Err(e) => {
+                    let oid = admin::get_airbase(db, target)
+                        .with_context(|| format_compact!("{e}, and it is not an objective"))?;
+                    let obj = objective!(db, oid)?;
+                    if obj.owner != side {
+                        bail!("{} is not a friendly objective", obj.name)
+                    }
+                    obj.zone.pos()
+                }
+            };
+            Ok(WithPosAndPackages { cfg, pos, packages })
+        }
+        match action.clone() {
+            ActionKind::Tanker(c) => Ok(Self::Tanker(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::Awacs(c) => Ok(Self::Awacs(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::Fighters(c) => Ok(Self::Fighters(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::FighersWaypoint => Ok(Self::FightersWaypoint(pos_group(
+                db,
+                lua,
+                side,
+                subst,
+                (),
+                s,
+            )?)),
+            ActionKind::Attackers(c) => Ok(Self::Attackers(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::AttackersWaypoint => Ok(Self::AttackersWaypoint(pos_group(
+                db,
+                lua,
+                side,
+                subst,
+                (),
+                s,
+            )?)),
+            ActionKind::Sead(c) => Ok(Self::Sead(pos(db, lua, side, subst, c, s)?)),
             ActionKind::SeadWaypoint => {
-                Ok(Self::SeadWaypoint(pos_group(db, lua, side, (), s)?))
+                Ok(Self::SeadWaypoint(pos_group(db, lua, side, subst, (), s)?))
+            }
+            ActionKind::Drone(c) => Ok(Self::Drone(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::DroneWaypoint => {
+                Ok(Self::DroneWaypoint(pos_group(db, lua, side, subst, (), s)?))
             }
-            ActionKind::Drone(c) => Ok(Self::Drone(pos(db, lua, side, c, s)?)),
-            ActionKind::DroneWaypoint => Ok(Self::DroneWaypoint(pos_group(db, lua, side, (), s)?)),
-            ActionKind::Nuke(c) => Ok(Self::Nuke(pos(db, lua, side, c, s)?)),
-            ActionKind::Paratrooper(c) => Ok(Self::Paratrooper(pos(db, lua, side, c, s)?)),
-            ActionKind::Deployable(c) => Ok(Self::Deployable(pos(db, lua, side, c, s)?)),
+            ActionKind::Recon(c) => Ok(Self::Recon(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::Jammer(c) => Ok(Self::Jammer(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::JammerWaypoint => Ok(Self::JammerWaypoint(pos_group(
+                db,
+                lua,
+                side,
+                subst,
+                (),
+                s,
+            )?)),
+            ActionKind::Nuke(c) => Ok(Self::Nuke(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::Paratrooper(c) => Ok(Self::Paratrooper(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::Deployable(c) => Ok(Self::Deployable(pos(db, lua, side, subst, c, s)?)),
             ActionKind::LogisticsRepair(c) => Ok(Self::LogisticsRepair(obj(db, c, s)?)),
             ActionKind::LogisticsTransfer(c) => Ok(Self::LogisticsTransfer(from_to(db, c, s)?)),
-            ActionKind::AwacsWaypoint => Ok(Self::AwacsWaypoint(pos_group(db, lua, side, (), s)?)),
-            ActionKind::TankerWaypoint => {
-                Ok(Self::TankerWaypoint(pos_group(db, lua, side, (), s)?))
+            ActionKind::Airdrop(c) => Ok(Self::Airdrop(airdrop(db, lua, side, subst, c, s)?)),
+            ActionKind::AwacsWaypoint => {
+                Ok(Self::AwacsWaypoint(pos_group(db, lua, side, subst, (), s)?))
             }
+            ActionKind::TankerWaypoint => Ok(Self::TankerWaypoint(pos_group(
+                db,
+                lua,
+                side,
+                subst,
+                (),
+                s,
+            )?)),
             ActionKind::Bomber(c) => Ok(Self::Bomber(jtac(c, s)?)),
-            ActionKind::Move(c) => Ok(Self::Move(pos_group(db, lua, side, c, s)?)),
+            ActionKind::Move(c) => Ok(Self::Move(pos_group(db, lua, side, subst, c, s)?)),
             ActionKind::CruiseMissileSpawn(c) => {
-                Ok(Self::CruiseMissileSpawn(pos(db, lua, side, c, s)?))
+                Ok(Self::CruiseMissileSpawn(pos(db, lua, side, subst, c, s)?))
             }
-            ActionKind::Rtb => Ok(Self::Rtb(pos_closest_base(db, lua, side, (), s)?)),
+            ActionKind::Rtb => Ok(Self::Rtb(pos_closest_base(db, lua, side, subst, (), s)?)),
             ActionKind::CruiseMissileWaypoint => Ok(Self::CruiseMissileWaypoint(pos_group(
                 db,
                 lua,
                 side,
+                subst,
                 (),
                 s,
             )?)),
+            ActionKind::NavalPatrol(c) => Ok(Self::NavalPatrol(pos(db, lua, side, subst, c, s)?)),
+            ActionKind::ShoreBombardment(c) => Ok(Self::ShoreBombardment(jtac(c, s)?)),
+            ActionKind::AntiShip(c) => Ok(Self::AntiShip(pos(db, lua, side, subst, c, s)?)),
         }
     }
 
-    fn pos(&self) -> Option<Vector2> {
+    pub(super) fn pos(&self) -> Option<Vector2> {
         match self {
             Self::Attackers(c) => Some(c.pos),
             Self::AttackersWaypoint(c) => Some(c.pos),
@@ -292,16 +425,23 @@ impl ActionArgs {
             Self::FightersWaypoint(c) => Some(c.pos),
             Self::LogisticsRepair(_) => None,
             Self::LogisticsTransfer(_) => None,
+            Self::Airdrop(c) => Some(c.pos),
             Self::Move(c) => Some(c.pos),
             Self::Nuke(c) => Some(c.pos),
             Self::Paratrooper(c) => Some(c.pos),
+            Self::Recon(c) => Some(c.pos),
+            Self::Jammer(c) => Some(c.pos),
+            Self::JammerWaypoint(c) => Some(c.pos),
             Self::Tanker(c) => Some(c.pos),
             Self::TankerWaypoint(c) => Some(c.pos),
+            Self::NavalPatrol(c) => Some(c.pos),
+            Self::ShoreBombardment(_) => None,
+            Self::AntiShip(c) => Some(c.pos),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionCmd {
     pub name: String,
     pub action: Action,
@@ -334,7 +474,7 @@ impl ActionCmd {
 
 // setup the awacs race track 90 degrees offset from the heading
 // to the nearest enemy objective
-fn racetrack_dist_and_heading(
+pub(super) fn racetrack_dist_and_heading(
     obj: &MapM<ObjectiveId, Objective>,
     pos: Vector2,
     enemy: Side,
@@ -354,22 +494,40 @@ fn group_position(lua: MizLua, name: &str) -> Result<Vector2> {
     Ok(Vector2::new(pos.x, pos.z))
 }
 
+fn naval_route<'lua>(pos: Vector2, dst: Vector2, speed: f64) -> Vec<MissionPoint<'lua>> {
+    let wpt = |pos: Vector2| MissionPoint {
+        action: None,
+        typ: PointType::TurningPoint,
+        airdrome_id: None,
+        helipad: None,
+        time_re_fu_ar: None,
+        link_unit: None,
+        pos: LuaVec2(pos),
+        alt: 0.,
+        alt_typ: Some(AltType::BARO),
+        speed,
+        eta: None,
+        speed_locked: None,
+        eta_locked: None,
+        name: None,
+        task: Box::new(Task::ComboTask(vec![])),
+    };
+    vec![wpt(pos), wpt(dst)]
+}
+
 impl Db {
-    pub fn start_action(
-        &mut self,
-        lua: MizLua,
-        perf: &mut PerfInner,
-        spctx: &SpawnCtx,
-        idx: &MizIndex,
-        jtacs: &Jtacs,
+    /// The number of points an action will cost. If args are not
+    /// known the base cost of a move is returned.
+    pub(super) fn action_cost(
+        &self,
         side: Side,
-        ucid: Option<Ucid>,
-        cmd: ActionCmd,
-    ) -> Result<()> {
-        let cost = match &cmd.action.kind {
+        action: &Action,
+        args: Option<&ActionArgs>,
+    ) -> Result<u32> {
+        Ok(match &action.kind {
             ActionKind::Nuke(nc) => {
                 let div = max(1, self.persisted.nukes_used * nc.cost_scale as u32);
-                max(1, cmd.action.cost / div)
+                max(1, action.cost / div)
             }
             ActionKind::Paratrooper(p) => {
                 let sq = self
@@ -378,7 +536,7 @@ impl Db {
                     .get(&side)
                     .and_then(|idx| idx.squads_by_name.get(&p.name))
                     .ok_or_else(|| anyhow!("missin squad"))?;
-                sq.cost + cmd.action.cost
+                sq.cost + action.cost
             }
             ActionKind::Deployable(d) => {
                 let dp = self
@@ -387,10 +545,10 @@ impl Db {
                     .get(&side)
                     .and_then(|idx| idx.deployables_by_name.get(&d.name))
                     .ok_or_else(|| anyhow!("missing deployable"))?;
-                dp.cost + cmd.action.cost
+                dp.cost + action.cost
             }
-            ActionKind::Move(_) => match &cmd.args {
-                ActionArgs::Move(a) => {
+            ActionKind::Move(_) => match args {
+                Some(ActionArgs::Move(a)) => {
                     let pos = self.group_center(&a.group)?;
                     let dist = na::distance(&pos.into(), &a.pos.into());
                     let group = group!(self, a.group)?;
@@ -404,15 +562,39 @@ impl Db {
                             }
                         }
                         DeployKind::Troop { .. } => a.cfg.troop,
+                        DeployKind::Action { spec, .. } => match &spec.kind {
+                            ActionKind::NavalPatrol(_) => {
+                                a.cfg.naval.ok_or_else(|| anyhow!("ships can't be moved"))?
+                            }
+                            _ => bail!("can't move this unit type"),
+                        },
                         _ => bail!("can't move this unit type"),
                     };
                     let steps = dist / (step as f64);
-                    steps as u32 * cmd.action.cost
+                    steps as u32 * action.cost
                 }
-                _ => cmd.action.cost,
+                _ => action.cost,
             },
-            _ => cmd.action.cost,
-        };
+            ActionKind::Airdrop(_) => match args {
+                Some(ActionArgs::Airdrop(a)) => a.packages as u32 * action.cost,
+                _ => action.cost,
+            },
+            _ => action.cost,
+        })
+    }
+
+    pub fn start_action(
+        &mut self,
+        lua: MizLua,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        jtacs: &Jtacs,
+        side: Side,
+        ucid: Option<Ucid>,
+        cmd: ActionCmd,
+    ) -> Result<Option<GroupId>> {
+        let cost = self.action_cost(side, &cmd.action, Some(&cmd.args))?;
         if let Some(ucid) = ucid.as_ref() {
             if !self.ephemeral.cfg.rules.actions.check(ucid) {
                 bail!("you are not authorized for actions")
@@ -449,6 +631,9 @@ impl Db {
                 bail!("{side} is out of {} actions", cmd.name)
             }
         }
+        let now = Utc::now();
+        let throttle = cmd.action.throttle.clone();
+        self.check_action_throttle(side, ucid.as_ref(), &cmd.name, &throttle, now)?;
         match cmd.action.geo_limit {
             ActionGeoLimit::Unlimited => (),
             ActionGeoLimit::NearFriendlyObjective { max } => {
@@ -536,7 +721,19 @@ impl Db {
             ActionArgs::LogisticsTransfer(args) => self
                 .ai_logistics_transfer(perf, spctx, idx, side, ucid.clone(), name, cmd.action, args)
                 .context("calling ai log transfer")?,
+            ActionArgs::Airdrop(args) => self
+                .airdrop(perf, spctx, idx, side, ucid.clone(), name, cmd.action, args)
+                .context("calling airdrop")?,
             ActionArgs::Nuke(args) => self.nuke(spctx, args).context("calling nuke")?,
+            ActionArgs::Recon(args) => self
+                .recon(perf, spctx, idx, side, ucid.clone(), name, cmd.action, args)
+                .context("calling recon")?,
+            ActionArgs::Jammer(args) => self
+                .jammer(perf, spctx, idx, side, ucid.clone(), name, cmd.action, args)
+                .context("calling jammer")?,
+            ActionArgs::JammerWaypoint(args) => self
+                .move_jammer(spctx, side, ucid.clone(), args)
+                .context("moving jammer")?,
             ActionArgs::Paratrooper(args) => self
                 .paratroops(perf, spctx, idx, side, ucid.clone(), name, cmd.action, args)
                 .context("calling paratroops")?,
@@ -552,6 +749,15 @@ impl Db {
                     .move_group(spctx, side, ucid, cmd.action.penalty.unwrap_or(0), args)
                     .context("moving unit")?,
             },
+            ActionArgs::NavalPatrol(args) => self
+                .naval_patrol(perf, spctx, idx, side, ucid.clone(), name, cmd.action, args)
+                .context("calling naval patrol")?,
+            ActionArgs::ShoreBombardment(args) => self
+                .shore_bombardment(lua, jtacs, side, args)
+                .context("calling shore bombardment")?,
+            ActionArgs::AntiShip(args) => self
+                .anti_ship(perf, spctx, idx, side, ucid.clone(), name, cmd.action, args)
+                .context("calling anti ship strike")?,
         };
         if let Some(ucid) = ucid.as_ref() {
             self.ephemeral.stat(Stat::Action {
@@ -572,7 +778,8 @@ impl Db {
             .or_default()
             .entry(cmd.name.clone())
             .or_default() += 1;
-        Ok(())
+        self.record_action_use(side, ucid.as_ref(), &cmd.name, &throttle, now);
+        Ok(gid)
     }
 
     pub(super) fn respawn_action(
@@ -591,9 +798,21 @@ impl Db {
             player,
             spec,
             time,
+            destination,
             ..
         } = &group.origin
         {
+            if let ActionKind::NavalPatrol(n) = &spec.kind {
+                if let Some(d) = n.duration
+                    && now - *time > Duration::hours(d as i64)
+                {
+                    return self.delete_group(&gid);
+                }
+                let mission = naval_route(spawn_pos, destination.unwrap_or(spawn_pos), n.speed);
+                self.ephemeral
+                    .spawn_group(perf, &self.persisted, idx, spctx, group, mission)?;
+                return Ok(());
+            }
             if let SpawnLoc::InAir { pos, .. } = loc {
                 let args = WithPosAndGroup {
                     pos: *pos,
@@ -627,6 +846,23 @@ impl Db {
                     )?;
                     return Ok(());
                 }
+                if let ActionKind::Jammer(ai) = &spec.kind {
+                    delete_expired!(ai.plane);
+                    let player = *player;
+                    let mission = self
+                        .jammer_mission(side, player, spawn_pos, args)
+                        .context("generating jammer mission")?;
+                    let group = group!(self, gid)?;
+                    self.ephemeral.spawn_group(
+                        perf,
+                        &self.persisted,
+                        idx,
+                        spctx,
+                        group,
+                        mission,
+                    )?;
+                    return Ok(());
+                }
                 if let ActionKind::Tanker(ai) = &spec.kind {
                     delete_expired!(ai);
                     let player = *player;
@@ -1121,17 +1357,14 @@ impl Db {
         penalty: u32,
         args: WithPosAndGroup<MoveCfg>,
     ) -> Result<Option<GroupId>> {
-        let pos = self.group_center(&args.group)?;
         let group = group_mut!(self, args.group)?;
         if group.side != side {
             bail!("can't move an enemy unit")
         }
+        let naval = group.tags.contains(UnitTag::Boat);
         self.ephemeral
             .groups_with_move_missions
             .insert(args.group, args.pos);
-        for uid in &group.units {
-            self.ephemeral.units_able_to_move.insert(*uid);
-        }
         if penalty > 0 {
             match &mut group.origin {
                 DeployKind::Deployed {
@@ -1144,13 +1377,62 @@ impl Db {
                 | DeployKind::Crate { .. }
                 | DeployKind::Objective { .. }
                 | DeployKind::ObjectiveDeprecated
+                | DeployKind::Offensive { .. }
+                | DeployKind::DownedPilot { .. }
                 | DeployKind::Troop { .. }
                 | DeployKind::Deployed { .. } => (),
             }
         }
+        if naval {
+            self.naval_move_mission(spctx, args.group, args.pos)?;
+        } else {
+            self.ground_move_mission(spctx, args.group, args.pos, VehicleFormation::OffRoad)?;
+        }
+        Ok(None)
+    }
+
+    /// Send a naval action group from where it is now to pos
+    fn naval_move_mission(&mut self, spctx: &SpawnCtx, gid: GroupId, dst: Vector2) -> Result<()> {
+        let pos = self.group_center(&gid)?;
+        let group = group_mut!(self, gid)?;
+        let speed = match &mut group.origin {
+            DeployKind::Action {
+                spec, destination, ..
+            } => match &spec.kind {
+                ActionKind::NavalPatrol(n) => {
+                    *destination = Some(dst);
+                    n.speed
+                }
+                _ => bail!("not a naval action group"),
+            },
+            _ => bail!("not a naval action group"),
+        };
+        let group = Group::get_by_name(spctx.lua(), &group.name).context("getting group")?;
+        let con = group.get_controller()?;
+        con.set_task(Task::Mission {
+            airborne: Some(false),
+            route: naval_route(pos, dst, speed),
+        })?;
+        Ok(())
+    }
+
+    /// Send a ground group from where it is now to pos, engaging
+    /// anything it meets along the way
+    pub(super) fn ground_move_mission(
+        &mut self,
+        spctx: &SpawnCtx,
+        gid: GroupId,
+        dst: Vector2,
+        formation: VehicleFormation,
+    ) -> Result<()> {
+        let pos = self.group_center(&gid)?;
+        let group = group!(self, gid)?;
+        for uid in &group.units {
+            self.ephemeral.units_able_to_move.insert(*uid);
+        }
         let land = Land::singleton(spctx.lua())?;
         let alt0 = land.get_height(LuaVec2(pos))?;
-        let alt1 = land.get_height(LuaVec2(args.pos))?;
+        let alt1 = land.get_height(LuaVec2(dst))?;
         let group = Group::get_by_name(spctx.lua(), &group.name).context("getting group")?;
         let con = group.get_controller()?;
         let att = Task::EngageTargets {
@@ -1172,7 +1454,7 @@ impl Db {
             airborne: Some(false),
             route: vec![
                 MissionPoint {
-                    action: Some(ActionTyp::Ground(VehicleFormation::OffRoad)),
+                    action: Some(ActionTyp::Ground(formation.clone())),
                     airdrome_id: None,
                     helipad: None,
                     typ: PointType::TurningPoint,
@@ -1197,13 +1479,13 @@ impl Db {
                     ])),
                 },
                 MissionPoint {
-                    action: Some(ActionTyp::Ground(VehicleFormation::OffRoad)),
+                    action: Some(ActionTyp::Ground(formation.clone())),
                     airdrome_id: None,
                     helipad: None,
                     typ: PointType::TurningPoint,
                     time_re_fu_ar: None,
                     link_unit: None,
-                    pos: LuaVec2(args.pos),
+                    pos: LuaVec2(dst),
                     alt: alt1,
                     alt_typ: Some(AltType::BARO),
                     speed: 20.,
@@ -1220,7 +1502,7 @@ impl Db {
                 },
             ],
         })?;
-        Ok(None)
+        Ok(())
     }
 
     fn rtb(&mut self, spctx: &SpawnCtx, mut args: WithPosAndGroup<()>) -> Result<Option<GroupId>> {
@@ -1558,8 +1840,17 @@ impl Db {
                     b.plane.altitude_typ.clone(),
                     b.plane.speed,
                 ),
+                ActionKind::Recon(r) => (
+                    *src,
+                    *tgt,
+                    r.plane.altitude,
+                    r.plane.altitude_typ.clone(),
+                    r.plane.speed,
+                ),
                 ActionKind::LogisticsRepair(p)
                 | ActionKind::LogisticsTransfer(p)
+                | ActionKind::AntiShip(p)
+                | ActionKind::Airdrop(AirdropCfg { plane: p, .. })
                 | ActionKind::Paratrooper(DeployableCfg {
                     name: _,
                     plane: Some(p),
@@ -1634,6 +1925,7 @@ impl Db {
                 rtb: _,
                 origin: _,
                 ammo: _,
+                packages: _,
             } => match &spec.kind {
                 ActionKind::Tanker(ai_plane_cfg) => (
                     ai_plane_cfg.altitude,
@@ -1660,6 +1952,16 @@ impl Db {
                     drone_cfg.plane.altitude_typ.clone(),
                     drone_cfg.plane.speed,
                 ),
+                ActionKind::Recon(recon_cfg) => (
+                    recon_cfg.plane.altitude,
+                    recon_cfg.plane.altitude_typ.clone(),
+                    recon_cfg.plane.speed,
+                ),
+                ActionKind::Jammer(jammer_cfg) => (
+                    jammer_cfg.plane.altitude,
+                    jammer_cfg.plane.altitude_typ.clone(),
+                    jammer_cfg.plane.speed,
+                ),
                 ActionKind::Sead(ai_plane_cfg) => (
                     ai_plane_cfg.altitude,
                     ai_plane_cfg.altitude_typ.clone(),
@@ -1675,6 +1977,11 @@ impl Db {
                     ai_plane_cfg.altitude_typ.clone(),
                     ai_plane_cfg.speed,
                 ),
+                ActionKind::AntiShip(ai_plane_cfg) => (
+                    ai_plane_cfg.altitude,
+                    ai_plane_cfg.altitude_typ.clone(),
+                    ai_plane_cfg.speed,
+                ),
                 _ => bail!("not a valid type"),
             },
             _ => bail!("not the right action kind"),
@@ -1751,6 +2058,222 @@ impl Db {
         )?))
     }
 
+    fn recon(
+        &mut self,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        side: Side,
+        ucid: Option<Ucid>,
+        name: String,
+        action: Action,
+        args: WithPos<ReconCfg>,
+    ) -> Result<Option<GroupId>> {
+        Ok(Some(self.add_and_spawn_ai_air(
+            perf,
+            spctx,
+            idx,
+            side,
+            &ucid,
+            name,
+            action,
+            0.,
+            &WithPos {
+                cfg: args.cfg.plane,
+                pos: args.pos,
+            },
+            Some(args.pos),
+            BitFlags::empty(),
+            |db, gid, _pos| db.ai_point_to_point_mission(gid, || Task::ComboTask(vec![])),
+        )?))
+    }
+
+    fn naval_patrol(
+        &mut self,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        side: Side,
+        ucid: Option<Ucid>,
+        name: String,
+        action: Action,
+        args: WithPos<NavalGroupCfg>,
+    ) -> Result<Option<GroupId>> {
+        let (_, heading, obj) =
+            Self::objective_near_point(&self.persisted.objectives, args.pos, |o| {
+                o.owner == side && o.kind.is_naval() && !o.captureable()
+            })
+            .ok_or_else(|| anyhow!("no friendly port or carrier to sail from"))?;
+        // start a little way out so we don't spawn on top of the carrier
+        let pos = obj.zone.pos() + pointing_towards2(heading) * 1_000.;
+        let oid = obj.id;
+        let sloc = SpawnLoc::AtPos {
+            pos,
+            offset_direction: pointing_towards2(heading),
+            group_heading: heading,
+        };
+        let origin = DeployKind::Action {
+            marks: FxHashSet::default(),
+            loc: sloc.clone(),
+            player: ucid,
+            name,
+            spec: action,
+            time: Utc::now(),
+            destination: Some(args.pos),
+            rtb: Some(pos),
+            origin: Some(oid),
+            ammo: 0,
+            packages: 0,
+        };
+        let gid = self
+            .add_group(
+                spctx,
+                idx,
+                side,
+                sloc,
+                &args.cfg.template,
+                origin,
+                UnitTag::Driveable.into(),
+            )
+            .context("creating group")?;
+        self.ephemeral
+            .spawn_group(
+                perf,
+                &self.persisted,
+                idx,
+                spctx,
+                group!(self, gid)?,
+                naval_route(pos, args.pos, args.cfg.speed),
+            )
+            .context("spawning group")?;
+        Ok(Some(gid))
+    }
+
+    fn shore_bombardment(
+        &mut self,
+        lua: MizLua,
+        jtacs: &Jtacs,
+        side: Side,
+        args: WithJtac<ShoreBombardmentCfg>,
+    ) -> Result<Option<GroupId>> {
+        let jt = jtacs.get(&args.jtac)?;
+        if jt.side() != side {
+            bail!("jtac {} is not on your side", args.jtac)
+        }
+        let tgt = match jt.target() {
+            Some(t) => Vector2::new(t.pos.x, t.pos.z),
+            None => bail!("jtac {} has no target", args.jtac),
+        };
+        let range2 = (args.cfg.range as f64).powi(2);
+        let mut closest: Option<(f64, GroupId)> = None;
+        for gid in &self.persisted.actions {
+            let group = group!(self, gid)?;
+            if group.side != side {
+                continue;
+            }
+            if let DeployKind::Action { spec, .. } = &group.origin
+                && let ActionKind::NavalPatrol(_) = &spec.kind
+            {
+                let pos = self.group_center(gid)?;
+                let dist = na::distance_squared(&pos.into(), &tgt.into());
+                if dist <= range2 && closest.map(|(d, _)| dist < d).unwrap_or(true) {
+                    closest = Some((dist, *gid));
+                }
+            }
+        }
+        let (_, gid) = closest.ok_or_else(|| {
+            anyhow!(
+                "no friendly naval group is within {} meters of the target",
+                args.cfg.range
+            )
+        })?;
+        let group = group!(self, gid)?;
+        let con = Group::get_by_name(lua, &group.name)
+            .context("getting group")?
+            .get_controller()?;
+        // push rather than set so the ship resumes its route afterwards
+        con.push_task(Task::FireAtPoint {
+            point: LuaVec2(tgt),
+            radius: Some(args.cfg.radius as f64),
+            expend_qty: Some(args.cfg.rounds as i64),
+            weapon_type: None,
+            altitude: None,
+            altitude_type: None,
+        })?;
+        let msg = format_compact!("{} is firing on jtac {}'s target", group.name, args.jtac);
+        self.ephemeral.msgs().panel_to_side(10, false, side, msg);
+        Ok(None)
+    }
+
+    fn anti_ship(
+        &mut self,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        side: Side,
+        ucid: Option<Ucid>,
+        name: String,
+        action: Action,
+        args: WithPos<AiPlaneCfg>,
+    ) -> Result<Option<GroupId>> {
+        Ok(Some(self.add_and_spawn_ai_air(
+            perf,
+            spctx,
+            idx,
+            side,
+            &ucid,
+            name,
+            action,
+            0.,
+            &args,
+            Some(args.pos),
+            BitFlags::empty(),
+            |db, gid, _pos| {
+                db.ai_point_to_point_mission(gid, || Task::EngageTargets {
+                    target_types: vec![Attribute::Ships],
+                    max_dist: Some(30_000.),
+                    priority: None,
+                })
+            },
+        )?))
+    }
+
+    fn airdrop(
+        &mut self,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        side: Side,
+        ucid: Option<Ucid>,
+        name: String,
+        action: Action,
+        args: WithPosAndPackages<AirdropCfg>,
+    ) -> Result<Option<GroupId>> {
+        let plane = WithPos {
+            cfg: args.cfg.plane.clone(),
+            pos: args.pos,
+        };
+        let gid = self.add_and_spawn_ai_air(
+            perf,
+            spctx,
+            idx,
+            side,
+            &ucid,
+            name,
+            action,
+            0.,
+            &plane,
+            Some(args.pos),
+            BitFlags::empty(),
+            |db, gid, _pos| db.ai_point_to_point_mission(gid, || Task::ComboTask(vec![])),
+        )?;
+        if let DeployKind::Action { packages, .. } = &mut group_mut!(self, gid)?.origin {
+            *packages = args.packages;
+        }
+        self.ephemeral.dirty();
+        Ok(Some(gid))
+    }
+
     fn add_and_spawn_ai_air<'lua>(
         &mut self,
         perf: &mut PerfInner,
@@ -1801,6 +2324,7 @@ impl Db {
             rtb: Some(pos),
             origin: Some(obj.id),
             ammo: 0,
+            packages: 0,
         };
         let gid = self
             .add_group(
@@ -1942,6 +2466,91 @@ impl Db {
         )?))
     }
 
+    fn jammer_mission<'lua>(
+        &mut self,
+        side: Side,
+        ucid: Option<Ucid>,
+        spawn_pos: Vector2,
+        args: WithPosAndGroup<()>,
+    ) -> Result<Vec<MissionPoint<'lua>>> {
+        let init_task = Task::ComboTask(vec![
+            Task::WrappedOption(AiOption::Air(AirOption::EcmUsing(AirEcmUsing::AlwaysUse))),
+            Task::WrappedCommand(Command::SetUnlimitedFuel(true)),
+        ]);
+        self.ai_loiter_point_mission(
+            side,
+            ucid,
+            args,
+            OrbitPattern::RaceTrack,
+            spawn_pos,
+            |k| match k {
+                ActionKind::Jammer(_) => true,
+                _ => false,
+            },
+            move || init_task.clone(),
+            move || vec![],
+        )
+    }
+
+    fn move_jammer<'lua>(
+        &mut self,
+        spctx: &SpawnCtx<'lua>,
+        side: Side,
+        ucid: Option<Ucid>,
+        args: WithPosAndGroup<()>,
+    ) -> Result<Option<GroupId>> {
+        let gid = args.group;
+        let group = group!(self, gid)?;
+        let pos = group_position(spctx.lua(), &group.name)?;
+        let mission = self
+            .jammer_mission(side, ucid, pos, args)
+            .context("generating jammer mission")?;
+        self.set_ai_mission(spctx, gid, mission)
+            .context("setting ai mission")?;
+        Ok(None)
+    }
+
+    fn jammer(
+        &mut self,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        side: Side,
+        ucid: Option<Ucid>,
+        name: String,
+        action: Action,
+        args: WithPos<JammerCfg>,
+    ) -> Result<Option<GroupId>> {
+        Ok(Some(self.add_and_spawn_ai_air(
+            perf,
+            spctx,
+            idx,
+            side,
+            &ucid,
+            name,
+            action,
+            0.,
+            &WithPos {
+                cfg: args.cfg.plane,
+                pos: args.pos,
+            },
+            None,
+            BitFlags::empty(),
+            move |db, gid, pos| {
+                db.jammer_mission(
+                    side,
+                    ucid,
+                    pos,
+                    WithPosAndGroup {
+                        cfg: (),
+                        pos: args.pos,
+                        group: gid,
+                    },
+                )
+            },
+        )?))
+    }
+
     fn ai_loiter_point_mission<'lua>(
         &mut self,
         side: Side,
@@ -1980,6 +2589,7 @@ impl Db {
                     ActionKind::Awacs(AwacsCfg { plane: a, .. })
                     | ActionKind::Tanker(a)
                     | ActionKind::Drone(DroneCfg { plane: a, .. })
+                    | ActionKind::Jammer(JammerCfg { plane: a, .. })
                     | ActionKind::CruiseMissileSpawn(a)
                     | ActionKind::Fighters(a)
                     | ActionKind::Attackers(a)
@@ -2023,6 +2633,7 @@ impl Db {
                     | ActionKind::SeadWaypoint
                     | ActionKind::AwacsWaypoint
                     | ActionKind::DroneWaypoint
+                    | ActionKind::JammerWaypoint
                     | ActionKind::CruiseMissileWaypoint
                     | ActionKind::TankerWaypoint
                     | ActionKind::FighersWaypoint
@@ -2031,15 +2642,22 @@ impl Db {
                     | ActionKind::Deployable(_)
                     | ActionKind::Paratrooper(_)
                     | ActionKind::Bomber(_)
+                    | ActionKind::Recon(_)
                     | ActionKind::Nuke(_)
                     | ActionKind::LogisticsRepair(_)
-                    | ActionKind::LogisticsTransfer(_) => bail!("not a race tracker"),
+                    | ActionKind::LogisticsTransfer(_)
+                    | ActionKind::Airdrop(_)
+                    | ActionKind::NavalPatrol(_)
+                    | ActionKind::ShoreBombardment(_)
+                    | ActionKind::AntiShip(_) => bail!("not a race tracker"),
                 }
             }
             DeployKind::Crate { .. }
             | DeployKind::Deployed { .. }
             | DeployKind::Objective { .. }
             | DeployKind::ObjectiveDeprecated
+            | DeployKind::Offensive { .. }
+            | DeployKind::DownedPilot { .. }
             | DeployKind::Troop { .. } => bail!("not a race tracker"),
         };
         let responsible = player
@@ -2296,6 +2914,7 @@ impl Db {
                 LimitEnforceTyp::DeleteOldest => match oldest {
                     Some(Oldest::Group(gid)) => self.delete_group(&gid)?,
                     Some(Oldest::Objective(oid)) => self.delete_objective(&oid)?,
+                    Some(Oldest::Minefield(id)) => self.delete_minefield(id),
                     None => (),
                 },
             }
@@ -2341,9 +2960,99 @@ impl Db {
                 });
                 Ok(())
             }
+            DeployableKind::Minefield(mcfg) => {
+                // lay the belt facing the nearest enemy objective
+                let heading = self
+                    .persisted
+                    .objectives
+                    .into_iter()
+                    .filter(|(_, obj)| obj.owner == side.opposite())
+                    .map(|(_, obj)| obj.zone.pos())
+                    .min_by(|p0, p1| {
+                        na::distance_squared(&pos.into(), &(*p0).into())
+                            .total_cmp(&na::distance_squared(&pos.into(), &(*p1).into()))
+                    })
+                    .map(|opos| azumith2d_to(pos, opos))
+                    .unwrap_or(0.);
+                self.add_minefield(side, ucid, &spec, *mcfg, pos, heading);
+                Ok(())
+            }
         }
     }
 
+    /// Drop the packages of an airdrop. If the drop point is at a
+    /// friendly objective the packages go straight into its warehouse,
+    /// otherwise they land as supply transfer crates from the origin.
+    fn airdrop_to_point(
+        &mut self,
+        lua: MizLua,
+        idx: &MizIndex,
+        pos: Vector2,
+        cfg: &AirdropCfg,
+        packages: u8,
+        origin: Option<ObjectiveId>,
+        ucid: Option<Ucid>,
+        side: Side,
+    ) -> Result<()> {
+        let packages = max(1, packages);
+        let near = Self::objective_near_point(&self.persisted.objectives, pos, |o| {
+            o.owner == side
+        })
+        .filter(|(dist, _, _)| *dist <= 5_000.)
+        .map(|(_, _, obj)| (obj.id, obj.name.clone()));
+        if let Some((oid, name)) = near {
+            let amount = min(100, packages as u32 * cfg.supply as u32) as u8;
+            self.airdrop_inventory(lua, oid, amount)
+                .with_context(|| format_compact!("resupplying {name}"))?;
+            let msg = format_compact!("airdrop delivered {packages} supply packages to {name}");
+            self.ephemeral.msgs().panel_to_side(10, false, side, msg);
+            return Ok(());
+        }
+        let ucid = ucid.ok_or_else(|| anyhow!("only player airdrops can leave crates"))?;
+        let origin = origin.ok_or_else(|| anyhow!("airdrop has no origin objective"))?;
+        let crate_cfg = self
+            .ephemeral
+            .cfg
+            .warehouse
+            .as_ref()
+            .and_then(|whcfg| whcfg.supply_transfer_crate.get(&side))
+            .ok_or_else(|| anyhow!("no supply transfer crate for {side}"))?
+            .clone();
+        let template = self
+            .ephemeral
+            .cfg
+            .crate_template
+            .get(&side)
+            .ok_or_else(|| anyhow!("missing crate template for {side}"))?
+            .clone();
+        let spctx = SpawnCtx::new(lua)?;
+        for i in 0..packages {
+            let spawnpos = SpawnLoc::AtPos {
+                pos: pos + Vector2::new(15. * i as f64, 0.),
+                offset_direction: Vector2::new(1., 0.),
+                group_heading: 0.,
+            };
+            let dk = DeployKind::Crate {
+                origin,
+                player: ucid,
+                spec: crate_cfg.clone(),
+            };
+            self.add_and_queue_group(
+                &spctx,
+                idx,
+                side,
+                spawnpos,
+                &template,
+                dk,
+                BitFlags::empty(),
+                None,
+            )?;
+        }
+        let msg = format_compact!("airdrop landed {packages} supply crates");
+        self.ephemeral.panel_to_player(&self.persisted, 10, &ucid, msg);
+        Ok(())
+    }
+
     fn paratroops_to_point(
         &mut self,
         lua: MizLua,
@@ -2426,6 +3135,9 @@ impl Db {
         let mut to_deploy: SmallVec<[(Vector2, String, Side, Ucid); 2]> = smallvec![];
         let mut to_paratroop: SmallVec<[(Vector2, String, Side, Ucid, ObjectiveId); 2]> =
             smallvec![];
+        let mut to_airdrop: SmallVec<
+            [(Vector2, AirdropCfg, u8, Option<ObjectiveId>, Option<Ucid>, Side); 2],
+        > = smallvec![];
         macro_rules! at_dest {
             ($group:expr, $dest:expr, $radius:expr) => {{
                 let r2 = f64::powi($radius, 2);
@@ -2455,6 +3167,7 @@ impl Db {
                 rtb,
                 player,
                 origin,
+                packages,
                 ..
             } = &mut group.origin
             {
@@ -2464,6 +3177,7 @@ impl Db {
                     | ActionKind::Attackers(ai)
                     | ActionKind::CruiseMissileSpawn(ai)
                     | ActionKind::Drone(DroneCfg { plane: ai, .. })
+                    | ActionKind::Jammer(JammerCfg { plane: ai, .. })
                     | ActionKind::Tanker(ai) => {
                         if let Some(d) = ai.duration {
                             if now - *time > Duration::hours(d as i64) {
@@ -2496,6 +3210,27 @@ impl Db {
                             }
                         }
                     }
+                    ActionKind::NavalPatrol(n) => {
+                        if let Some(d) = n.duration {
+                            if now - *time > Duration::hours(d as i64) {
+                                to_delete.push(*gid);
+                            }
+                        }
+                    }
+                    ActionKind::Recon(_) | ActionKind::AntiShip(_) => {
+                        if let Some(target) = *destination {
+                            if at_dest!(group, target, 2_000.) {
+                                destination.take();
+                            }
+                        }
+                        if destination.is_none() {
+                            if let Some(target) = *rtb {
+                                if at_dest!(group, target, 10_000.) {
+                                    to_delete.push(*gid);
+                                }
+                            }
+                        }
+                    }
                     ActionKind::Rtb => {
                         if let Some(target) = *rtb {
                             if at_dest!(group, target, 10_000.) {
@@ -2543,6 +3278,28 @@ impl Db {
                             }
                         }
                     }
+                    ActionKind::Airdrop(a) => {
+                        if let Some(target) = *destination {
+                            if at_dest!(group, target, 800.) {
+                                destination.take();
+                                to_airdrop.push((
+                                    target,
+                                    a.clone(),
+                                    *packages,
+                                    *origin,
+                                    *player,
+                                    group.side,
+                                ));
+                            }
+                        }
+                        if destination.is_none() {
+                            if let Some(target) = *rtb {
+                                if at_dest!(group, target, 10_000.) {
+                                    to_delete.push(*gid);
+                                }
+                            }
+                        }
+                    }
                     ActionKind::Paratrooper(t) => {
                         if let Some(target) = *destination {
                             if at_dest!(group, target, 800.) {
@@ -2631,6 +3388,8 @@ impl Db {
                     | ActionKind::CruiseMissileWaypoint
                     | ActionKind::TankerWaypoint
                     | ActionKind::DroneWaypoint
+                    | ActionKind::JammerWaypoint
+                    | ActionKind::ShoreBombardment(_)
                     | ActionKind::Nuke(_) => {
                         bail!("should not be a group")
                     }
@@ -2690,6 +3449,17 @@ impl Db {
                 )
             }
         }
+        for (dst, cfg, packages, origin, ucid, side) in to_airdrop {
+            if let Err(e) = self.airdrop_to_point(lua, idx, dst, &cfg, packages, origin, ucid, side)
+            {
+                self.ephemeral.msgs().panel_to_side(
+                    10,
+                    false,
+                    side,
+                    format_compact!("airdrop mission failed {e:?}"),
+                );
+            }
+        }
         Ok(())
     }
 }
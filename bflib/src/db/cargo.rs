@@ -23,7 +23,9 @@ use crate::{
 };
 use anyhow::{Result, anyhow, bail};
 use bfprotocols::{
-    cfg::{CargoConfig, Crate, Deployable, DeployableKind, LimitEnforceTyp, Troop, Vehicle},
+    cfg::{
+        CargoConfig, Crate, Deployable, DeployableKind, LifeType, LimitEnforceTyp, Troop, Vehicle,
+    },
     db::{
         group::GroupId,
         objective::{ObjectiveId, ObjectiveKind},
@@ -71,6 +73,7 @@ pub enum Unpakistan {
 pub enum Oldest {
     Group(GroupId),
     Objective(ObjectiveId),
+    Minefield(u64),
 }
 
 impl fmt::Display for Unpakistan {
@@ -98,15 +101,25 @@ pub struct InternalTroop {
     pub troop: Troop,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RescuedPilot {
+    pub player: Ucid,
+    pub name: String,
+    pub life_type: LifeType,
+    pub weight: u32,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Cargo {
     pub troops: SmallVec<[InternalTroop; 2]>,
     pub crates: SmallVec<[(ObjectiveId, Crate); 1]>,
+    #[serde(default)]
+    pub pilots: SmallVec<[RescuedPilot; 1]>,
 }
 
 impl Cargo {
     pub fn num_troops(&self) -> usize {
-        self.troops.len()
+        self.troops.len() + self.pilots.len()
     }
 
     pub fn num_crates(&self) -> usize {
@@ -122,9 +135,11 @@ impl Cargo {
             .crates
             .iter()
             .fold(0, |acc, (_, cr)| acc + cr.weight as i64);
-        self.troops
+        let tr = self
+            .troops
             .iter()
-            .fold(cr, |acc, it| acc + it.troop.weight as i64)
+            .fold(cr, |acc, it| acc + it.troop.weight as i64);
+        self.pilots.iter().fold(tr, |acc, p| acc + p.weight as i64)
     }
 }
 
@@ -295,6 +310,8 @@ impl Db {
                 | DeployKind::Troop { .. }
                 | DeployKind::Objective { .. }
                 | DeployKind::ObjectiveDeprecated
+                | DeployKind::Offensive { .. }
+                | DeployKind::DownedPilot { .. }
                 | DeployKind::Action { .. } => {
                     bail!("group {:?} is listed in crates but isn't a crate", gid)
                 }
@@ -395,6 +412,16 @@ impl Db {
                 }
             }
         }
+        for (id, mf) in &self.persisted.minefields {
+            if let Some(d_name) = mf.spec.path.last() {
+                if mf.side == side && d_name.as_str() == name {
+                    if oldest.is_none() {
+                        oldest = Some(Oldest::Minefield(*id));
+                    }
+                    n += 1;
+                }
+            }
+        }
         Ok((n, oldest))
     }
 
@@ -585,6 +612,8 @@ impl Db {
                             | DeployKind::Crate { .. }
                             | DeployKind::Objective { .. }
                             | DeployKind::ObjectiveDeprecated
+                            | DeployKind::Offensive { .. }
+                            | DeployKind::DownedPilot { .. }
                             | DeployKind::Troop { .. }
                             | DeployKind::Action { .. } => (),
                         }
@@ -723,6 +752,7 @@ impl Db {
                     LimitEnforceTyp::DeleteOldest => match oldest {
                         Some(Oldest::Group(gid)) => db.delete_group(&gid)?,
                         Some(Oldest::Objective(oid)) => db.delete_objective(&oid)?,
+                        Some(Oldest::Minefield(id)) => db.delete_minefield(id),
                         None => (),
                     },
                 }
@@ -808,6 +838,7 @@ impl Db {
                     {
                         self.adjust_points(&st.ucid, amount as i32, "for supply transfer");
                     }
+                    self.fill_supply_request(&st.ucid, to)?;
                     return Ok(Unpakistan::TransferedSupplies(
                         objective!(self, from)?.name.clone(),
                         objective!(self, to)?.name.clone(),
@@ -914,6 +945,27 @@ impl Db {
                                 }
                                 return Ok(Unpakistan::Unpacked(dep));
                             }
+                            DeployableKind::Minefield(mcfg) => {
+                                let pos = self.ephemeral.slot_instance_pos(lua, slot)?;
+                                for cr in have.values().flat_map(|c| c.iter()) {
+                                    self.delete_group(&cr.group)?
+                                }
+                                self.add_minefield(
+                                    st.side,
+                                    st.ucid,
+                                    &spec,
+                                    *mcfg,
+                                    centroid,
+                                    azumith3d(pos.x.0),
+                                );
+                                self.charge_for_item(
+                                    &st.ucid,
+                                    from_obj,
+                                    spec.cost,
+                                    &format_compact!("for {dep} unpack"),
+                                );
+                                return Ok(Unpakistan::Unpacked(dep));
+                            }
                         },
                     }
                 }
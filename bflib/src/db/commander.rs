@@ -0,0 +1,382 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{
+    Db,
+    actions::{ActionArgs, ActionCmd, WithFromTo, WithObj, WithPos},
+};
+use crate::{ewr::Ewr, jtac::Jtacs, spawnctx::SpawnCtx};
+use bfprotocols::{
+    cfg::{ActionKind, AiCommanderCfg, UnitTag},
+    db::objective::ObjectiveId,
+    perf::PerfInner,
+    stats::Stat,
+};
+use chrono::{Duration, prelude::*};
+use compact_str::format_compact;
+use dcso3::{MizLua, String, Vector2, coalition::Side, env::miz::MizIndex};
+use enumflags2::BitFlags;
+use fxhash::FxHashMap;
+use log::{debug, info};
+use smallvec::{SmallVec, smallvec};
+use std::{cmp::min, sync::Arc};
+
+/// The runtime state of the per side AI commanders. The budgets
+/// themselves are persisted.
+#[derive(Debug, Clone, Default)]
+pub(super) struct AiCommander {
+    last_decision: FxHashMap<Side, DateTime<Utc>>,
+    recent_targets: FxHashMap<(Side, ObjectiveId), DateTime<Utc>>,
+}
+
+struct Candidate {
+    name: String,
+    args: ActionArgs,
+    target: ObjectiveId,
+    reason: String,
+}
+
+/// The fraction of its full strength the commander acts with given
+/// the number of human players on its side
+fn strength(cfg: &AiCommanderCfg, players: u32) -> f64 {
+    if players <= cfg.min_players {
+        1.
+    } else if players >= cfg.max_players {
+        0.
+    } else {
+        (cfg.max_players - players) as f64 / (cfg.max_players - cfg.min_players) as f64
+    }
+}
+
+impl Db {
+    fn human_players(&self, side: Side) -> u32 {
+        self.ephemeral
+            .players_by_slot
+            .values()
+            .filter_map(|ucid| self.persisted.players.get(ucid))
+            .filter(|player| player.side == side)
+            .count() as u32
+    }
+
+    fn ai_action(&self, side: Side, name: &Option<String>) -> Option<(String, ActionKind)> {
+        let name = name.as_ref()?;
+        let action = self.ephemeral.cfg.actions.get(&side)?.get(name)?;
+        Some((name.clone(), action.kind.clone()))
+    }
+
+    fn ai_candidates(
+        &self,
+        cfg: &AiCommanderCfg,
+        side: Side,
+        jtacs: &Jtacs,
+        ewr: &Ewr,
+        now: DateTime<Utc>,
+    ) -> SmallVec<[Candidate; 8]> {
+        let recent = Duration::seconds(cfg.decision_interval as i64 * 3);
+        let fresh = |oid: &ObjectiveId| {
+            self.ephemeral
+                .ai_commander
+                .recent_targets
+                .get(&(side, *oid))
+                .map(|ts| now - *ts >= recent)
+                .unwrap_or(true)
+        };
+        let owned = || {
+            self.persisted
+                .objectives
+                .into_iter()
+                .filter(move |(_, obj)| obj.owner == side)
+        };
+        let mut candidates: SmallVec<[Candidate; 8]> = smallvec![];
+        // defend the objective with the most enemy aircraft nearby
+        if let Some((name, ActionKind::Fighters(acfg))) = self.ai_action(side, &cfg.cap) {
+            let threat = owned()
+                .filter(|(oid, _)| fresh(oid))
+                .map(|(oid, obj)| {
+                    let n =
+                        ewr.enemy_tracks_near(now, side, obj.zone.pos(), cfg.threat_range as f64);
+                    (*oid, obj, n)
+                })
+                .filter(|(_, _, n)| *n > 0)
+                .max_by_key(|(_, _, n)| *n);
+            if let Some((oid, obj, n)) = threat {
+                candidates.push(Candidate {
+                    name,
+                    args: ActionArgs::Fighters(WithPos {
+                        cfg: acfg,
+                        pos: obj.zone.pos(),
+                    }),
+                    target: oid,
+                    reason: format_compact!("{n} enemy aircraft near {}", obj.name).into(),
+                })
+            }
+        }
+        // repair the most damaged logistics
+        if let Some((name, ActionKind::LogisticsRepair(acfg))) =
+            self.ai_action(side, &cfg.logistics_repair)
+        {
+            let damaged = owned()
+                .filter(|(oid, obj)| {
+                    fresh(oid) && !obj.is_farp() && obj.logi < cfg.repair_threshold
+                })
+                .min_by_key(|(_, obj)| obj.logi);
+            if let Some((oid, obj)) = damaged {
+                candidates.push(Candidate {
+                    name,
+                    args: ActionArgs::LogisticsRepair(WithObj {
+                        cfg: acfg,
+                        oid: *oid,
+                    }),
+                    target: *oid,
+                    reason: format_compact!("{} logistics at {}%", obj.name, obj.logi).into(),
+                })
+            }
+        }
+        // strike a jtac target, sead for air defenses
+        let sead = self.ai_action(side, &cfg.sead);
+        let attack = self.ai_action(side, &cfg.attack);
+        let air_defense = UnitTag::SAM | UnitTag::AAA | UnitTag::TrackRadar | UnitTag::SearchRadar;
+        for jtac in jtacs.jtacs().filter(|j| j.side() == side) {
+            let target = match jtac.target() {
+                Some(target) => target,
+                None => continue,
+            };
+            let oid = jtac.location().oid;
+            if !fresh(&oid) {
+                continue;
+            }
+            let tags = self
+                .ephemeral
+                .cfg
+                .unit_classification
+                .get(&target.typ)
+                .map(|t| t.0)
+                .unwrap_or(BitFlags::empty());
+            let pos = Vector2::new(target.pos.x, target.pos.z);
+            let args = match (&sead, &attack) {
+                (Some((name, ActionKind::Sead(acfg))), _) if tags.intersects(air_defense) => {
+                    Some((
+                        name.clone(),
+                        ActionArgs::Sead(WithPos {
+                            cfg: acfg.clone(),
+                            pos,
+                        }),
+                    ))
+                }
+                (_, Some((name, ActionKind::Attackers(acfg)))) => Some((
+                    name.clone(),
+                    ActionArgs::Attackers(WithPos {
+                        cfg: acfg.clone(),
+                        pos,
+                    }),
+                )),
+                (_, _) => None,
+            };
+            if let Some((name, args)) = args {
+                candidates.push(Candidate {
+                    name,
+                    args,
+                    target: oid,
+                    reason: format_compact!("jtac {} spotted {}", jtac.gid(), target.typ).into(),
+                });
+                break;
+            }
+        }
+        // resupply the objective that is lowest on supply or fuel from
+        // the nearest hub
+        if let Some((name, ActionKind::LogisticsTransfer(acfg))) =
+            self.ai_action(side, &cfg.logistics_transfer)
+        {
+            let short = owned()
+                .filter(|(oid, obj)| {
+                    fresh(oid)
+                        && !obj.is_farp()
+                        && !obj.kind.is_hub()
+                        && min(obj.supply, obj.fuel) < cfg.supply_threshold
+                })
+                .min_by_key(|(_, obj)| min(obj.supply, obj.fuel));
+            if let Some((oid, obj)) = short {
+                let pos = obj.zone.pos();
+                let hub = self
+                    .persisted
+                    .logistics_hubs
+                    .into_iter()
+                    .filter_map(|hid| self.persisted.objectives.get(hid).map(|h| (hid, h)))
+                    .filter(|(_, hub)| hub.owner == side)
+                    .min_by(|(_, h0), (_, h1)| {
+                        let d0 = na::distance_squared(&pos.into(), &h0.zone.pos().into());
+                        let d1 = na::distance_squared(&pos.into(), &h1.zone.pos().into());
+                        d0.total_cmp(&d1)
+                    });
+                if let Some((hid, hub)) = hub {
+                    candidates.push(Candidate {
+                        name,
+                        args: ActionArgs::LogisticsTransfer(WithFromTo {
+                            cfg: acfg,
+                            from: *hid,
+                            to: *oid,
+                        }),
+                        target: *oid,
+                        reason: format_compact!(
+                            "{} supply {}% fuel {}%, resupply from {}",
+                            obj.name,
+                            obj.supply,
+                            obj.fuel,
+                            hub.name
+                        )
+                        .into(),
+                    })
+                }
+            }
+        }
+        // keep a drone over the front
+        if let Some((name, ActionKind::Drone(acfg))) = self.ai_action(side, &cfg.drone) {
+            let front = owned()
+                .filter(|(oid, obj)| fresh(oid) && !obj.is_farp() && self.is_front_line(oid))
+                .next();
+            if let Some((oid, obj)) = front {
+                candidates.push(Candidate {
+                    name,
+                    args: ActionArgs::Drone(WithPos {
+                        cfg: acfg,
+                        pos: obj.zone.pos(),
+                    }),
+                    target: *oid,
+                    reason: format_compact!("reconnaissance over {}", obj.name).into(),
+                })
+            }
+        }
+        candidates
+    }
+
+    fn run_ai_commander(
+        &mut self,
+        lua: MizLua,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        jtacs: &Jtacs,
+        ewr: &Ewr,
+        side: Side,
+        cfg: &AiCommanderCfg,
+        now: DateTime<Utc>,
+    ) {
+        let last = match self.ephemeral.ai_commander.last_decision.get(&side) {
+            Some(last) => *last,
+            None => {
+                self.ephemeral.ai_commander.last_decision.insert(side, now);
+                return;
+            }
+        };
+        let elapsed = now - last;
+        if elapsed < Duration::seconds(cfg.decision_interval as i64) {
+            return;
+        }
+        self.ephemeral.ai_commander.last_decision.insert(side, now);
+        let strength = strength(cfg, self.human_players(side));
+        let earned = cfg.points_per_hour as f64 * strength * elapsed.num_seconds() as f64 / 3600.;
+        let budget = self.persisted.ai_budget.get_or_default_cow(side);
+        *budget = min(cfg.max_budget, *budget + earned.round() as u32);
+        let budget = *budget;
+        self.ephemeral.dirty();
+        if strength <= 0. {
+            return;
+        }
+        for c in self.ai_candidates(cfg, side, jtacs, ewr, now) {
+            let action = match self
+                .ephemeral
+                .cfg
+                .actions
+                .get(&side)
+                .and_then(|actions| actions.get(&c.name))
+            {
+                Some(action) => action.clone(),
+                None => continue,
+            };
+            let cost = match self.action_cost(side, &action, Some(&c.args)) {
+                Ok(cost) => cost,
+                Err(e) => {
+                    debug!("{side} ai commander could not price {}: {e:?}", c.name);
+                    continue;
+                }
+            };
+            if cost > budget {
+                debug!(
+                    "{side} ai commander can't afford {} ({}), {cost} > {budget}",
+                    c.name, c.reason
+                );
+                continue;
+            }
+            let cmd = ActionCmd {
+                name: c.name.clone(),
+                action,
+                args: c.args,
+            };
+            match self.start_action(lua, perf, spctx, idx, jtacs, side, None, cmd) {
+                Err(e) => debug!("{side} ai commander could not start {}: {e:?}", c.name),
+                Ok(_) => {
+                    info!(
+                        "{side} ai commander started {} for {} points: {}",
+                        c.name, cost, c.reason
+                    );
+                    if let Some(budget) = self.persisted.ai_budget.get_mut_cow(&side) {
+                        *budget = budget.saturating_sub(cost);
+                    }
+                    self.ephemeral
+                        .ai_commander
+                        .recent_targets
+                        .insert((side, c.target), now);
+                    self.ephemeral.stat(Stat::AiCommanderAction {
+                        side,
+                        action: c.name,
+                        reason: c.reason,
+                        cost,
+                    });
+                    self.ephemeral.dirty();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Let each side's AI commander, if configured, accrue budget and
+    /// spend it on an action
+    pub fn run_ai_commanders(
+        &mut self,
+        lua: MizLua,
+        perf: &mut PerfInner,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        jtacs: &Jtacs,
+        ewr: &Ewr,
+        now: DateTime<Utc>,
+    ) {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let recent = cfg
+            .ai_commander
+            .values()
+            .map(|c| c.decision_interval as i64 * 3)
+            .max()
+            .unwrap_or(0);
+        self.ephemeral
+            .ai_commander
+            .recent_targets
+            .retain(|_, ts| now - *ts < Duration::seconds(recent));
+        for (side, ccfg) in &cfg.ai_commander {
+            self.run_ai_commander(lua, perf, spctx, idx, jtacs, ewr, *side, ccfg, now)
+        }
+    }
+}
@@ -0,0 +1,379 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{Db, cargo::RescuedPilot, group::DeployKind};
+use crate::{
+    maybe,
+    spawnctx::{SpawnCtx, SpawnLoc},
+};
+use anyhow::{Result, anyhow, bail};
+use bfprotocols::{cfg::LifeType, db::group::GroupId, stats::Stat};
+use chrono::{Duration, prelude::*};
+use compact_str::format_compact;
+use dcso3::{
+    LuaVec2, LuaVec3, MizLua, String, Vector2, Vector3, centroid2d,
+    env::miz::MizIndex,
+    land::Land,
+    net::{SlotId, Ucid},
+    object::DcsOid,
+    trigger::{Modulation, Trigger},
+    unit::ClassUnit,
+};
+use enumflags2::BitFlags;
+use log::{error, info};
+use smallvec::{SmallVec, smallvec};
+use std::{mem, sync::Arc};
+
+/// The runtime state of combat search and rescue. The downed pilots
+/// themselves are persisted.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Csar {
+    /// downed pilots whose beacon needs to be started
+    pub(super) start_beacons: Vec<GroupId>,
+    /// the names of the beacons of downed pilots that are gone
+    pub(super) stop_beacons: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum PilotPickup {
+    Rescued(String),
+    Captured(String),
+}
+
+impl Db {
+    fn downed_pilot_name(&self, ucid: &Ucid) -> String {
+        self.persisted
+            .players
+            .get(ucid)
+            .map(|p| p.name.clone())
+            .unwrap_or_default()
+    }
+
+    fn return_pilot_life(&mut self, ucid: &Ucid, life_type: LifeType) {
+        if !self.ephemeral.cfg.limited_lives {
+            return;
+        }
+        let max_lives = match self.ephemeral.cfg.default_lives.get(&life_type) {
+            Some((n, _)) => *n,
+            None => return,
+        };
+        let player = match self.persisted.players.get_mut_cow(ucid) {
+            Some(player) => player,
+            None => return,
+        };
+        if let Some((_, lives)) = player.lives.get_mut_cow(&life_type) {
+            *lives += 1;
+            if *lives >= max_lives {
+                player.lives.remove_cow(&life_type);
+            }
+            self.ephemeral.stat(Stat::Life {
+                id: *ucid,
+                lives: player.lives.clone(),
+            });
+            self.ephemeral.dirty();
+        }
+    }
+
+    /// Spawn a downed pilot if the player in unit id ejected away
+    /// from a friendly objective
+    pub fn pilot_ejected(
+        &mut self,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        id: &DcsOid<ClassUnit>,
+        pos: Vector2,
+        now: DateTime<Utc>,
+    ) -> Result<Option<GroupId>> {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let ccfg = match cfg.csar.as_ref() {
+            Some(ccfg) => ccfg,
+            None => return Ok(None),
+        };
+        let ucid = match self.ephemeral.player_in_unit(id) {
+            Some(ucid) => *ucid,
+            None => return Ok(None),
+        };
+        let player = maybe!(self.persisted.players, ucid, "player")?;
+        let side = player.side;
+        let life_type = match player.airborne {
+            Some(life_type) => life_type,
+            None => return Ok(None),
+        };
+        let friendly = self
+            .persisted
+            .objectives
+            .into_iter()
+            .any(|(_, obj)| obj.owner == side && obj.zone.contains(pos));
+        if friendly {
+            return Ok(None);
+        }
+        let template = match ccfg.template.get(&side) {
+            Some(template) => template,
+            None => return Ok(None),
+        };
+        let location = SpawnLoc::AtPos {
+            pos,
+            offset_direction: Vector2::new(1., 0.),
+            group_heading: 0.,
+        };
+        let dk = DeployKind::DownedPilot {
+            player: ucid,
+            life_type,
+            time: now,
+        };
+        let gid = self.add_and_queue_group(
+            spctx,
+            idx,
+            side,
+            location,
+            template,
+            dk,
+            BitFlags::empty(),
+            None,
+        )?;
+        self.ephemeral.csar.start_beacons.push(gid);
+        let name = self.downed_pilot_name(&ucid);
+        info!("{side} pilot {name} is down, group {gid}");
+        let msg = format_compact!("{name} ejected, downed pilot {gid} is awaiting rescue");
+        self.ephemeral.msgs.panel_to_side(10, false, side, msg);
+        Ok(Some(gid))
+    }
+
+    /// Pick up the downed pilot nearest the unit in slot. Friendly
+    /// pilots are loaded as cargo, enemy pilots are captured.
+    pub fn pickup_pilot(&mut self, lua: MizLua, slot: &SlotId) -> Result<Option<PilotPickup>> {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let ccfg = match cfg.csar.as_ref() {
+            Some(ccfg) => ccfg,
+            None => return Ok(None),
+        };
+        let (cargo_capacity, side, unit_name) = self.unit_cargo_cfg(slot)?;
+        let unit = self.ephemeral.slot_instance_unit(lua, slot)?;
+        let pos = unit.get_position()?;
+        let point = Vector2::new(pos.p.x, pos.p.z);
+        let max_dist = (cfg.crate_load_distance as f64).powi(2);
+        let found = self
+            .persisted
+            .downed_pilots
+            .into_iter()
+            .filter_map(|gid| self.persisted.groups.get(gid).map(|g| (*gid, g)))
+            .find_map(|(gid, g)| match &g.origin {
+                DeployKind::DownedPilot {
+                    player, life_type, ..
+                } => {
+                    let in_range = g
+                        .units
+                        .into_iter()
+                        .filter_map(|uid| self.persisted.units.get(uid))
+                        .any(|u| na::distance_squared(&u.pos.into(), &point.into()) <= max_dist);
+                    in_range.then(|| (gid, g.side, *player, *life_type))
+                }
+                _ => None,
+            });
+        let (gid, pilot_side, pilot, life_type) = match found {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        if unit.in_air()? {
+            bail!("you must land to pick up a downed pilot")
+        }
+        let ucid = self
+            .ephemeral
+            .player_in_slot(slot)
+            .cloned()
+            .ok_or_else(|| anyhow!("can't find player in slot {slot:?}"))?;
+        if pilot == ucid {
+            bail!("you can't rescue yourself")
+        }
+        let name = self.downed_pilot_name(&pilot);
+        if pilot_side != side {
+            self.delete_group(&gid)?;
+            self.adjust_points(
+                &ucid,
+                ccfg.capture_points as i32,
+                &format_compact!("for capturing {name}"),
+            );
+            let msg = "you have been captured by the enemy";
+            self.ephemeral
+                .panel_to_player(&self.persisted, 10, &pilot, msg);
+            return Ok(Some(PilotPickup::Captured(name)));
+        }
+        let cargo = self.ephemeral.cargo.entry(slot.clone()).or_default();
+        if cargo_capacity.troop_slots as usize <= cargo.num_troops()
+            || cargo_capacity.total_slots as usize <= cargo.num_total()
+        {
+            bail!("you already have a full load onboard")
+        }
+        cargo.pilots.push(RescuedPilot {
+            player: pilot,
+            name: name.clone(),
+            life_type,
+            weight: ccfg.weight,
+        });
+        Trigger::singleton(lua)?
+            .action()?
+            .set_unit_internal_cargo(unit_name, cargo.weight() as i64)?;
+        self.delete_group(&gid)?;
+        Ok(Some(PilotPickup::Rescued(name)))
+    }
+
+    /// Deliver the rescued pilots onboard the unit in slot if it has
+    /// landed at a friendly objective, returning their lives. If the
+    /// pilots can't be delivered here but troops are also onboard
+    /// nothing is delivered, so the troops can still be unloaded.
+    pub fn deliver_pilots(&mut self, lua: MizLua, slot: &SlotId) -> Result<SmallVec<[String; 1]>> {
+        let has_troops = match self.ephemeral.cargo.get(slot) {
+            Some(cargo) if !cargo.pilots.is_empty() => !cargo.troops.is_empty(),
+            Some(_) | None => return Ok(smallvec![]),
+        };
+        let unit = self.ephemeral.slot_instance_unit(lua, slot)?;
+        if unit.in_air()? {
+            if has_troops {
+                return Ok(smallvec![]);
+            }
+            bail!("you must land to deliver rescued pilots")
+        }
+        let unit_name = unit.get_name()?;
+        let side = self
+            .ephemeral
+            .get_slot_info(slot)
+            .ok_or_else(|| anyhow!("no slot info for {slot:?}"))?
+            .side;
+        let pos = unit.get_position()?;
+        let point = Vector2::new(pos.p.x, pos.p.z);
+        let friendly = self
+            .persisted
+            .objectives
+            .into_iter()
+            .any(|(_, obj)| obj.owner == side && obj.zone.contains(point));
+        if !friendly {
+            if has_troops {
+                return Ok(smallvec![]);
+            }
+            bail!("you must land at a friendly objective to deliver rescued pilots")
+        }
+        let ucid = self
+            .ephemeral
+            .player_in_slot(slot)
+            .cloned()
+            .ok_or_else(|| anyhow!("can't find player in slot {slot:?}"))?;
+        let cargo = self
+            .ephemeral
+            .cargo
+            .get_mut(slot)
+            .ok_or_else(|| anyhow!("no cargo for {slot:?}"))?;
+        let pilots = mem::take(&mut cargo.pilots);
+        Trigger::singleton(lua)?
+            .action()?
+            .set_unit_internal_cargo(unit_name, cargo.weight())?;
+        let points = self
+            .ephemeral
+            .cfg
+            .csar
+            .as_ref()
+            .map(|c| c.rescue_points)
+            .unwrap_or(0);
+        let mut delivered = smallvec![];
+        for p in pilots {
+            self.return_pilot_life(&p.player, p.life_type);
+            self.adjust_points(
+                &ucid,
+                points as i32,
+                &format_compact!("for rescuing {}", p.name),
+            );
+            let msg = format_compact!(
+                "you have been rescued, your {} life was returned",
+                p.life_type
+            );
+            self.ephemeral
+                .panel_to_player(&self.persisted, 10, &p.player, msg);
+            delivered.push(p.name);
+        }
+        Ok(delivered)
+    }
+
+    /// Remove downed pilots who have waited too long for rescue and
+    /// start and stop the downed pilot beacons
+    pub fn run_csar(&mut self, lua: MizLua, now: DateTime<Utc>) -> Result<()> {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let ccfg = match cfg.csar.as_ref() {
+            Some(ccfg) => ccfg,
+            None => return Ok(()),
+        };
+        let expire = Duration::minutes(ccfg.expire as i64);
+        let expired: SmallVec<[GroupId; 4]> = self
+            .persisted
+            .downed_pilots
+            .into_iter()
+            .filter_map(|gid| self.persisted.groups.get(gid))
+            .filter_map(|g| match &g.origin {
+                DeployKind::DownedPilot { time, .. } if now - *time >= expire => Some(g.id),
+                _ => None,
+            })
+            .collect();
+        for gid in expired {
+            if let DeployKind::DownedPilot { player, .. } = &self.persisted.groups[&gid].origin {
+                let side = self.persisted.groups[&gid].side;
+                let name = self.downed_pilot_name(player);
+                let msg = format_compact!("downed pilot {name} {gid} was not rescued in time");
+                self.ephemeral.msgs.panel_to_side(10, false, side, msg);
+            }
+            self.delete_group(&gid)?
+        }
+        let act = Trigger::singleton(lua)?.action()?;
+        for name in mem::take(&mut self.ephemeral.csar.stop_beacons) {
+            if ccfg.beacon.is_some()
+                && let Err(e) = act.stop_transmission(name.clone())
+            {
+                error!("failed to stop beacon {name} {e:?}")
+            }
+        }
+        let bcfg = match ccfg.beacon.as_ref() {
+            Some(bcfg) => bcfg,
+            None => {
+                self.ephemeral.csar.start_beacons.clear();
+                return Ok(());
+            }
+        };
+        let land = Land::singleton(lua)?;
+        for gid in mem::take(&mut self.ephemeral.csar.start_beacons) {
+            let group = match self.persisted.groups.get(&gid) {
+                Some(group) => group,
+                None => continue,
+            };
+            let pos = centroid2d(
+                group
+                    .units
+                    .into_iter()
+                    .filter_map(|uid| self.persisted.units.get(uid))
+                    .map(|u| u.pos),
+            );
+            let alt = land.get_height(LuaVec2(pos))? + 2.;
+            if let Err(e) = act.radio_transmission(
+                bcfg.file.clone(),
+                LuaVec3(Vector3::new(pos.x, alt, pos.y)),
+                Modulation::AM,
+                true,
+                bcfg.frequency,
+                bcfg.power,
+                group.name.clone(),
+            ) {
+                error!("failed to start beacon for downed pilot {gid} {e:?}")
+            }
+        }
+        Ok(())
+    }
+}
@@ -16,11 +16,18 @@ for more details.
 
 use super::{
     cargo::Cargo,
+    commander::AiCommander,
+    csar::Csar,
+    frontline::FrontLine,
     group::{SpawnedGroup, SpawnedUnit},
+    jammer::Jamming,
     logistics::LogiStage,
     markup::ObjectiveMarkup,
-    objective::Objective,
+    objective::{CaptureProgress, Objective},
+    offensive::Offensives,
     persisted::Persisted,
+    recon::Recon,
+    threatmap::ThreatMap,
 };
 use crate::{
     bg::Task,
@@ -31,9 +38,9 @@ use crate::{
 use anyhow::{Context, Result, anyhow, bail};
 use bfprotocols::{
     cfg::{
-        ActionKind, AiPlaneCfg, AwacsCfg, BomberCfg, Cfg, Crate, Deployable, DeployableCfg,
-        DeployableKind, DeployableObjective, DroneCfg, Troop, UnitTag, Vehicle, VictoryCondition,
-        WarehouseConfig,
+        ActionKind, AiPlaneCfg, AirdropCfg, AwacsCfg, BomberCfg, Cfg, Crate, Deployable,
+        DeployableCfg, DeployableKind, DeployableObjective, DroneCfg, JammerCfg, NavalGroupCfg,
+        ReconCfg, Troop, UnitTag, Vehicle, VictoryCondition, WarehouseConfig,
     },
     db::{
         group::{GroupId, UnitId},
@@ -132,6 +139,9 @@ pub struct Ephemeral {
     pub(super) units_potentially_close_to_enemies: FxHashSet<UnitId>,
     pub(super) production_by_side: FxHashMap<Side, Arc<Production>>,
     pub(super) actions_taken: FxHashMap<Side, FxHashMap<String, u32>>,
+    pub(super) last_budget_regen: Option<DateTime<Utc>>,
+    pub(super) action_queue_marks: FxHashMap<u64, MarkId>,
+    pub(super) minefield_marks: FxHashMap<u64, MarkId>,
     pub(super) delayspawnq: BTreeMap<DateTime<Utc>, SmallVec<[GroupId; 8]>>,
     pub(super) awacs_stn: u32,
     pub(super) logistics_stage: LogiStage,
@@ -140,6 +150,15 @@ pub struct Ephemeral {
     sync_warehouse: Vec<(ObjectiveId, Vehicle)>,
     pub(super) msgs: MsgQ,
     pub(super) victory: Option<(DateTime<Utc>, Side)>,
+    pub(super) front_line: FrontLine,
+    pub(super) capture_progress: FxHashMap<ObjectiveId, CaptureProgress>,
+    pub(super) upgrade_respawns: Vec<ObjectiveId>,
+    pub(super) ai_commander: AiCommander,
+    pub(super) offensives: Offensives,
+    pub(super) csar: Csar,
+    pub(super) recon: Recon,
+    pub(super) threat_map: ThreatMap,
+    pub(super) jamming: Jamming,
 }
 
 impl Default for Ephemeral {
@@ -171,6 +190,9 @@ impl Default for Ephemeral {
             units_potentially_close_to_enemies: FxHashSet::default(),
             production_by_side: FxHashMap::default(),
             actions_taken: FxHashMap::default(),
+            last_budget_regen: None,
+            action_queue_marks: FxHashMap::default(),
+            minefield_marks: FxHashMap::default(),
             delayspawnq: BTreeMap::default(),
             awacs_stn: 0o77777,
             spawnq: VecDeque::default(),
@@ -179,6 +201,15 @@ impl Default for Ephemeral {
             msgs: MsgQ::default(),
             logistics_stage: LogiStage::default(),
             victory: None,
+            front_line: FrontLine::default(),
+            capture_progress: FxHashMap::default(),
+            upgrade_respawns: Vec::default(),
+            ai_commander: AiCommander::default(),
+            offensives: Offensives::default(),
+            csar: Csar::default(),
+            recon: Recon::default(),
+            threat_map: ThreatMap::default(),
+            jamming: Jamming::default(),
         }
     }
 }
@@ -236,6 +267,12 @@ impl Ephemeral {
         }
     }
 
+    pub fn update_capture_markup(&mut self, oid: &ObjectiveId, progress: Option<(Side, u8, bool)>) {
+        if let Some(mk) = self.objective_markup.get_mut(oid) {
+            mk.update_capture(&mut self.msgs, progress)
+        }
+    }
+
     pub fn remove_objective_markup(&mut self, oid: &ObjectiveId) {
         if let Some(mk) = self.objective_markup.remove(oid) {
             mk.remove(&mut self.msgs)
@@ -771,10 +808,24 @@ impl Ephemeral {
                         plane: AiPlaneCfg { template, .. },
                         ..
                     })
+                    | ActionKind::Recon(ReconCfg {
+                        plane: AiPlaneCfg { template, .. },
+                        ..
+                    })
+                    | ActionKind::Jammer(JammerCfg {
+                        plane: AiPlaneCfg { template, .. },
+                        ..
+                    })
                     | ActionKind::Fighters(AiPlaneCfg { template, .. })
                     | ActionKind::Attackers(AiPlaneCfg { template, .. })
                     | ActionKind::LogisticsRepair(AiPlaneCfg { template, .. })
-                    | ActionKind::LogisticsTransfer(AiPlaneCfg { template, .. }) => {
+                    | ActionKind::LogisticsTransfer(AiPlaneCfg { template, .. })
+                    | ActionKind::Airdrop(AirdropCfg {
+                        plane: AiPlaneCfg { template, .. },
+                        ..
+                    })
+                    | ActionKind::AntiShip(AiPlaneCfg { template, .. })
+                    | ActionKind::NavalPatrol(NavalGroupCfg { template, .. }) => {
                         miz.get_group_by_name(mizidx, GroupKind::Any, *side, template.as_str())?
                             .ok_or_else(|| anyhow!("missing template for action {act:?}"))?;
                     }
@@ -810,6 +861,7 @@ impl Ephemeral {
                     ActionKind::AwacsWaypoint
                     | ActionKind::TankerWaypoint
                     | ActionKind::DroneWaypoint
+                    | ActionKind::JammerWaypoint
                     | ActionKind::CruiseMissileWaypoint
                     | ActionKind::FighersWaypoint
                     | ActionKind::AttackersWaypoint
@@ -817,6 +869,7 @@ impl Ephemeral {
                     | ActionKind::SeadWaypoint
                     | ActionKind::Move(_)
                     | ActionKind::Rtb
+                    | ActionKind::ShoreBombardment(_)
                     | ActionKind::Nuke(_) => (),
                 }
             }
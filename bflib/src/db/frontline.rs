@@ -0,0 +1,248 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::Db;
+use bfprotocols::db::objective::ObjectiveId;
+use dcso3::{
+    Color, LuaVec3, Vector2, Vector3,
+    coalition::Side,
+    trigger::{FreeformSpec, LineType, MarkId, SideFilter},
+};
+use fxhash::FxHashMap;
+use log::warn;
+use smallvec::{SmallVec, smallvec};
+use std::sync::Arc;
+
+/// The objective adjacency graph and the set of objectives that
+/// currently border an objective with a different owner. Farps are
+/// not part of the graph.
+///
+/// The graph drives capture, culling and supply, but not the
+/// threatened state. That tracks enemies actually seen near an
+/// objective. Deriving it from adjacency would leave every front
+/// line objective threatened forever, which would block troop
+/// deployment there and spam threat warnings.
+#[derive(Debug, Clone, Default)]
+pub(super) struct FrontLine {
+    adjacent: FxHashMap<ObjectiveId, SmallVec<[ObjectiveId; 8]>>,
+    front: FxHashMap<ObjectiveId, Side>,
+    marks: SmallVec<[MarkId; 2]>,
+}
+
+impl FrontLine {
+    fn link(&mut self, a: ObjectiveId, b: ObjectiveId) {
+        if a != b {
+            let adj = self.adjacent.entry(a).or_default();
+            if !adj.contains(&b) {
+                adj.push(b)
+            }
+            let adj = self.adjacent.entry(b).or_default();
+            if !adj.contains(&a) {
+                adj.push(a)
+            }
+        }
+    }
+
+    pub(super) fn contains(&self, oid: &ObjectiveId) -> bool {
+        self.front.contains_key(oid)
+    }
+
+    pub(super) fn adjacent(&self, oid: &ObjectiveId) -> &[ObjectiveId] {
+        self.adjacent.get(oid).map(|a| &a[..]).unwrap_or(&[])
+    }
+}
+
+impl Db {
+    /// Build the objective adjacency graph from the front line
+    /// config. Two objectives are adjacent if they are within the
+    /// adjacency distance of each other, if one is the other's
+    /// nearest neighbor, or if they are explicitly linked.
+    pub(super) fn build_objective_graph(&mut self) {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let flcfg = match cfg.front_line.as_ref() {
+            Some(flcfg) => flcfg,
+            None => return,
+        };
+        let max_dist = (flcfg.adjacency_distance as f64).powi(2);
+        let objectives = self
+            .persisted
+            .objectives
+            .into_iter()
+            .filter(|(_, obj)| !obj.is_farp())
+            .map(|(oid, obj)| (*oid, obj.zone.pos()))
+            .collect::<SmallVec<[(ObjectiveId, Vector2); 128]>>();
+        let mut fl = FrontLine::default();
+        for (oid, pos) in &objectives {
+            let mut nearest: Option<(ObjectiveId, f64)> = None;
+            for (other, opos) in &objectives {
+                if oid == other {
+                    continue;
+                }
+                let dist = na::distance_squared(&(*pos).into(), &(*opos).into());
+                if dist <= max_dist {
+                    fl.link(*oid, *other)
+                }
+                if nearest.map(|(_, nd)| dist < nd).unwrap_or(true) {
+                    nearest = Some((*other, dist))
+                }
+            }
+            if let Some((other, _)) = nearest {
+                fl.link(*oid, other)
+            }
+        }
+        for (a, b) in &flcfg.links {
+            let pa = self.persisted.objectives_by_name.get(a.as_str());
+            let pb = self.persisted.objectives_by_name.get(b.as_str());
+            match (pa, pb) {
+                (Some(a), Some(b)) => fl.link(*a, *b),
+                (None, _) | (_, None) => {
+                    warn!("front line link {a} <-> {b} references an unknown objective")
+                }
+            }
+        }
+        fl.marks = self.ephemeral.front_line.marks.clone();
+        self.ephemeral.front_line = fl;
+        self.update_front_line();
+    }
+
+    /// Recompute the front line after objective ownership changes,
+    /// redrawing it if it moved or changed hands.
+    pub fn update_front_line(&mut self) {
+        let draw = match self.ephemeral.cfg.front_line.as_ref() {
+            Some(flcfg) => flcfg.draw,
+            None => return,
+        };
+        let objectives = &self.persisted.objectives;
+        let owner = |oid: &ObjectiveId| objectives.get(oid).map(|o| o.owner);
+        let front = self
+            .ephemeral
+            .front_line
+            .adjacent
+            .iter()
+            .filter_map(|(oid, adj)| {
+                let side = owner(oid)?;
+                adj.iter()
+                    .any(|a| owner(a).map(|s| s != side).unwrap_or(false))
+                    .then_some((*oid, side))
+            })
+            .collect::<FxHashMap<_, _>>();
+        if front == self.ephemeral.front_line.front {
+            return;
+        }
+        self.ephemeral.front_line.front = front;
+        if draw {
+            self.draw_front_line()
+        }
+    }
+
+    /// Draw each side's front as a closed polygon, the convex hull of
+    /// the zones of the front line objectives it owns
+    fn draw_front_line(&mut self) {
+        let fl = &mut self.ephemeral.front_line;
+        for id in fl.marks.drain(..) {
+            self.ephemeral.msgs.delete_mark(id)
+        }
+        for side in [Side::Red, Side::Blue] {
+            let mut points: SmallVec<[Vector2; 128]> = smallvec![];
+            for (oid, owner) in &fl.front {
+                let obj = match self.persisted.objectives.get(oid) {
+                    Some(obj) if *owner == side => obj,
+                    Some(_) | None => continue,
+                };
+                let pos = obj.zone.pos();
+                let radius = obj.zone.radius();
+                for i in 0..8 {
+                    let a = i as f64 * std::f64::consts::FRAC_PI_4;
+                    points.push(pos + Vector2::new(a.cos(), a.sin()) * radius)
+                }
+            }
+            let hull = convex_hull(points);
+            if hull.len() < 3 {
+                continue;
+            }
+            let (color, fill_color) = match side {
+                Side::Red => (Color::red(0.75), Color::red(0.1)),
+                Side::Blue | Side::Neutral => (Color::blue(0.75), Color::blue(0.1)),
+            };
+            let id = MarkId::new();
+            let spec = FreeformSpec {
+                points: hull
+                    .into_iter()
+                    .map(|p| LuaVec3(Vector3::new(p.x, 0., p.y)))
+                    .collect(),
+                color,
+                fill_color,
+                line_type: LineType::LongDash,
+                read_only: true,
+            };
+            self.ephemeral
+                .msgs
+                .freeform_to_all(SideFilter::All, id, spec, None);
+            fl.marks.push(id);
+        }
+    }
+
+    pub fn is_front_line(&self, oid: &ObjectiveId) -> bool {
+        self.ephemeral.front_line.contains(oid)
+    }
+
+    /// When the front line only mode is enabled an objective can only
+    /// be captured by a side that owns an adjacent objective.
+    pub fn can_capture(&self, oid: &ObjectiveId, side: Side) -> bool {
+        match self.ephemeral.cfg.front_line.as_ref() {
+            Some(flcfg) if flcfg.capture_front_only => {
+                let adj = self.ephemeral.front_line.adjacent(oid);
+                adj.is_empty()
+                    || adj.iter().any(|a| {
+                        self.persisted
+                            .objectives
+                            .get(a)
+                            .map(|o| o.owner == side)
+                            .unwrap_or(false)
+                    })
+            }
+            Some(_) | None => true,
+        }
+    }
+}
+
+/// The convex hull of a set of points in counter clockwise order
+/// (Andrew's monotone chain)
+fn convex_hull(mut points: SmallVec<[Vector2; 128]>) -> SmallVec<[Vector2; 32]> {
+    points.sort_by(|p0, p1| p0.x.total_cmp(&p1.x).then(p0.y.total_cmp(&p1.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points.into_iter().collect();
+    }
+    let cross =
+        |o: Vector2, a: Vector2, b: Vector2| (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+    let mut hull: SmallVec<[Vector2; 32]> = smallvec![];
+    for p in &points {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], *p) <= 0. {
+            hull.pop();
+        }
+        hull.push(*p)
+    }
+    let lower = hull.len() + 1;
+    for p in points.iter().rev().skip(1) {
+        while hull.len() >= lower && cross(hull[hull.len() - 2], hull[hull.len() - 1], *p) <= 0. {
+            hull.pop();
+        }
+        hull.push(*p)
+    }
+    hull.pop();
+    hull
+}
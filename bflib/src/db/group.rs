@@ -22,7 +22,7 @@ use crate::{
 };
 use anyhow::{Context, Result, anyhow, bail};
 use bfprotocols::{
-    cfg::{Action, ActionKind, Crate, Deployable, Troop, UnitTag, UnitTags, Vehicle},
+    cfg::{Action, ActionKind, Crate, Deployable, LifeType, Troop, UnitTag, UnitTags, Vehicle},
     db::objective::ObjectiveId,
     stats::{self, EnId},
 };
@@ -110,6 +110,18 @@ pub enum DeployKind {
         origin: Option<ObjectiveId>,
         #[serde(skip)]
         ammo: i32,
+        /// the number of supply packages carried by an airdrop
+        #[serde(default)]
+        packages: u8,
+    },
+    Offensive {
+        origin: ObjectiveId,
+        target: ObjectiveId,
+    },
+    DownedPilot {
+        player: Ucid,
+        life_type: LifeType,
+        time: DateTime<Utc>,
     },
 }
 
@@ -355,6 +367,34 @@ impl Db {
                         .mark_to_side(group.side, group_center, true, msg),
                 )
             }
+            DeployKind::Offensive { origin, target } => {
+                let name = |oid: &ObjectiveId| {
+                    self.persisted
+                        .objectives
+                        .get(oid)
+                        .map(|o| o.name.clone())
+                        .unwrap_or_default()
+                };
+                let msg = format_compact!(
+                    "offensive {gid} from {} toward {}",
+                    name(origin),
+                    name(target)
+                );
+                Some(
+                    self.ephemeral
+                        .msgs
+                        .mark_to_side(group.side, group_center, true, msg),
+                )
+            }
+            DeployKind::DownedPilot { player, .. } => {
+                let name = self.persisted.players[player].name.clone();
+                let msg = format_compact!("downed pilot {name} {gid} awaiting rescue");
+                Some(
+                    self.ephemeral
+                        .msgs
+                        .mark_to_side(group.side, group_center, true, msg),
+                )
+            }
         };
         if let Some(id) = id {
             self.ephemeral.group_marks.insert(*gid, id);
@@ -401,6 +441,13 @@ impl Db {
                     self.persisted.jtacs.remove_cow(gid);
                 }
             }
+            DeployKind::Offensive { .. } => {
+                self.persisted.offensives.remove_cow(gid);
+            }
+            DeployKind::DownedPilot { .. } => {
+                self.persisted.downed_pilots.remove_cow(gid);
+                self.ephemeral.csar.stop_beacons.push(group.name.clone());
+            }
         }
         if let Some(id) = self.ephemeral.group_marks.remove(gid) {
             self.ephemeral.msgs.delete_mark(id);
@@ -826,6 +873,12 @@ impl Db {
                     self.persisted.jtacs.insert_cow(gid);
                 }
             }
+            DeployKind::Offensive { .. } => {
+                self.persisted.offensives.insert_cow(gid);
+            }
+            DeployKind::DownedPilot { .. } => {
+                self.persisted.downed_pilots.insert_cow(gid);
+            }
         }
         self.persisted.groups.insert_cow(gid, spawned);
         self.persisted.groups_by_name.insert_cow(group_name, gid);
@@ -1022,6 +1075,7 @@ impl Db {
                 if self.persisted.deployed.contains(&gid)
                     || self.persisted.troops.contains(&gid)
                     || self.persisted.crates.contains(&gid)
+                    || self.persisted.downed_pilots.contains(&gid)
                 {
                     if health == 0 {
                         match &group!(self, gid)?.origin {
@@ -1048,7 +1102,9 @@ impl Db {
                             | DeployKind::Action { .. }
                             | DeployKind::Crate { .. }
                             | DeployKind::Objective { .. }
-                            | DeployKind::ObjectiveDeprecated => (),
+                            | DeployKind::ObjectiveDeprecated
+                            | DeployKind::Offensive { .. }
+                            | DeployKind::DownedPilot { .. } => (),
                         }
                         self.delete_group(&gid)?
                     }
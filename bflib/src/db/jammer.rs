@@ -0,0 +1,182 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{Db, actions::racetrack_dist_and_heading, group::DeployKind};
+use crate::spawnctx::SpawnLoc;
+use anyhow::Result;
+use bfprotocols::{
+    cfg::{ActionKind, JammerCfg, UnitTag},
+    db::group::GroupId,
+};
+use dcso3::{
+    MizLua, Vector2, Vector3, azumith2d_to, change_heading,
+    coalition::Side,
+    controller::{AiOption, GroundOption},
+    degrees_to_radians,
+    group::Group,
+};
+use fxhash::FxHashMap;
+use log::error;
+use smallvec::{SmallVec, smallvec};
+use std::f64;
+
+#[derive(Debug, Clone)]
+struct JammerCone {
+    side: Side,
+    pos: Vector3,
+    /// the direction the cone points in radians
+    axis: f64,
+    cfg: JammerCfg,
+}
+
+impl JammerCone {
+    fn covers(&self, pos: Vector3) -> bool {
+        let range = (self.cfg.range as f64).powi(2);
+        if na::distance_squared(&self.pos.into(), &pos.into()) > range {
+            return false;
+        }
+        let az = azumith2d_to(
+            Vector2::new(self.pos.x, self.pos.z),
+            Vector2::new(pos.x, pos.z),
+        );
+        let diff = (az - self.axis).abs();
+        let diff = if diff > f64::consts::PI {
+            2. * f64::consts::PI - diff
+        } else {
+            diff
+        };
+        diff <= degrees_to_radians(self.cfg.beam_width as f64) / 2.
+    }
+}
+
+/// The runtime state of jamming, it is rebuilt from the jammer
+/// action groups every slow tick
+#[derive(Debug, Clone, Default)]
+pub(super) struct Jamming {
+    cones: SmallVec<[JammerCone; 4]>,
+    /// sam groups currently degraded and the engagement range they
+    /// were restricted to
+    sams: FxHashMap<GroupId, u8>,
+}
+
+impl Db {
+    /// The jammer degrading a radar of side at pos, if any
+    pub fn jammed(&self, side: Side, pos: Vector3) -> Option<&JammerCfg> {
+        self.ephemeral
+            .jamming
+            .cones
+            .iter()
+            .find(|cone| cone.side != side && cone.covers(pos))
+            .map(|cone| &cone.cfg)
+    }
+
+    /// Update the jamming cones from the airborne jammers, and degrade
+    /// or restore enemy SAMs as they enter or leave them
+    pub fn run_jammers(&mut self, lua: MizLua) -> Result<()> {
+        let mut cones: SmallVec<[JammerCone; 4]> = smallvec![];
+        for gid in &self.persisted.actions {
+            let group = match self.persisted.groups.get(gid) {
+                Some(group) => group,
+                None => continue,
+            };
+            let (center, cfg) = match &group.origin {
+                DeployKind::Action {
+                    spec,
+                    loc: SpawnLoc::InAir { pos, .. },
+                    ..
+                } => match &spec.kind {
+                    ActionKind::Jammer(cfg) => (*pos, cfg),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            // the race track is perpendicular to the heading to the
+            // nearest enemy objective, the jammer points down that heading
+            let (_, heading) = racetrack_dist_and_heading(
+                &self.persisted.objectives,
+                center,
+                group.side.opposite(),
+            );
+            let axis = change_heading(heading, -f64::consts::FRAC_PI_2);
+            for uid in &group.units {
+                if let Some(unit) = self.persisted.units.get(uid)
+                    && !unit.dead
+                    && unit.airborne_velocity.is_some()
+                {
+                    cones.push(JammerCone {
+                        side: group.side,
+                        pos: unit.position.p.0,
+                        axis,
+                        cfg: cfg.clone(),
+                    })
+                }
+            }
+        }
+        self.ephemeral.jamming.cones = cones;
+        let mut jammed: FxHashMap<GroupId, u8> = FxHashMap::default();
+        if !self.ephemeral.jamming.cones.is_empty() {
+            for (gid, group) in &self.persisted.groups {
+                if !group.tags.contains(UnitTag::SearchRadar) {
+                    continue;
+                }
+                for uid in &group.units {
+                    if let Some(unit) = self.persisted.units.get(uid)
+                        && !unit.dead
+                        && unit.tags.contains(UnitTag::SearchRadar)
+                        && let Some(cfg) = self.jammed(group.side, unit.position.p.0)
+                    {
+                        jammed.insert(*gid, cfg.sam_range);
+                        break;
+                    }
+                }
+            }
+        }
+        let mut changed: SmallVec<[(GroupId, u8); 8]> = smallvec![];
+        for (gid, range) in &jammed {
+            if self.ephemeral.jamming.sams.get(gid) != Some(range) {
+                changed.push((*gid, *range));
+            }
+        }
+        for gid in self.ephemeral.jamming.sams.keys() {
+            if !jammed.contains_key(gid) {
+                changed.push((*gid, 100));
+            }
+        }
+        for (gid, range) in changed {
+            let group = match self.persisted.groups.get(&gid) {
+                Some(group) => group,
+                None => continue,
+            };
+            // the group may not be spawned at the moment
+            if let Ok(group) = Group::get_by_name(lua, &group.name) {
+                let con = match group.get_controller() {
+                    Ok(con) => con,
+                    Err(e) => {
+                        error!("could not get the controller of jammed sam {gid}, {e:?}");
+                        continue;
+                    }
+                };
+                if let Err(e) = con.set_option(AiOption::Ground(
+                    GroundOption::AcEngagementRangeRestriction(range),
+                )) {
+                    error!("could not set the engagement range of jammed sam {gid}, {e:?}")
+                }
+            }
+        }
+        self.ephemeral.jamming.sams = jammed;
+        Ok(())
+    }
+}
@@ -18,6 +18,7 @@ use super::{
     ephemeral::{Equipment, Production},
     objective::Objective,
     persisted::Persisted,
+    upgrade::upgraded_capacity,
     Db, Map, MapS, SetS,
 };
 use crate::{admin::WarehouseKind, maybe, objective, objective_mut, Task};
@@ -33,6 +34,7 @@ use compact_str::{format_compact, CompactString};
 use dcso3::{
     airbase::Airbase,
     coalition::Side,
+    net::Ucid,
     object::DcsObject,
     perf::record_perf,
     warehouse::{self, LiquidType},
@@ -46,8 +48,9 @@ use smallvec::{smallvec, SmallVec};
 use std::{
     cmp::{max, min},
     collections::hash_map::Entry,
-    mem,
+    fmt, mem,
     ops::{AddAssign, SubAssign},
+    str::FromStr,
     sync::Arc,
 };
 use tokio::sync::mpsc::UnboundedSender;
@@ -101,6 +104,17 @@ impl Inventory {
             taken
         }
     }
+
+    pub fn fill(&mut self, percent: f32) -> u32 {
+        if self.stored >= self.capacity {
+            0
+        } else {
+            let added = max(1, (self.capacity as f32 * percent) as u32);
+            let added = min(added, self.capacity - self.stored);
+            self.stored += added;
+            added
+        }
+    }
 }
 
 impl AddAssign<u32> for Inventory {
@@ -207,10 +221,121 @@ impl Transfer {
 struct Needed<'a> {
     oid: &'a ObjectiveId,
     obj: &'a Objective,
+    request: Option<&'a SupplyRequest>,
+    front: u32,
+    weight: u32,
     demanded: u32,
     allocated: u32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RequestPriority {
+    Low,
+    Normal,
+    High,
+}
+
+impl RequestPriority {
+    fn weight(&self) -> u32 {
+        match self {
+            Self::Low => 1,
+            Self::Normal => 2,
+            Self::High => 4,
+        }
+    }
+}
+
+impl FromStr for RequestPriority {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "low" => Ok(Self::Low),
+            "normal" => Ok(Self::Normal),
+            "high" => Ok(Self::High),
+            s => bail!("unknown priority {s}, expected low, normal, or high"),
+        }
+    }
+}
+
+impl fmt::Display for RequestPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Normal => write!(f, "normal"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RequestItem {
+    Fuel,
+    Supplies,
+    Equipment(String),
+}
+
+impl fmt::Display for RequestItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fuel => write!(f, "fuel"),
+            Self::Supplies => write!(f, "supplies"),
+            Self::Equipment(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupplyRequest {
+    pub by: Ucid,
+    pub side: Side,
+    pub priority: RequestPriority,
+    pub items: SmallVec<[RequestItem; 4]>,
+    pub expires: DateTime<Utc>,
+}
+
+impl SupplyRequest {
+    fn equipment_weight(&self, name: &str) -> u32 {
+        let wanted = self.items.iter().any(|i| match i {
+            RequestItem::Supplies => true,
+            RequestItem::Equipment(n) => n.as_str() == name,
+            RequestItem::Fuel => false,
+        });
+        if wanted { self.priority.weight() } else { 1 }
+    }
+
+    fn liquid_weight(&self, _: &LiquidType) -> u32 {
+        if self.items.contains(&RequestItem::Fuel) {
+            self.priority.weight()
+        } else {
+            1
+        }
+    }
+
+    fn satisfied(&self, obj: &Objective) -> bool {
+        self.items.iter().all(|i| match i {
+            RequestItem::Fuel => obj.fuel >= 100,
+            RequestItem::Supplies => obj.supply >= 100,
+            RequestItem::Equipment(name) => {
+                let inv = obj.get_equipment(name);
+                inv.stored >= inv.capacity
+            }
+        })
+    }
+
+    pub fn items(&self) -> CompactString {
+        self.items
+            .iter()
+            .fold(CompactString::new(""), |mut acc, i| {
+                if !acc.is_empty() {
+                    acc.push_str(", ");
+                }
+                acc.push_str(&format_compact!("{i}"));
+                acc
+            })
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Warehouse {
     pub(super) base_equipment: Map<String, Inventory>,
@@ -345,7 +470,7 @@ impl Db {
             };
             for (name, equip) in &production.equipment {
                 for (oid, obj) in self.persisted.objectives.iter_mut_cow() {
-                    if obj.owner == side {
+                    if obj.owner == side && obj.kind.has_warehouse() {
                         let hub = self.persisted.logistics_hubs.contains(&oid);
                         let capacity = whcfg.capacity(hub, equip.production);
                         let inv = obj.warehouse.equipment.get_or_default_cow(name.clone());
@@ -356,7 +481,7 @@ impl Db {
             }
             for (name, qty) in &production.liquids {
                 for (oid, obj) in self.persisted.objectives.iter_mut_cow() {
-                    if obj.owner == side {
+                    if obj.owner == side && obj.kind.has_warehouse() {
                         let hub = self.persisted.logistics_hubs.contains(&oid);
                         let capacity = whcfg.capacity(hub, *qty);
                         let inv = obj.warehouse.liquids.get_or_default_cow(*name);
@@ -395,11 +520,28 @@ impl Db {
                     airbase
                         .auto_capture(false)
                         .context("setting airbase autocapture")?;
+                    // carriers move, so they are linked to their ship by unit name
+                    let ship = self
+                        .persisted
+                        .units_by_name
+                        .get(&name)
+                        .and_then(|uid| self.persisted.units.get(uid))
+                        .map(|unit| unit.group);
+                    let linked = |obj: &Objective| match obj.kind {
+                        ObjectiveKind::Carrier => ship.is_some_and(|gid| {
+                            obj.groups.into_iter().any(|(_, gids)| gids.contains(&gid))
+                        }),
+                        ObjectiveKind::Port => false,
+                        ObjectiveKind::Airbase
+                        | ObjectiveKind::Farp { .. }
+                        | ObjectiveKind::Fob
+                        | ObjectiveKind::Logistics => obj.zone.contains(pos),
+                    };
                     let oid = self
                         .persisted
                         .objectives
                         .into_iter()
-                        .find(|(_, obj)| obj.zone.contains(pos));
+                        .find(|(_, obj)| linked(obj));
                     let w = airbase
                         .get_warehouse()
                         .context("getting airbase warehouse")?;
@@ -439,10 +581,14 @@ impl Db {
         load_and_sync_airbases().context("loading and syncing airbases")?;
         let mut adjust_warehouses_for_miz_changes = || -> Result<()> {
             for (oid, obj) in self.persisted.objectives.iter_mut_cow() {
+                if !obj.kind.has_warehouse() {
+                    continue;
+                }
                 let mut del_eq: SmallVec<[String; 8]> = smallvec![];
                 let mut del_l: SmallVec<[LiquidType; 4]> = smallvec![];
                 if let Some(prod) = self.ephemeral.production_by_side.get(&obj.owner) {
                     let hub = self.persisted.logistics_hubs.contains(oid);
+                    let bonus = obj.capacity_bonus(&self.ephemeral.cfg);
                     for (name, _) in &obj.warehouse.equipment {
                         if !prod.equipment.contains_key(name) {
                             del_eq.push(name.clone());
@@ -460,12 +606,13 @@ impl Db {
                         obj.warehouse.liquids.remove_cow(&liq);
                     }
                     for (name, eqip) in &prod.equipment {
-                        let capacity = whcfg.capacity(hub, eqip.production);
+                        let capacity =
+                            upgraded_capacity(whcfg.capacity(hub, eqip.production), bonus);
                         let inv = obj.warehouse.equipment.get_or_default_cow(name.clone());
                         inv.capacity = capacity;
                     }
                     for (name, prod) in &prod.liquids {
-                        let capacity = whcfg.capacity(hub, *prod);
+                        let capacity = upgraded_capacity(whcfg.capacity(hub, *prod), bonus);
                         let inv = obj.warehouse.liquids.get_or_default_cow(*name);
                         inv.capacity = capacity;
                     }
@@ -476,7 +623,7 @@ impl Db {
         adjust_warehouses_for_miz_changes().context("adjusting warehouses for miz changes")?;
         let mut missing = vec![];
         for (oid, obj) in &self.persisted.objectives {
-            if !self.ephemeral.airbase_by_oid.contains_key(oid) {
+            if obj.kind.has_warehouse() && !self.ephemeral.airbase_by_oid.contains_key(oid) {
                 missing.push(obj.name.clone());
             }
         }
@@ -490,6 +637,161 @@ impl Db {
         Ok(())
     }
 
+    pub fn add_supply_request(
+        &mut self,
+        ucid: &Ucid,
+        oid: ObjectiveId,
+        priority: RequestPriority,
+        items: SmallVec<[RequestItem; 4]>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let lifetime = match self.ephemeral.cfg.warehouse.as_ref() {
+            Some(whcfg) => Duration::minutes(whcfg.supply_request_lifetime as i64),
+            None => bail!("supply requests require the warehouse system"),
+        };
+        if items.is_empty() {
+            bail!("you must request at least one item")
+        }
+        let player = maybe!(self.persisted.players, ucid, "player")?;
+        let obj = objective!(self, oid)?;
+        if obj.owner != player.side {
+            bail!("{} is not owned by your side", obj.name)
+        }
+        if self.persisted.logistics_hubs.contains(&oid) {
+            bail!("{} is a logistics hub and can't request supplies", obj.name)
+        }
+        if let Some(open) = self.persisted.supply_requests.get(&oid) {
+            if open.side == player.side && &open.by != ucid && open.expires > now {
+                let by = self
+                    .persisted
+                    .players
+                    .get(&open.by)
+                    .map(|p| p.name.as_str())
+                    .unwrap_or("another player");
+                bail!(
+                    "{by} already requested {} at {}, wait for it to be filled or expire",
+                    open.items(),
+                    obj.name
+                )
+            }
+        }
+        for item in &items {
+            if let RequestItem::Equipment(name) = item {
+                if obj.warehouse.equipment.get(name).is_none() {
+                    bail!("{} does not stock {name}", obj.name)
+                }
+            }
+        }
+        let req = SupplyRequest {
+            by: *ucid,
+            side: player.side,
+            priority,
+            items,
+            expires: now + lifetime,
+        };
+        if req.satisfied(obj) {
+            bail!("{} is already fully stocked with {}", obj.name, req.items())
+        }
+        let side = req.side;
+        let msg = format_compact!(
+            "{} requests {} at {}, {} priority",
+            player.name,
+            req.items(),
+            obj.name,
+            priority
+        );
+        self.persisted.supply_requests.insert_cow(oid, req);
+        self.ephemeral.msgs().panel_to_side(10, false, side, msg);
+        self.ephemeral.dirty();
+        Ok(())
+    }
+
+    pub fn cancel_supply_request(&mut self, ucid: &Ucid, oid: ObjectiveId) -> Result<()> {
+        let player = maybe!(self.persisted.players, ucid, "player")?;
+        let obj = objective!(self, oid)?;
+        match self.persisted.supply_requests.get(&oid) {
+            Some(req) if req.side == player.side => {
+                let side = req.side;
+                let msg = format_compact!(
+                    "{} cancelled the supply request at {}",
+                    player.name,
+                    obj.name
+                );
+                self.persisted.supply_requests.remove_cow(&oid);
+                self.ephemeral.msgs().panel_to_side(10, false, side, msg);
+                self.ephemeral.dirty();
+                Ok(())
+            }
+            Some(_) | None => bail!("there is no open supply request at {}", obj.name),
+        }
+    }
+
+    pub fn supply_requests(
+        &self,
+        side: Side,
+    ) -> impl Iterator<Item = (&Objective, &SupplyRequest)> {
+        self.persisted
+            .supply_requests
+            .into_iter()
+            .filter(move |(_, req)| req.side == side)
+            .filter_map(|(oid, req)| Some((self.persisted.objectives.get(oid)?, req)))
+    }
+
+    /// Close the open request at oid if it is now satisfied, and award
+    /// the player who answered it the fill bonus, unless they made the
+    /// request themselves.
+    pub(super) fn fill_supply_request(&mut self, ucid: &Ucid, oid: ObjectiveId) -> Result<bool> {
+        let obj = objective!(self, oid)?;
+        match self.persisted.supply_requests.get(&oid) {
+            Some(req) if req.side == obj.owner && req.satisfied(obj) => {
+                let side = req.side;
+                let by_requester = &req.by == ucid;
+                let msg = format_compact!("the supply request at {} has been filled", obj.name);
+                self.persisted.supply_requests.remove_cow(&oid);
+                self.ephemeral.msgs().panel_to_side(10, false, side, msg);
+                if !by_requester
+                    && let Some(amount) = self
+                        .ephemeral
+                        .cfg
+                        .points
+                        .as_ref()
+                        .map(|p| p.supply_request_fill)
+                {
+                    self.adjust_points(ucid, amount as i32, "for filling a supply request");
+                }
+                self.ephemeral.dirty();
+                Ok(true)
+            }
+            Some(_) | None => Ok(false),
+        }
+    }
+
+    pub fn expire_supply_requests(&mut self, now: DateTime<Utc>) {
+        let mut closed: SmallVec<[(ObjectiveId, Side, Option<CompactString>); 4]> = smallvec![];
+        for (oid, req) in &self.persisted.supply_requests {
+            match self.persisted.objectives.get(oid) {
+                None => closed.push((*oid, req.side, None)),
+                Some(obj) if obj.owner != req.side => closed.push((*oid, req.side, None)),
+                Some(obj) if req.satisfied(obj) => {
+                    let msg = format_compact!("the supply request at {} has been filled", obj.name);
+                    closed.push((*oid, req.side, Some(msg)))
+                }
+                Some(obj) if now >= req.expires => {
+                    let msg = format_compact!("the supply request at {} has expired", obj.name);
+                    closed.push((*oid, req.side, Some(msg)))
+                }
+                Some(_) => (),
+            }
+        }
+        for (oid, side, msg) in closed {
+            self.persisted.supply_requests.remove_cow(&oid);
+            if let Some(msg) = msg {
+                self.ephemeral.msgs().panel_to_side(10, false, side, msg);
+            }
+            self.ephemeral.dirty();
+        }
+    }
+
     pub fn admin_tick_now(&mut self) {
         match &mut self.ephemeral.logistics_stage {
             LogiStage::Init
@@ -523,6 +825,7 @@ impl Db {
                         .persisted
                         .objectives
                         .into_iter()
+                        .filter(|(_, obj)| obj.kind.has_warehouse())
                         .map(|(id, _)| *id)
                         .collect();
                     self.ephemeral.logistics_stage = LogiStage::SyncToWarehouses { objectives }
@@ -532,6 +835,7 @@ impl Db {
                         .persisted
                         .objectives
                         .into_iter()
+                        .filter(|(_, obj)| obj.kind.has_warehouse())
                         .map(|(id, _)| *id)
                         .collect();
                     self.ephemeral.logistics_stage = LogiStage::SyncFromWarehouses { objectives };
@@ -582,6 +886,7 @@ impl Db {
                         .persisted
                         .objectives
                         .into_iter()
+                        .filter(|(_, obj)| obj.kind.has_warehouse())
                         .map(|(id, _)| *id)
                         .collect();
                     self.ephemeral.logistics_stage = LogiStage::SyncToWarehouses { objectives };
@@ -621,6 +926,9 @@ impl Db {
             None => return Ok(()),
         };
         let obj = objective_mut!(self, oid)?;
+        if !obj.kind.has_warehouse() {
+            return Ok(());
+        }
         let other_production = match self.ephemeral.production_by_side.get(&obj.owner.opposite()) {
             Some(q) => Arc::clone(q),
             None => Arc::new(Production::default()),
@@ -631,11 +939,12 @@ impl Db {
         };
         let map = warehouse::Warehouse::get_resource_map(lua).context("getting resource map")?;
         let hub = obj.kind.is_hub();
+        let bonus = obj.capacity_bonus(&self.ephemeral.cfg);
         map.for_each(|name, _| {
             match production.equipment.get(&name) {
                 Some(equip) => {
                     let inv = obj.warehouse.equipment.get_or_default_cow(name);
-                    inv.capacity = whcfg.capacity(hub, equip.production);
+                    inv.capacity = upgraded_capacity(whcfg.capacity(hub, equip.production), bonus);
                 }
                 None => {
                     if let Some(_) = other_production.equipment.get(&name) {
@@ -651,7 +960,7 @@ impl Db {
             match production.liquids.get(&name) {
                 Some(qty) => {
                     let inv = obj.warehouse.liquids.get_or_default_cow(name);
-                    inv.capacity = whcfg.capacity(hub, *qty);
+                    inv.capacity = upgraded_capacity(whcfg.capacity(hub, *qty), bonus);
                 }
                 None => {
                     if let Some(_) = other_production.liquids.get(&name) {
@@ -692,8 +1001,11 @@ impl Db {
         let mut suppliers: SmallVec<[(ObjectiveId, Option<ObjectiveId>); 64]> = smallvec![];
         for (oid, obj) in &self.persisted.objectives {
             match obj.kind {
-                ObjectiveKind::Logistics => (),
-                ObjectiveKind::Airbase | ObjectiveKind::Farp { .. } | ObjectiveKind::Fob => {
+                ObjectiveKind::Logistics | ObjectiveKind::Port => (),
+                ObjectiveKind::Airbase
+                | ObjectiveKind::Farp { .. }
+                | ObjectiveKind::Fob
+                | ObjectiveKind::Carrier => {
                     let hub = self.compute_supplier(obj)?;
                     suppliers.push((*oid, hub));
                 }
@@ -781,6 +1093,10 @@ impl Db {
     pub fn deliver_supplies_from_logistics_hubs(&mut self) -> Result<Vec<Transfer>> {
         self.update_supply_status()
             .context("updating supply status")?;
+        let front_weight = match self.ephemeral.cfg.front_line.as_ref() {
+            Some(flcfg) => max(1, flcfg.supply_weight),
+            None => 1,
+        };
         let mut transfers: Vec<Transfer> = vec![];
         for lid in &self.persisted.logistics_hubs {
             let logi = objective!(self, lid)?;
@@ -793,20 +1109,34 @@ impl Db {
                 .map(|(oid, obj)| Needed {
                     oid,
                     obj,
+                    request: self
+                        .persisted
+                        .supply_requests
+                        .get(oid)
+                        .filter(|r| r.side == obj.owner),
+                    front: if self.is_front_line(oid) {
+                        front_weight
+                    } else {
+                        1
+                    },
+                    weight: 1,
                     demanded: 0,
                     allocated: 0,
                 })
                 .collect();
             macro_rules! schedule_transfers {
-                ($typ:expr, $from:ident, $get:ident) => {
+                ($typ:expr, $from:ident, $get:ident, $weight:ident) => {
                     for (name, inv) in &logi.warehouse.$from {
                         if inv.stored == 0 {
                             continue;
                         }
+                        for n in &mut needed {
+                            n.weight = n.front * n.request.map(|r| r.$weight(name)).unwrap_or(1);
+                        }
                         needed.sort_by(|n0, n1| {
                             let i0 = n0.obj.$get(name);
                             let i1 = n1.obj.$get(name);
-                            i0.stored.cmp(&i1.stored)
+                            n1.weight.cmp(&n0.weight).then(i0.stored.cmp(&i1.stored))
                         });
                         let mut total_demanded = 0;
                         for n in &mut needed {
@@ -827,7 +1157,7 @@ impl Db {
                                 if have == 0 {
                                     break;
                                 }
-                                let allocation = max(1, have >> 3);
+                                let allocation = min(have, max(1, have >> 3) * n.weight);
                                 let amount = min(allocation, n.demanded - n.allocated);
                                 n.allocated += amount;
                                 total_filled += amount;
@@ -847,8 +1177,13 @@ impl Db {
                     }
                 };
             }
-            schedule_transfers!(TransferItem::Equipment, equipment, get_equipment);
-            schedule_transfers!(TransferItem::Liquid, liquids, get_liquids);
+            schedule_transfers!(
+                TransferItem::Equipment,
+                equipment,
+                get_equipment,
+                equipment_weight
+            );
+            schedule_transfers!(TransferItem::Liquid, liquids, get_liquids, liquid_weight);
         }
         Ok(transfers)
     }
@@ -1087,6 +1422,17 @@ impl Db {
         if amount > 100 {
             bail!("enter a percentage")
         }
+        self.reduce_inventory(lua, oid, amount)
+    }
+
+    /// Reduce every produced item in the objective's warehouse by
+    /// amount percent
+    pub(super) fn reduce_inventory(
+        &mut self,
+        lua: MizLua,
+        oid: ObjectiveId,
+        amount: u8,
+    ) -> Result<()> {
         let percent = amount as f32 / 100.;
         let production = match self
             .ephemeral
@@ -1116,6 +1462,43 @@ impl Db {
         Ok(())
     }
 
+    /// Add amount percent of capacity to every produced item in the
+    /// objective's warehouse, up to its capacity
+    pub(super) fn airdrop_inventory(
+        &mut self,
+        lua: MizLua,
+        oid: ObjectiveId,
+        amount: u8,
+    ) -> Result<()> {
+        let percent = amount as f32 / 100.;
+        let production = match self
+            .ephemeral
+            .production_by_side
+            .get(&objective!(self, oid)?.owner)
+        {
+            Some(p) => Arc::clone(p),
+            None => return Ok(()),
+        };
+        let (obj, warehouse) = self
+            .sync_warehouse_to_objective(lua, oid)
+            .with_context(|| format_compact!("syncing warehouses to {oid}"))?;
+        for name in production.equipment.keys() {
+            if let Some(inv) = obj.warehouse.equipment.get_mut_cow(name) {
+                inv.fill(percent);
+            }
+        }
+        for liq in production.liquids.keys() {
+            if let Some(inv) = obj.warehouse.liquids.get_mut_cow(&liq) {
+                inv.fill(percent);
+            }
+        }
+        sync_obj_to_warehouse(obj, &warehouse).context("syncing from warehouse")?;
+        self.update_supply_status()
+            .context("updating supply status")?;
+        self.ephemeral.dirty();
+        Ok(())
+    }
+
     pub fn admin_log_inventory(
         &mut self,
         lua: MizLua,
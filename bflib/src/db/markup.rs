@@ -47,6 +47,8 @@ pub(super) struct ObjectiveMarkup {
     label: MarkId,
     pos: Vector2,
     supply_connections: FxHashMap<ObjectiveId, MarkId>,
+    capture: Option<(Side, u8, bool)>,
+    capture_label: Option<MarkId>,
 }
 
 fn text_color(side: Side, a: f32) -> Color {
@@ -96,11 +98,16 @@ impl ObjectiveMarkup {
             threatened_ring,
             supply_connections,
             label,
+            capture: _,
+            capture_label,
         } = self;
         msgq.delete_mark(owner_ring);
         msgq.delete_mark(threatened_ring);
         msgq.delete_mark(capturable_ring);
         msgq.delete_mark(label);
+        if let Some(id) = capture_label {
+            msgq.delete_mark(id)
+        }
         for (_, id) in supply_connections {
             msgq.delete_mark(id)
         }
@@ -173,12 +180,64 @@ impl ObjectiveMarkup {
         }
     }
 
+    /// show, update, or remove the capture progress label. progress
+    /// is the capturing side, the percent complete, and whether the
+    /// capture is currently contested
+    pub(super) fn update_capture(&mut self, msgq: &mut MsgQ, progress: Option<(Side, u8, bool)>) {
+        if self.capture == progress {
+            return;
+        }
+        self.capture = progress;
+        match progress {
+            None => {
+                if let Some(id) = self.capture_label.take() {
+                    msgq.delete_mark(id)
+                }
+            }
+            Some((side, pct, contested)) => {
+                let text = if contested {
+                    format_compact!("Contested: {pct}%")
+                } else {
+                    format_compact!("Capturing: {pct}%")
+                };
+                match self.capture_label {
+                    Some(id) => {
+                        msgq.set_markup_text(id, text.into());
+                        msgq.set_markup_color(id, text_color(side, 1.));
+                    }
+                    None => {
+                        let id = MarkId::new();
+                        msgq.text_to_all(
+                            SideFilter::All,
+                            id,
+                            TextSpec {
+                                pos: LuaVec3(Vector3::new(
+                                    self.pos.x - 1500.,
+                                    1.,
+                                    self.pos.y + 1500.,
+                                )),
+                                color: text_color(side, 1.),
+                                fill_color: Color::black(0.),
+                                font_size: 12,
+                                read_only: true,
+                                text: text.into(),
+                            },
+                        );
+                        self.capture_label = Some(id);
+                    }
+                }
+            }
+        }
+    }
+
     pub(super) fn new(cfg: &Cfg, msgq: &mut MsgQ, obj: &Objective, persisted: &Persisted) -> Self {
         let text_color = |a| text_color(obj.owner, a);
         let all_spec = match obj.kind {
-            ObjectiveKind::Airbase | ObjectiveKind::Fob | ObjectiveKind::Logistics => {
-                SideFilter::All
-            }
+            ObjectiveKind::Airbase
+            | ObjectiveKind::Fob
+            | ObjectiveKind::Logistics
+            | ObjectiveKind::Port
+            | ObjectiveKind::Carrier => SideFilter::All,
             ObjectiveKind::Farp { .. } => obj.owner.into(),
         };
         let mut t = ObjectiveMarkup::default();
@@ -311,7 +370,11 @@ impl ObjectiveMarkup {
             },
         );
         match obj.kind {
-            ObjectiveKind::Airbase | ObjectiveKind::Farp { .. } | ObjectiveKind::Fob => (),
+            ObjectiveKind::Airbase
+            | ObjectiveKind::Farp { .. }
+            | ObjectiveKind::Fob
+            | ObjectiveKind::Port
+            | ObjectiveKind::Carrier => (),
             ObjectiveKind::Logistics => {
                 for oid in &obj.warehouse.destination {
                     let id = MarkId::new();
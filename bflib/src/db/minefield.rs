@@ -0,0 +1,262 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{Db, group::DeployKind};
+use anyhow::Result;
+use bfprotocols::{
+    cfg::{Deployable, MinefieldCfg, UnitTag},
+    db::group::GroupId,
+};
+use chrono::{Duration, prelude::*};
+use dcso3::{
+    Color, LuaVec2, LuaVec3, MizLua, Quad2, Vector2, Vector3,
+    coalition::Side,
+    net::Ucid,
+    normal2, pointing_towards2,
+    trigger::{LineType, MarkId, QuadSpec, Trigger},
+};
+use log::error;
+use rand::{Rng, thread_rng};
+use serde_derive::{Deserialize, Serialize};
+use smallvec::{SmallVec, smallvec};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Minefield {
+    pub side: Side,
+    pub player: Ucid,
+    pub spec: Deployable,
+    pub cfg: MinefieldCfg,
+    pub area: Quad2,
+    /// the number of mines left in the field
+    pub mines: u32,
+}
+
+impl Db {
+    /// Lay a minefield centered on pos. The belt runs across heading
+    /// (radians), so it faces the direction the deployer was looking.
+    pub fn add_minefield(
+        &mut self,
+        side: Side,
+        player: Ucid,
+        spec: &Deployable,
+        cfg: MinefieldCfg,
+        pos: Vector2,
+        heading: f64,
+    ) -> u64 {
+        let along = pointing_towards2(heading) * (cfg.depth as f64 / 2.);
+        let across = normal2(pointing_towards2(heading)) * (cfg.length as f64 / 2.);
+        let area = Quad2 {
+            p0: LuaVec2(pos + across + along),
+            p1: LuaVec2(pos - across + along),
+            p2: LuaVec2(pos - across - along),
+            p3: LuaVec2(pos + across - along),
+        };
+        let id = self
+            .persisted
+            .minefields
+            .into_iter()
+            .map(|(id, _)| *id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let mf = Minefield {
+            side,
+            player,
+            spec: spec.clone(),
+            cfg,
+            area,
+            mines: cfg.mines,
+        };
+        self.persisted.minefields.insert_cow(id, mf);
+        self.draw_minefield(id);
+        self.ephemeral.dirty();
+        id
+    }
+
+    pub fn delete_minefield(&mut self, id: u64) {
+        self.persisted.minefields.remove_cow(&id);
+        if let Some(mark) = self.ephemeral.minefield_marks.remove(&id) {
+            self.ephemeral.msgs.delete_mark(mark);
+        }
+        self.ephemeral.dirty();
+    }
+
+    fn draw_minefield(&mut self, id: u64) {
+        let mf = match self.persisted.minefields.get(&id) {
+            Some(mf) => mf,
+            None => return,
+        };
+        let p = |p: LuaVec2| LuaVec3(Vector3::new(p.x, 0., p.y));
+        let mark = MarkId::new();
+        self.ephemeral.msgs.quad_to_all(
+            mf.side.into(),
+            mark,
+            QuadSpec {
+                p0: p(mf.area.p0),
+                p1: p(mf.area.p1),
+                p2: p(mf.area.p2),
+                p3: p(mf.area.p3),
+                color: Color::red(0.75),
+                fill_color: Color::red(0.15),
+                line_type: LineType::Dashed,
+                read_only: true,
+            },
+            None,
+        );
+        if let Some(old) = self.ephemeral.minefield_marks.insert(id, mark) {
+            self.ephemeral.msgs.delete_mark(old);
+        }
+    }
+
+    /// True if any living unit of the group is inside a minefield
+    /// laid by the other side
+    pub fn in_enemy_minefield(&self, gid: &GroupId) -> bool {
+        let group = match self.persisted.groups.get(gid) {
+            Some(group) => group,
+            None => return false,
+        };
+        self.persisted
+            .minefields
+            .into_iter()
+            .filter(|(_, mf)| mf.side != group.side)
+            .any(|(_, mf)| {
+                group
+                    .units
+                    .into_iter()
+                    .any(|uid| match self.persisted.units.get(uid) {
+                        Some(unit) => !unit.dead && mf.area.contains(LuaVec2(unit.pos)),
+                        None => false,
+                    })
+            })
+    }
+
+    /// Roll for enemy ground units moving through minefields, let
+    /// engineers clear mines, and remove exhausted fields
+    pub fn run_minefields(&mut self, lua: MizLua, now: DateTime<Utc>) -> Result<()> {
+        if self.persisted.minefields.len() == 0 {
+            return Ok(());
+        }
+        // unit positions are updated incrementally, so a unit that
+        // moved in the last couple of ticks is still considered moving
+        let recent = Duration::seconds(2 * self.ephemeral.cfg.slow_timed_events_freq as i64);
+        let act = Trigger::singleton(lua)?.action()?;
+        let mut rng = thread_rng();
+        let mut hits: SmallVec<[(u64, Vector3); 8]> = smallvec![];
+        for uid in &self.ephemeral.units_able_to_move {
+            let unit = match self.persisted.units.get(uid) {
+                Some(unit) => unit,
+                None => continue,
+            };
+            if unit.dead
+                || unit.tags.contains(UnitTag::Aircraft)
+                || unit.tags.contains(UnitTag::Boat)
+                || !unit.moved.map(|ts| now - ts <= recent).unwrap_or(false)
+            {
+                continue;
+            }
+            for (id, mf) in &self.persisted.minefields {
+                if mf.side != unit.side
+                    && mf.area.contains(LuaVec2(unit.pos))
+                    && rng.gen_range(0..100) < mf.cfg.hit_chance as u32
+                {
+                    hits.push((*id, unit.position.p.0));
+                    break;
+                }
+            }
+        }
+        for (id, pos) in hits {
+            if let Some(mf) = self.persisted.minefields.get_mut_cow(&id)
+                && mf.mines > 0
+            {
+                mf.mines -= 1;
+                if let Err(e) = act.explosion(LuaVec3(pos), mf.cfg.power as f32) {
+                    error!("could not detonate mine {e:?}")
+                }
+                self.ephemeral.dirty();
+            }
+        }
+        let mut clearing: SmallVec<[u64; 4]> = smallvec![];
+        for gid in &self.persisted.troops {
+            let group = match self.persisted.groups.get(gid) {
+                Some(group) => group,
+                None => continue,
+            };
+            match &group.origin {
+                DeployKind::Troop { spec, .. } if spec.can_clear_mines => (),
+                _ => continue,
+            }
+            for (id, mf) in &self.persisted.minefields {
+                if mf.side == group.side {
+                    continue;
+                }
+                let inside = group.units.into_iter().any(|uid| {
+                    self.persisted
+                        .units
+                        .get(uid)
+                        .map(|u| !u.dead && mf.area.contains(LuaVec2(u.pos)))
+                        .unwrap_or(false)
+                });
+                if inside {
+                    clearing.push(*id);
+                }
+            }
+        }
+        let mut cleared: SmallVec<[u64; 4]> = smallvec![];
+        for id in clearing {
+            if let Some(mf) = self.persisted.minefields.get_mut_cow(&id)
+                && mf.mines > 0
+            {
+                mf.mines = mf.mines.saturating_sub(mf.cfg.clear_rate);
+                if mf.mines == 0 {
+                    cleared.push(id);
+                }
+                self.ephemeral.dirty();
+            }
+        }
+        let exhausted: SmallVec<[(u64, Side); 4]> = self
+            .persisted
+            .minefields
+            .into_iter()
+            .filter(|(_, mf)| mf.mines == 0)
+            .map(|(id, mf)| (*id, mf.side))
+            .collect();
+        for (id, side) in exhausted {
+            self.delete_minefield(id);
+            if cleared.contains(&id) {
+                let msg = "enemy engineers cleared one of our minefields";
+                self.ephemeral.msgs.panel_to_side(10, false, side, msg);
+                let msg = "our engineers cleared an enemy minefield";
+                self.ephemeral
+                    .msgs
+                    .panel_to_side(10, false, side.opposite(), msg);
+            } else {
+                let msg = "one of our minefields has been exhausted";
+                self.ephemeral.msgs.panel_to_side(10, false, side, msg);
+            }
+        }
+        let undrawn: SmallVec<[u64; 4]> = self
+            .persisted
+            .minefields
+            .into_iter()
+            .filter(|(id, _)| !self.ephemeral.minefield_marks.contains_key(id))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in undrawn {
+            self.draw_minefield(id)
+        }
+        Ok(())
+    }
+}
@@ -32,7 +32,7 @@ use crate::{
 };
 use anyhow::{Context, Result, anyhow, bail};
 use bfprotocols::{
-    cfg::{Cfg, Vehicle},
+    cfg::{Cfg, UnitTag, Vehicle},
     db::{
         group::GroupId,
         objective::{ObjectiveId, ObjectiveKind},
@@ -43,7 +43,7 @@ use bfprotocols::{
 use chrono::prelude::*;
 use compact_str::CompactString;
 use dcso3::{
-    centroid2d, coalition::Side, controller::PointType, coord::Coord, env::miz::{Group, Miz, MizIndex, Skill, TriggerZone, TriggerZoneTyp}, land::Land, net::Net, trigger::Trigger, LuaVec2, LuaVec3, MizLua, String, Vector2, Vector3
+    centroid2d, coalition::Side, controller::PointType, coord::Coord, env::miz::{Group, GroupKind, Miz, MizIndex, Skill, TriggerZone, TriggerZoneTyp}, land::Land, net::Net, trigger::Trigger, LuaVec2, LuaVec3, MizLua, String, Vector2, Vector3
 };
 use enumflags2::BitFlags;
 use fxhash::FxHashSet;
@@ -63,6 +63,10 @@ impl Db {
     /// - FO: Fob
     /// - SA: Sam site
     /// - LO: Logistics Objective
+    /// - PO: Port, naval actions sail from here. The zone center must be in the water.
+    ///   Ports have no airbase or warehouse
+    /// - CV: Carrier, a carrier group with slots. The carrier is linked to its
+    ///   objective by unit name, not by the zone
     ///
     /// Then a 1 character code for the default owner
     /// followed by the display name
@@ -92,8 +96,14 @@ impl Db {
         } else if let Some(name) = name.strip_prefix("LO") {
             let (side, name) = side_and_name(name)?;
             (ObjectiveKind::Logistics, side, name)
+        } else if let Some(name) = name.strip_prefix("PO") {
+            let (side, name) = side_and_name(name)?;
+            (ObjectiveKind::Port, side, name)
+        } else if let Some(name) = name.strip_prefix("CV") {
+            let (side, name) = side_and_name(name)?;
+            (ObjectiveKind::Carrier, side, name)
         } else {
-            bail!("invalid objective type for {name}, expected AB, FO, of LO")
+            bail!("invalid objective type for {name}, expected AB, FO, LO, PO, or CV")
         };
         let id = ObjectiveId::new();
         let mut logistics_detached = false;
@@ -140,6 +150,7 @@ impl Db {
             last_threatened_ts: Utc::now(),
             warehouse: Warehouse::default(),
             points: 0,
+            upgrades: vec![],
             logistics_detached,
             last_activate: DateTime::<Utc>::default(),
             // initialized by load
@@ -190,6 +201,19 @@ impl Db {
                 }
             }
         };
+        // the carrier itself must keep the name it has in the miz, otherwise
+        // the slots linked to it would lose their deck
+        let mut extra_tags = BitFlags::empty();
+        if let ObjectiveKind::Carrier = &self.persisted.objectives[&obj].kind
+            && let Ok(gifo) = spctx.get_template_ref(idx, GroupKind::Any, side, name)
+            && let Ok(units) = gifo.group.units()
+            && let Ok(unit) = units.first()
+            && let Ok(typ) = unit.typ()
+            && let Some(tags) = self.ephemeral.cfg.unit_classification.get(&Vehicle(typ))
+            && tags.contains(UnitTag::Boat)
+        {
+            extra_tags = UnitTag::NavalSpawnPoint.into();
+        }
         let gid = self.add_group(
             spctx,
             idx,
@@ -201,7 +225,7 @@ impl Db {
             },
             name,
             DeployKind::Objective { origin: obj },
-            BitFlags::empty(),
+            extra_tags,
         )?;
         let o = objective_mut!(self, obj)?;
         o.groups.get_or_default_cow(side).insert_cow(gid);
@@ -406,6 +430,14 @@ impl Db {
             for gid in &self.persisted.troops {
                 self.ephemeral.push_spawn(*gid);
             }
+            for gid in &self.persisted.offensives {
+                self.ephemeral.push_spawn(*gid);
+                self.ephemeral.offensives.pending_moves.push(*gid);
+            }
+            for gid in &self.persisted.downed_pilots {
+                self.ephemeral.push_spawn(*gid);
+                self.ephemeral.csar.start_beacons.push(*gid);
+            }
             let actions: SmallVec<[GroupId; 16]> =
                 SmallVec::from_iter(self.persisted.actions.into_iter().map(|g| *g));
             debug!("respawn actions");
@@ -478,6 +510,12 @@ impl Db {
             Ok(())
         };
         mark_deployed_and_logistics().context("marking deployed and logistics")?;
+        self.build_objective_graph();
+        for (oid, obj) in &self.persisted.objectives {
+            if !obj.upgrades.is_empty() {
+                self.ephemeral.upgrade_respawns.push(*oid);
+            }
+        }
         let net = Net::singleton(lua)?;
         let act = Trigger::singleton(lua)?.action()?;
         // spawn all the markup
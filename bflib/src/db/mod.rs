@@ -36,16 +36,27 @@ use dcso3::{
 use std::{cmp::max, fs::File, path::Path, sync::Arc};
 use tokio::sync::mpsc::UnboundedSender;
 
+pub mod actionqueue;
 pub mod actions;
 pub mod cargo;
+pub mod commander;
+pub mod csar;
 pub mod ephemeral;
+pub mod frontline;
 pub mod group;
+pub mod jammer;
 pub mod logistics;
 pub mod markup;
+pub mod minefield;
 pub mod mizinit;
 pub mod objective;
+pub mod offensive;
 pub mod persisted;
 pub mod player;
+pub mod recon;
+pub mod threatmap;
+pub mod throttle;
+pub mod upgrade;
 
 pub type Map<K, V> = immutable_chunkmap::map::Map<K, V, 256>;
 pub type MapM<K, V> = immutable_chunkmap::map::Map<K, V, 64>;
@@ -216,13 +227,15 @@ impl Db {
         }
     }
 
-    pub fn ewrs(&self) -> impl Iterator<Item = (Vector3, Side, &DeployableEwr)> {
+    pub fn ewrs(&self) -> impl Iterator<Item = (GroupId, Vector3, Side, &DeployableEwr)> {
         self.persisted.ewrs.into_iter().filter_map(|gid| {
             let group = self.persisted.groups.get(gid)?;
             match &group.origin {
                 DeployKind::Crate { .. }
                 | DeployKind::Objective { .. }
                 | DeployKind::ObjectiveDeprecated
+                | DeployKind::Offensive { .. }
+                | DeployKind::DownedPilot { .. }
                 | DeployKind::Troop { .. } => None,
                 DeployKind::Action {
                     spec:
@@ -242,7 +255,7 @@ impl Db {
                             .into_iter()
                             .map(|u| self.persisted.units[u].position.p.0),
                     );
-                    Some((pos, group.side, ewr))
+                    Some((*gid, pos, group.side, ewr))
                 }
                 DeployKind::Action { .. } | DeployKind::Deployed { .. } => None,
             }
@@ -300,6 +313,8 @@ impl Db {
                     | DeployKind::Action { .. }
                     | DeployKind::Objective { .. }
                     | DeployKind::ObjectiveDeprecated
+                    | DeployKind::Offensive { .. }
+                    | DeployKind::DownedPilot { .. }
                     | DeployKind::Troop { .. }
                     | DeployKind::Deployed { .. } => None,
                 }
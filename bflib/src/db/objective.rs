@@ -28,7 +28,7 @@ use crate::{
 };
 use anyhow::{Context, Result, anyhow};
 use bfprotocols::{
-    cfg::{Deployable, DeployableObjective, UnitTag, Vehicle, VictoryCondition},
+    cfg::{Deployable, DeployableObjective, TimedCaptureCfg, UnitTag, Vehicle, VictoryCondition},
     db::{
         group::{GroupId, UnitId},
         objective::{ObjectiveId, ObjectiveKind},
@@ -293,6 +293,8 @@ pub struct Objective {
     pub(super) logistics_detached: bool,
     #[serde(default)]
     pub points: i32,
+    #[serde(default)]
+    pub(super) upgrades: Vec<Set<GroupId>>,
     #[serde(skip)]
     pub(super) spawned: bool,
     #[serde(skip)]
@@ -328,14 +330,22 @@ impl Objective {
     pub fn is_farp(&self) -> bool {
         match &self.kind {
             ObjectiveKind::Farp { .. } => true,
-            ObjectiveKind::Airbase | ObjectiveKind::Fob | ObjectiveKind::Logistics => false,
+            ObjectiveKind::Airbase
+            | ObjectiveKind::Fob
+            | ObjectiveKind::Logistics
+            | ObjectiveKind::Port
+            | ObjectiveKind::Carrier => false,
         }
     }
 
     pub fn is_airbase(&self) -> bool {
         match &self.kind {
             ObjectiveKind::Airbase => true,
-            ObjectiveKind::Farp { .. } | ObjectiveKind::Fob | ObjectiveKind::Logistics => false,
+            ObjectiveKind::Farp { .. }
+            | ObjectiveKind::Fob
+            | ObjectiveKind::Logistics
+            | ObjectiveKind::Port
+            | ObjectiveKind::Carrier => false,
         }
     }
 
@@ -356,6 +366,16 @@ impl Objective {
     }
 }
 
+/// The progress of a timed capture of an objective
+#[derive(Debug, Clone)]
+pub(super) struct CaptureProgress {
+    side: Side,
+    progress: f32,
+    contested: bool,
+    announced: u8,
+    last: DateTime<Utc>,
+}
+
 impl Db {
     pub fn objective(&self, id: &ObjectiveId) -> Result<&Objective> {
         objective!(self, id)
@@ -635,6 +655,7 @@ impl Db {
             warehouse: Warehouse::default(),
             logistics_detached: false,
             points: 0,
+            upgrades: vec![],
             last_threatened_ts: now,
             last_change_ts: now,
             last_activate: DateTime::<Utc>::default(),
@@ -872,8 +893,12 @@ impl Db {
         let mut became_threatened: SmallVec<[ObjectiveId; 4]> = smallvec![];
         let mut became_clear: SmallVec<[ObjectiveId; 4]> = smallvec![];
         let cooldown = Duration::seconds(self.ephemeral.cfg.threatened_cooldown as i64);
+        let keep_front_spawned = match cfg.front_line.as_ref() {
+            Some(flcfg) => flcfg.keep_front_spawned,
+            None => false,
+        };
         for (oid, obj) in self.persisted.objectives.iter_mut_cow() {
-            let mut spawn = false;
+            let mut spawn = keep_front_spawned && self.ephemeral.front_line.contains(oid);
             let mut is_threatened = false;
             let pos3 = obj.threat_pos3;
             if let Err(e) = check_close_players(obj, pos3, &mut spawn, &mut is_threatened) {
@@ -1055,7 +1080,8 @@ impl Db {
             .into_iter()
             .filter_map(|(oid, obj)| {
                 let logi = obj.logi as f32 / 100.;
-                let repair_time = self.ephemeral.cfg.repair_time as f32 / logi;
+                let speedup = obj.repair_speedup(&self.ephemeral.cfg) as f32 / 100.;
+                let repair_time = self.ephemeral.cfg.repair_time as f32 * (1. - speedup) / logi;
                 if repair_time < i64::MAX as f32 {
                     let repair_time = Duration::seconds(repair_time as i64);
                     if obj.health < 100 && (now - obj.last_change_ts) >= repair_time {
@@ -1075,9 +1101,13 @@ impl Db {
     }
 
     pub fn capturable_objectives(&self) -> SmallVec<[ObjectiveId; 1]> {
+        let front_only = match self.ephemeral.cfg.front_line.as_ref() {
+            Some(flcfg) => flcfg.capture_front_only,
+            None => false,
+        };
         let mut cap = smallvec![];
         for (oid, obj) in &self.persisted.objectives {
-            if obj.captureable() {
+            if obj.captureable() && (!front_only || obj.is_farp() || self.is_front_line(oid)) {
                 cap.push(*oid)
             }
         }
@@ -1121,17 +1151,152 @@ impl Db {
         })
     }
 
+    /// Advance timed captures, returning the objectives whose capture
+    /// completed this round
+    fn update_capture_progress(
+        &mut self,
+        cfg: &TimedCaptureCfg,
+        now: DateTime<Utc>,
+        rates: &FxHashMap<ObjectiveId, SmallVec<[(Side, f32); 2]>>,
+        present: &FxHashMap<ObjectiveId, SmallVec<[Side; 2]>>,
+    ) -> Result<SmallVec<[ObjectiveId; 1]>> {
+        let capture_time = max(1, cfg.capture_time) as f32;
+        let mut complete = smallvec![];
+        let oids = rates
+            .keys()
+            .chain(self.ephemeral.capture_progress.keys())
+            .copied()
+            .collect::<FxHashSet<_>>();
+        for oid in oids {
+            let obj = match self.persisted.objectives.get(&oid) {
+                Some(obj) => obj,
+                None => {
+                    self.ephemeral.capture_progress.remove(&oid);
+                    continue;
+                }
+            };
+            let name = obj.name.clone();
+            let owner = obj.owner;
+            let attacker = rates.get(&oid).and_then(|r| match &r[..] {
+                [(side, rate)] if self.can_capture(&oid, *side) => Some((*side, *rate)),
+                _ => None,
+            });
+            let contested = present.get(&oid).map(|s| s.len() > 1).unwrap_or(false);
+            let cp = self
+                .ephemeral
+                .capture_progress
+                .entry(oid)
+                .or_insert_with(|| CaptureProgress {
+                    side: attacker.map(|(s, _)| s).unwrap_or(owner),
+                    progress: 0.,
+                    contested: false,
+                    announced: 0,
+                    last: now,
+                });
+            let elapsed = (now - cp.last).as_seconds_f32().max(0.);
+            cp.last = now;
+            let became_contested = contested && !cp.contested;
+            cp.contested = contested;
+            match attacker {
+                None => cp.progress -= cfg.decay_rate * elapsed / capture_time,
+                Some(_) if contested => (),
+                Some((side, rate)) => {
+                    let delta = rate.min(cfg.max_rate) * elapsed / capture_time;
+                    if cp.side == side {
+                        cp.progress += delta
+                    } else {
+                        cp.progress -= delta;
+                        if cp.progress <= 0. {
+                            cp.side = side;
+                            cp.progress = -cp.progress;
+                            cp.announced = 0;
+                        }
+                    }
+                }
+            }
+            let side = cp.side;
+            if cp.progress >= 1. {
+                self.ephemeral.capture_progress.remove(&oid);
+                self.ephemeral.update_capture_markup(&oid, None);
+                complete.push(oid);
+                continue;
+            }
+            if cp.progress <= 0. && attacker.is_none() {
+                self.ephemeral.capture_progress.remove(&oid);
+                self.ephemeral.update_capture_markup(&oid, None);
+                continue;
+            }
+            let pct = (cp.progress.clamp(0., 1.) * 100.) as u8;
+            let quarter = pct / 25;
+            let announce = quarter > cp.announced;
+            if announce {
+                cp.announced = quarter;
+            }
+            self.ephemeral
+                .update_capture_markup(&oid, Some((side, pct, contested)));
+            if became_contested {
+                let msg = format_compact!("the capture of {name} is contested");
+                self.ephemeral
+                    .msgs()
+                    .panel_to_side(10, false, side, msg.clone());
+                self.ephemeral
+                    .msgs()
+                    .panel_to_side(10, false, side.opposite(), msg);
+            } else if announce && side != owner {
+                self.ephemeral.msgs().panel_to_side(
+                    10,
+                    false,
+                    side,
+                    format_compact!("capturing {name}: {pct}%"),
+                );
+                self.ephemeral.msgs().panel_to_side(
+                    10,
+                    false,
+                    side.opposite(),
+                    format_compact!("{name} is being captured: {pct}%"),
+                );
+            }
+        }
+        Ok(complete)
+    }
+
     pub fn check_capture(
         &mut self,
         lua: MizLua,
         now: DateTime<Utc>,
     ) -> Result<SmallVec<[(Side, ObjectiveId); 1]>> {
-        let mut captured: FxHashMap<ObjectiveId, Vec<(Side, Ucid, Option<ObjectiveId>, GroupId)>> =
-            FxHashMap::default();
+        let mut captured: FxHashMap<
+            ObjectiveId,
+            Vec<(Side, Option<Ucid>, Option<ObjectiveId>, GroupId)>,
+        > = FxHashMap::default();
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let mut rates: FxHashMap<ObjectiveId, SmallVec<[(Side, f32); 2]>> = FxHashMap::default();
+        let mut present: FxHashMap<ObjectiveId, SmallVec<[Side; 2]>> = FxHashMap::default();
+        let ignore_dead = cfg
+            .timed_capture
+            .as_ref()
+            .map(|tc| tc.ignore_dead)
+            .unwrap_or(false);
         for (oid, obj) in &self.persisted.objectives {
             if obj.captureable() {
+                let in_zone = |gid: &GroupId| -> Result<usize> {
+                    Ok(group!(self, gid)?
+                        .units
+                        .into_iter()
+                        .filter_map(|uid| self.persisted.units.get(uid))
+                        .filter(|u| !(ignore_dead && u.dead) && obj.zone.contains(u.pos))
+                        .count())
+                };
                 for gid in &self.persisted.troops {
                     let group = group!(self, gid)?;
+                    let n = in_zone(gid)?;
+                    if n == 0 {
+                        continue;
+                    }
+                    let sides = present.entry(*oid).or_default();
+                    if !sides.contains(&group.side) {
+                        sides.push(group.side);
+                    }
                     match &group.origin {
                         DeployKind::Troop {
                             spec,
@@ -1139,34 +1304,85 @@ impl Db {
                             origin,
                             moved_by: _,
                             cost_fraction: _,
-                        } if spec.can_capture => {
-                            let in_range = group
-                                .units
-                                .into_iter()
-                                .filter_map(|uid| self.persisted.units.get(uid))
-                                .any(|u| obj.zone.contains(u.pos));
-                            if in_range {
-                                captured
-                                    .entry(*oid)
-                                    .or_default()
-                                    .push((group.side, *player, *origin, *gid));
+                        } if spec.can_capture && !self.in_enemy_minefield(gid) => {
+                            captured.entry(*oid).or_default().push((
+                                group.side,
+                                Some(*player),
+                                *origin,
+                                *gid,
+                            ));
+                            if let Some(tc) = cfg.timed_capture.as_ref() {
+                                let rate = tc.troop_rate.get(&spec.name).copied().unwrap_or(1.);
+                                let rates = rates.entry(*oid).or_default();
+                                match rates.iter_mut().find(|(s, _)| *s == group.side) {
+                                    Some((_, r)) => *r += rate * n as f32,
+                                    None => rates.push((group.side, rate * n as f32)),
+                                }
                             }
                         }
                         DeployKind::Crate { .. }
                         | DeployKind::Deployed { .. }
                         | DeployKind::Objective { .. }
                         | DeployKind::ObjectiveDeprecated
+                        | DeployKind::Offensive { .. }
+                        | DeployKind::DownedPilot { .. }
                         | DeployKind::Action { .. }
                         | DeployKind::Troop { .. } => (),
                     }
                 }
+                for gid in &self.persisted.offensives {
+                    let group = group!(self, gid)?;
+                    let n = in_zone(gid)?;
+                    if n == 0 {
+                        continue;
+                    }
+                    let sides = present.entry(*oid).or_default();
+                    if !sides.contains(&group.side) {
+                        sides.push(group.side);
+                    }
+                    if let DeployKind::Offensive { origin, .. } = &group.origin {
+                        captured.entry(*oid).or_default().push((
+                            group.side,
+                            None,
+                            Some(*origin),
+                            *gid,
+                        ));
+                        if cfg.timed_capture.is_some() {
+                            let rate = cfg
+                                .ground_offensive
+                                .as_ref()
+                                .map(|o| o.capture_rate)
+                                .unwrap_or(1.);
+                            let rates = rates.entry(*oid).or_default();
+                            match rates.iter_mut().find(|(s, _)| *s == group.side) {
+                                Some((_, r)) => *r += rate * n as f32,
+                                None => rates.push((group.side, rate * n as f32)),
+                            }
+                        }
+                    }
+                }
+                if cfg.timed_capture.is_some() {
+                    for gid in &self.persisted.deployed {
+                        let group = group!(self, gid)?;
+                        if in_zone(gid)? > 0 {
+                            let sides = present.entry(*oid).or_default();
+                            if !sides.contains(&group.side) {
+                                sides.push(group.side);
+                            }
+                        }
+                    }
+                }
             }
         }
+        if let Some(tc) = cfg.timed_capture.as_ref() {
+            let complete = self.update_capture_progress(tc, now, &rates, &present)?;
+            captured.retain(|oid, _| complete.contains(oid));
+        }
         let mut actually_captured = smallvec![];
         let mut to_mark: SmallVec<[GroupId; 32]> = smallvec![];
         for (oid, gids) in captured {
             let (side, _, _, _) = gids.first().ok_or_else(|| anyhow!("no guid"))?;
-            if gids.iter().all(|(s, _, _, _)| side == s) {
+            if gids.iter().all(|(s, _, _, _)| side == s) && self.can_capture(&oid, *side) {
                 let obj = objective_mut!(self, oid)?;
                 let name = obj.name.clone();
                 let previous_owner = obj.owner;
@@ -1192,20 +1408,20 @@ impl Db {
                         }
                     }
                 }
-                let abid = self
-                    .ephemeral
-                    .airbase_by_oid
-                    .get(&oid)
-                    .ok_or_else(|| anyhow!("no airbase for objective {}", obj.name))?;
-                let airbase =
-                    Airbase::get_instance(lua, abid).context("getting captured airbase")?;
-                airbase
-                    .set_coalition(*side)
-                    .context("setting airbase coalition")?;
+                // ports have no airbase
+                if let Some(abid) = self.ephemeral.airbase_by_oid.get(&oid) {
+                    let airbase =
+                        Airbase::get_instance(lua, abid).context("getting captured airbase")?;
+                    airbase
+                        .set_coalition(*side)
+                        .context("setting airbase coalition")?;
+                }
                 self.repair_one_logi_step(*side, now, oid)
                     .context("repairing captured airbase logi")?;
                 self.repair_services(*side, now, oid)
                     .context("repairing captured airbase services")?;
+                self.downgrade_captured_objective(oid)
+                    .context("downgrading captured objective")?;
                 self.capture_warehouse(lua, oid)
                     .context("capturing warehouse")?;
                 self.setup_supply_lines().context("setup supply lines")?;
@@ -1215,10 +1431,11 @@ impl Db {
                 for (_, ucid, troop_origin, gid) in gids {
                     self.delete_group(&gid)
                         .context("deleting capturing troops")?;
-                    if previous_owner != new_owner || troop_origin != Some(oid) {
-                        if !ucids.contains(&ucid) {
-                            ucids.push(ucid);
-                        }
+                    if let Some(ucid) = ucid
+                        && (previous_owner != new_owner || troop_origin != Some(oid))
+                        && !ucids.contains(&ucid)
+                    {
+                        ucids.push(ucid);
                     }
                 }
                 self.ephemeral.stat(Stat::Capture {
@@ -1238,11 +1455,13 @@ impl Db {
             }
         }
         if actually_captured.len() > 0 {
+            self.update_front_line();
             self.ephemeral.logistics_stage = LogiStage::SyncToWarehouses {
                 objectives: self
                     .persisted
                     .objectives
                     .into_iter()
+                    .filter(|(_, obj)| obj.kind.has_warehouse())
                     .map(|(oid, _)| *oid)
                     .collect(),
             };
@@ -0,0 +1,212 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{Db, group::DeployKind};
+use crate::{
+    group, objective,
+    spawnctx::{SpawnCtx, SpawnLoc},
+};
+use anyhow::{Context, Result, anyhow};
+use bfprotocols::{
+    cfg::GroundOffensiveCfg,
+    db::{group::GroupId, objective::ObjectiveId},
+};
+use chrono::{Duration, prelude::*};
+use compact_str::format_compact;
+use dcso3::{MizLua, azumith2d_to, controller::VehicleFormation, env::miz::MizIndex};
+use enumflags2::BitFlags;
+use fxhash::FxHashMap;
+use log::{error, info};
+use smallvec::{SmallVec, smallvec};
+use std::{cmp::max, mem, sync::Arc};
+
+/// The runtime state of the AI ground offensives. The offensive groups
+/// themselves are persisted.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Offensives {
+    last_launch: FxHashMap<ObjectiveId, DateTime<Utc>>,
+    /// offensive groups that need their route once they have spawned
+    pub(super) pending_moves: Vec<GroupId>,
+}
+
+impl Db {
+    /// The objective and adjacent enemy objective an offensive should
+    /// be launched from and toward, if any
+    fn offensive_candidate(
+        &mut self,
+        cfg: &GroundOffensiveCfg,
+        now: DateTime<Utc>,
+    ) -> Option<(ObjectiveId, ObjectiveId)> {
+        let max_dist = (cfg.max_distance as f64).powi(2);
+        let mut active: FxHashMap<_, u32> = FxHashMap::default();
+        for gid in &self.persisted.offensives {
+            if let Some(group) = self.persisted.groups.get(gid) {
+                *active.entry(group.side).or_default() += 1;
+            }
+        }
+        for (oid, obj) in &self.persisted.objectives {
+            if obj.is_farp()
+                || !cfg.template.contains_key(&obj.owner)
+                || active.get(&obj.owner).copied().unwrap_or(0) >= cfg.max_active
+                || !self.is_front_line(oid)
+                || obj.supply < cfg.min_supply
+                || obj.fuel < cfg.min_fuel
+            {
+                continue;
+            }
+            // a well supplied objective launches offensives more often
+            let interval = cfg.interval as i64 * 100 / max(1, obj.supply) as i64;
+            let last = *self
+                .ephemeral
+                .offensives
+                .last_launch
+                .entry(*oid)
+                .or_insert(now);
+            if now - last < Duration::minutes(interval) {
+                continue;
+            }
+            let pos = obj.zone.pos();
+            let target = self
+                .ephemeral
+                .front_line
+                .adjacent(oid)
+                .iter()
+                .filter_map(|aid| self.persisted.objectives.get(aid).map(|a| (aid, a)))
+                .filter(|(aid, adj)| adj.owner != obj.owner && self.can_capture(aid, obj.owner))
+                .map(|(aid, adj)| {
+                    let dist = na::distance_squared(&pos.into(), &adj.zone.pos().into());
+                    (*aid, dist)
+                })
+                .filter(|(_, dist)| *dist <= max_dist)
+                .min_by(|(_, d0), (_, d1)| d0.total_cmp(d1));
+            if let Some((target, _)) = target {
+                return Some((*oid, target));
+            }
+        }
+        None
+    }
+
+    fn launch_offensive(
+        &mut self,
+        lua: MizLua,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        cfg: &GroundOffensiveCfg,
+        origin: ObjectiveId,
+        target: ObjectiveId,
+        now: DateTime<Utc>,
+    ) -> Result<GroupId> {
+        let obj = objective!(self, origin)?;
+        let side = obj.owner;
+        let pos = obj.zone.pos();
+        let tpos = objective!(self, target)?.zone.pos();
+        let template = cfg
+            .template
+            .get(&side)
+            .ok_or_else(|| anyhow!("no offensive template for {side}"))?;
+        let location = SpawnLoc::AtPos {
+            pos,
+            offset_direction: (tpos - pos).normalize(),
+            group_heading: azumith2d_to(pos, tpos),
+        };
+        let gid = self
+            .add_and_queue_group(
+                spctx,
+                idx,
+                side,
+                location,
+                template,
+                DeployKind::Offensive { origin, target },
+                BitFlags::empty(),
+                None,
+            )
+            .context("adding offensive group")?;
+        if let Err(e) = self.reduce_inventory(lua, origin, cfg.supply_cost) {
+            self.delete_group(&gid)?;
+            return Err(e.context("consuming offensive supplies"));
+        }
+        self.ephemeral.offensives.last_launch.insert(origin, now);
+        self.ephemeral.offensives.pending_moves.push(gid);
+        Ok(gid)
+    }
+
+    /// Give offensive groups that have spawned their route to the
+    /// target, and disband offensives whose target is no longer held
+    /// by the enemy
+    fn advance_offensives(&mut self, spctx: &SpawnCtx) -> Result<()> {
+        let mut disband: SmallVec<[GroupId; 4]> = smallvec![];
+        for gid in &self.persisted.offensives {
+            let group = group!(self, gid)?;
+            if let DeployKind::Offensive { target, .. } = &group.origin
+                && objective!(self, target)?.owner == group.side
+            {
+                disband.push(*gid)
+            }
+        }
+        for gid in disband {
+            self.delete_group(&gid)?
+        }
+        for gid in mem::take(&mut self.ephemeral.offensives.pending_moves) {
+            let target = match self.persisted.groups.get(&gid).map(|g| &g.origin) {
+                Some(DeployKind::Offensive { target, .. }) => *target,
+                Some(_) | None => continue,
+            };
+            if !self.ephemeral.object_id_by_gid.contains_key(&gid) {
+                self.ephemeral.offensives.pending_moves.push(gid);
+                continue;
+            }
+            let pos = objective!(self, target)?.zone.pos();
+            if let Err(e) = self.ground_move_mission(spctx, gid, pos, VehicleFormation::OnRoad) {
+                error!("failed to route offensive group {gid} {e:?}")
+            }
+        }
+        Ok(())
+    }
+
+    /// Launch AI ground offensives from well supplied front line
+    /// objectives toward adjacent enemy objectives
+    pub fn run_ground_offensives(
+        &mut self,
+        lua: MizLua,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let ocfg = match cfg.ground_offensive.as_ref() {
+            Some(ocfg) => ocfg,
+            None => return Ok(()),
+        };
+        self.advance_offensives(spctx)?;
+        if let Some((origin, target)) = self.offensive_candidate(ocfg, now) {
+            let gid = self.launch_offensive(lua, spctx, idx, ocfg, origin, target, now)?;
+            let from = objective!(self, origin)?;
+            let to = objective!(self, target)?;
+            let side = from.owner;
+            info!(
+                "{side} launched offensive {gid} from {} toward {}",
+                from.name, to.name
+            );
+            let msg = format_compact!("{} launched an offensive toward {}", from.name, to.name);
+            self.ephemeral.msgs.panel_to_side(10, false, side, msg);
+            let msg = format_compact!("enemy armor is advancing on {}", to.name);
+            self.ephemeral
+                .msgs
+                .panel_to_side(10, false, side.opposite(), msg);
+        }
+        Ok(())
+    }
+}
@@ -15,9 +15,13 @@ for more details.
 */
 
 use super::{
+    actionqueue::QueuedAction,
     group::{SpawnedGroup, SpawnedUnit},
+    logistics::SupplyRequest,
+    minefield::Minefield,
     objective::Objective,
     player::Player,
+    throttle::ActionUses,
     Map, MapM, MapS, Set, SetM, SetS,
 };
 use bfprotocols::db::{
@@ -42,6 +46,10 @@ pub struct Persisted {
     pub ewrs: SetS<GroupId>,
     #[serde(default)]
     pub actions: SetS<GroupId>,
+    #[serde(default)]
+    pub offensives: SetS<GroupId>,
+    #[serde(default)]
+    pub downed_pilots: SetS<GroupId>,
     pub objectives: MapM<ObjectiveId, Objective>,
     pub objectives_by_name: MapM<String, ObjectiveId>,
     pub objectives_by_group: MapM<GroupId, ObjectiveId>,
@@ -49,8 +57,20 @@ pub struct Persisted {
     #[serde(default)]
     pub logistics_hubs: SetS<ObjectiveId>,
     #[serde(default)]
+    pub supply_requests: MapS<ObjectiveId, SupplyRequest>,
+    #[serde(default)]
     pub nukes_used: u32,
     #[serde(default)]
+    pub ai_budget: MapS<Side, u32>,
+    #[serde(default)]
+    pub action_queue: MapS<u64, QueuedAction>,
+    #[serde(default)]
+    pub minefields: MapS<u64, Minefield>,
+    #[serde(default)]
+    pub action_uses: MapS<Side, MapS<String, ActionUses>>,
+    #[serde(default)]
+    pub command_budget: MapS<Side, f64>,
+    #[serde(default)]
     pub logistics_ticks_since_delivery: u32,
     #[serde(default)]
     pub oid: i64,
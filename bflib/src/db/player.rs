@@ -19,7 +19,10 @@ use crate::{maybe, maybe_mut, objective_mut};
 use anyhow::{Context, Result, anyhow, bail};
 use bfprotocols::{
     cfg::{LifeType, PointsCfg, UnitTag, Vehicle},
-    db::{group::GroupId, objective::ObjectiveId},
+    db::{
+        group::GroupId,
+        objective::{ObjectiveId, ObjectiveKind},
+    },
     shots::{Dead, Who},
     stats::{self, EnId, Stat},
 };
@@ -60,6 +63,21 @@ pub enum SlotAuth {
     },
     NotRegistered(Side),
     VehicleNotAvailable(Vehicle),
+    LowFuel {
+        life_type: LifeType,
+        fuel: u8,
+        required: u8,
+    },
+    LowSupply {
+        life_type: LifeType,
+        supply: u8,
+        required: u8,
+    },
+    UpgradeRequired(u8),
+    CarrierDamaged {
+        health: u8,
+        required: u8,
+    },
     Denied,
 }
 
@@ -74,6 +92,11 @@ pub enum TakeoffRes {
     NoLifeTaken,
     OutOfLives,
     OutOfPoints,
+    WeaponRestricted {
+        weapon: String,
+        supply: u8,
+        required: u8,
+    },
 }
 
 #[derive(Debug, Clone, Default)]
@@ -258,7 +281,9 @@ impl Db {
                             DeployKind::Action { player, .. } => player.clone(),
                             DeployKind::Crate { .. }
                             | DeployKind::Objective { .. }
-                            | DeployKind::ObjectiveDeprecated => None,
+                            | DeployKind::ObjectiveDeprecated
+                            | DeployKind::Offensive { .. }
+                            | DeployKind::DownedPilot { .. } => None,
                         })
                 }
             }
@@ -337,6 +362,26 @@ impl Db {
         if let Some((_, Some(inst))) = &mut player.current_slot {
             inst.landed_at_objective = None;
         }
+        if let Some(whcfg) = self.ephemeral.cfg.warehouse.as_ref()
+            && !whcfg.weapon_min_supply.is_empty()
+            && let Some((_, obj)) = owned_objective.as_ref()
+        {
+            for ammo in unit.get_ammo().context("getting ammo")? {
+                let typ = ammo
+                    .context("unwrapping ammo")?
+                    .type_name()
+                    .context("getting ammo type name")?;
+                if let Some(required) = whcfg.weapon_min_supply.get(&typ)
+                    && obj.supply < *required
+                {
+                    return Ok(TakeoffRes::WeaponRestricted {
+                        weapon: typ,
+                        supply: obj.supply,
+                        required: *required,
+                    });
+                }
+            }
+        }
         let obj_balance = owned_objective.as_ref().map(|(_, o)| o.points).unwrap_or(0);
         let res = if strict && cost as i32 > max(0, player.points) + obj_balance {
             return Ok(TakeoffRes::OutOfPoints);
@@ -453,6 +498,7 @@ impl Db {
             }
         });
         self.ephemeral.stat(Stat::Land { id: ucid });
+        let side = player.side;
         if let Some(oid) = owned_objective {
             *player_lives += 1;
             player.airborne = None;
@@ -482,6 +528,7 @@ impl Db {
                     );
                 }
             }
+            self.deliver_recon_intel(&ucid, side, Utc::now());
             self.ephemeral.dirty();
             if !self.ephemeral.cfg.limited_lives {
                 None
@@ -612,7 +659,46 @@ impl Db {
         if objective.captureable() {
             return SlotAuth::ObjectiveHasNoLogistics;
         }
+        if let ObjectiveKind::Carrier = objective.kind
+            && objective.health < self.ephemeral.cfg.carrier_min_health
+        {
+            return SlotAuth::CarrierDamaged {
+                health: objective.health,
+                required: self.ephemeral.cfg.carrier_min_health,
+            };
+        }
+        if let Some(ucfg) = self.ephemeral.cfg.objective_upgrades.as_ref()
+            && let Some(i) = ucfg
+                .tiers
+                .iter()
+                .position(|t| t.slots.contains(sifo.unit_name.as_str()))
+            && objective.tier() <= i as u8
+        {
+            return SlotAuth::UpgradeRequired(i as u8 + 1);
+        }
         let life_type = self.ephemeral.cfg.life_types[&sifo.typ];
+        if let Some(whcfg) = self.ephemeral.cfg.warehouse.as_ref()
+            && !whcfg.exempt_airframes.contains(sifo.typ.as_str())
+        {
+            if let Some(required) = whcfg.min_fuel.get(&life_type)
+                && objective.fuel < *required
+            {
+                return SlotAuth::LowFuel {
+                    life_type,
+                    fuel: objective.fuel,
+                    required: *required,
+                };
+            }
+            if let Some(required) = whcfg.min_supply.get(&life_type)
+                && objective.supply < *required
+            {
+                return SlotAuth::LowSupply {
+                    life_type,
+                    supply: objective.supply,
+                    required: *required,
+                };
+            }
+        }
         macro_rules! yes {
             () => {
                 if let Some(whcfg) = self.ephemeral.cfg.warehouse.as_ref() {
@@ -0,0 +1,277 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{Db, group::DeployKind};
+use crate::landcache::LandCache;
+use anyhow::Result;
+use bfprotocols::{
+    cfg::{ActionKind, LifeType},
+    db::group::GroupId,
+    stats::{DetectionSource, EnId, Stat},
+};
+use chrono::{Duration, prelude::*};
+use compact_str::format_compact;
+use dcso3::{MizLua, Vector3, centroid2d, coalition::Side, land::Land, net::Ucid, trigger::MarkId};
+use fxhash::{FxHashMap, FxHashSet};
+use smallvec::{SmallVec, smallvec};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy)]
+struct ReconMark {
+    id: MarkId,
+    expires: DateTime<Utc>,
+}
+
+/// The runtime state of recon. Neither the marks nor undelivered
+/// player intel survive a restart.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Recon {
+    /// enemy groups currently marked for each side
+    marks: FxHashMap<Side, FxHashMap<GroupId, ReconMark>>,
+    /// enemy groups spotted by players that have not been delivered yet
+    intel: FxHashMap<Ucid, FxHashSet<GroupId>>,
+}
+
+impl Recon {
+    /// The enemy groups currently marked by each side
+    pub(super) fn marked(&self) -> impl Iterator<Item = (Side, GroupId)> + '_ {
+        self.marks
+            .iter()
+            .flat_map(|(side, marks)| marks.keys().map(move |gid| (*side, *gid)))
+    }
+}
+
+struct ReconSource {
+    side: Side,
+    pos: Vector3,
+    range: f64,
+    mark_duration: u32,
+    player: Option<Ucid>,
+}
+
+impl Db {
+    /// Enemy ground groups with at least one unit within range of pos
+    /// and in line of sight of it
+    fn recon_visible_groups(
+        &self,
+        land: &Land,
+        landcache: &mut LandCache,
+        side: Side,
+        pos: Vector3,
+        range: f64,
+    ) -> Result<SmallVec<[GroupId; 8]>> {
+        let range2 = range.powi(2);
+        let mut found: SmallVec<[GroupId; 8]> = smallvec![];
+        let enemy = match self.persisted.groups_by_side.get(&side.opposite()) {
+            Some(groups) => groups,
+            None => return Ok(found),
+        };
+        for gid in enemy {
+            let group = match self.persisted.groups.get(gid) {
+                Some(group) => group,
+                None => continue,
+            };
+            for uid in &group.units {
+                let unit = match self.persisted.units.get(uid) {
+                    Some(unit) if !unit.dead && unit.airborne_velocity.is_none() => unit,
+                    _ => continue,
+                };
+                let mut upos = unit.position.p.0;
+                upos.y += 2.;
+                let dist = na::distance_squared(&pos.into(), &upos.into());
+                if dist <= range2 && landcache.is_visible(land, dist.sqrt(), pos, upos)? {
+                    found.push(*gid);
+                    break;
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    fn mark_recon_group(&mut self, side: Side, gid: GroupId, duration: u32, now: DateTime<Utc>) {
+        let expires = now + Duration::minutes(duration as i64);
+        let marks = self.ephemeral.recon.marks.entry(side).or_default();
+        if let Some(mark) = marks.get_mut(&gid) {
+            mark.expires = std::cmp::max(mark.expires, expires);
+            return;
+        }
+        let group = match self.persisted.groups.get(&gid) {
+            Some(group) => group,
+            None => return,
+        };
+        let pos = centroid2d(
+            group
+                .units
+                .into_iter()
+                .filter_map(|uid| self.persisted.units.get(uid))
+                .map(|u| u.pos),
+        );
+        let msg = format_compact!("recon: enemy {} {gid}", group.template_name);
+        let id = self.ephemeral.msgs.mark_to_side(side, pos, true, msg);
+        marks.insert(gid, ReconMark { id, expires });
+        for uid in &group.units {
+            self.ephemeral.stat(Stat::Detected {
+                id: EnId::Unit(*uid),
+                detected: true,
+                source: DetectionSource::Recon,
+            })
+        }
+    }
+
+    /// Deliver the intel a player gathered on their recon flight. Called
+    /// when they land at a friendly objective.
+    pub(super) fn deliver_recon_intel(&mut self, ucid: &Ucid, side: Side, now: DateTime<Utc>) {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let rcfg = match cfg.player_recon.as_ref() {
+            Some(rcfg) => rcfg,
+            None => return,
+        };
+        let intel = match self.ephemeral.recon.intel.remove(ucid) {
+            Some(intel) => intel,
+            None => return,
+        };
+        let mut n = 0;
+        for gid in intel {
+            if self.persisted.groups.get(&gid).is_some() {
+                self.mark_recon_group(side, gid, rcfg.mark_duration, now);
+                n += 1;
+            }
+        }
+        if n == 0 {
+            return;
+        }
+        let name = self
+            .persisted
+            .players
+            .get(ucid)
+            .map(|p| p.name.clone())
+            .unwrap_or_default();
+        let msg = format_compact!("{name} delivered recon intel on {n} enemy groups");
+        self.ephemeral.msgs.panel_to_side(10, false, side, msg);
+        if rcfg.points > 0 {
+            self.adjust_points(ucid, (n * rcfg.points) as i32, "for recon intel");
+        }
+    }
+
+    /// Spot enemy groups from ai and player recon aircraft, and expire
+    /// old recon marks
+    pub fn run_recon(
+        &mut self,
+        lua: MizLua,
+        landcache: &mut LandCache,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let mut expired: SmallVec<[(MarkId, GroupId); 8]> = smallvec![];
+        for marks in self.ephemeral.recon.marks.values_mut() {
+            marks.retain(|gid, mark| {
+                if mark.expires > now && self.persisted.groups.get(gid).is_some() {
+                    true
+                } else {
+                    expired.push((mark.id, *gid));
+                    false
+                }
+            })
+        }
+        for (id, gid) in expired {
+            self.ephemeral.msgs.delete_mark(id);
+            if let Some(group) = self.persisted.groups.get(&gid) {
+                for uid in &group.units {
+                    self.ephemeral.stat(Stat::Detected {
+                        id: EnId::Unit(*uid),
+                        detected: false,
+                        source: DetectionSource::Recon,
+                    })
+                }
+            }
+        }
+        let mut sources: SmallVec<[ReconSource; 8]> = smallvec![];
+        for gid in &self.persisted.actions {
+            let group = match self.persisted.groups.get(gid) {
+                Some(group) => group,
+                None => continue,
+            };
+            let rcfg = match &group.origin {
+                DeployKind::Action { spec, .. } => match &spec.kind {
+                    ActionKind::Recon(rcfg) => rcfg,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            for uid in &group.units {
+                if let Some(unit) = self.persisted.units.get(uid)
+                    && !unit.dead
+                    && unit.airborne_velocity.is_some()
+                {
+                    sources.push(ReconSource {
+                        side: group.side,
+                        pos: unit.position.p.0,
+                        range: rcfg.range as f64,
+                        mark_duration: rcfg.mark_duration,
+                        player: None,
+                    })
+                }
+            }
+        }
+        let mut flying: FxHashSet<Ucid> = FxHashSet::default();
+        if let Some(rcfg) = cfg.player_recon.as_ref() {
+            for (ucid, player, inst) in self.instanced_players() {
+                flying.insert(*ucid);
+                if inst.in_air && cfg.life_types.get(&inst.typ) == Some(&LifeType::Recon) {
+                    sources.push(ReconSource {
+                        side: player.side,
+                        pos: inst.position.p.0,
+                        range: rcfg.range as f64,
+                        mark_duration: rcfg.mark_duration,
+                        player: Some(*ucid),
+                    })
+                }
+            }
+        }
+        // intel is lost along with the aircraft carrying it
+        self.ephemeral
+            .recon
+            .intel
+            .retain(|ucid, _| flying.contains(ucid));
+        if sources.is_empty() {
+            return Ok(());
+        }
+        let land = Land::singleton(lua)?;
+        for src in sources {
+            let groups =
+                self.recon_visible_groups(&land, landcache, src.side, src.pos, src.range)?;
+            match src.player {
+                None => {
+                    for gid in groups {
+                        self.mark_recon_group(src.side, gid, src.mark_duration, now)
+                    }
+                }
+                Some(ucid) => {
+                    let intel = self.ephemeral.recon.intel.entry(ucid).or_default();
+                    let n = groups.into_iter().filter(|gid| intel.insert(*gid)).count();
+                    if n > 0 {
+                        let msg = format_compact!(
+                            "recon: spotted {n} new enemy groups, land at a friendly objective to deliver the intel"
+                        );
+                        self.ephemeral
+                            .panel_to_player(&self.persisted, 10, &ucid, msg)
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
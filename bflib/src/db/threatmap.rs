@@ -0,0 +1,272 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::Db;
+use bfprotocols::{
+    cfg::{ThreatMapCfg, UnitTag},
+    db::group::GroupId,
+    stats::EnId,
+};
+use chrono::prelude::*;
+use dcso3::{
+    Color, LuaVec3, Vector2, Vector3, centroid2d,
+    coalition::Side,
+    trigger::{CircleSpec, LineType, MarkId, SideFilter},
+};
+use fxhash::{FxHashMap, FxHashSet};
+use smallvec::{SmallVec, smallvec};
+use std::sync::Arc;
+
+/// Rings are redrawn when their center moves further than this
+/// (Meters)
+const MOVE_THRESHOLD: f64 = 500.;
+
+/// The alpha of a fresh ring in percent
+const FULL_ALPHA: u8 = 75;
+
+#[derive(Debug, Clone, Copy)]
+struct Ring {
+    id: MarkId,
+    pos: Vector2,
+    radius: f64,
+    alpha: u8,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SamIntel {
+    ring: Ring,
+    last_seen: DateTime<Utc>,
+}
+
+/// The F10 threat map layers. Each side sees its own ewr coverage,
+/// and the rings of the enemy sam groups it has spotted. Rings fade as
+/// intel goes stale. None of this survives a restart.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ThreatMap {
+    ewr: FxHashMap<GroupId, Ring>,
+    sam: FxHashMap<(Side, GroupId), SamIntel>,
+}
+
+fn ewr_color(alpha: u8) -> Color {
+    Color::green(alpha as f32 / 100.)
+}
+
+fn sam_color(alpha: u8) -> Color {
+    Color::red(alpha as f32 / 100.)
+}
+
+impl Db {
+    fn draw_ring(&mut self, to: Side, pos: Vector2, radius: f64, color: Color) -> MarkId {
+        let id = MarkId::new();
+        self.ephemeral.msgs.circle_to_all(
+            SideFilter::from(to),
+            id,
+            CircleSpec {
+                center: LuaVec3(Vector3::new(pos.x, 0., pos.y)),
+                radius,
+                color,
+                fill_color: Color::white(0.),
+                line_type: LineType::Dashed,
+                read_only: true,
+            },
+            None,
+        );
+        id
+    }
+
+    /// The center and engagement radius of the living sam units in a
+    /// group, or None if it has none
+    fn sam_ring(&self, gid: &GroupId) -> Option<(Vector2, f64)> {
+        let group = self.persisted.groups.get(gid)?;
+        let cfg = &self.ephemeral.cfg;
+        let mut radius = 0.;
+        let mut points: SmallVec<[Vector2; 16]> = smallvec![];
+        for uid in &group.units {
+            let unit = match self.persisted.units.get(uid) {
+                Some(unit) if !unit.dead => unit,
+                Some(_) | None => continue,
+            };
+            if unit
+                .tags
+                .intersects(UnitTag::LR | UnitTag::MR | UnitTag::SR)
+            {
+                if let Some(dist) = cfg.threatened_distance.get(&unit.typ) {
+                    radius = f64::max(radius, *dist as f64);
+                    points.push(unit.pos);
+                }
+            }
+        }
+        if points.is_empty() || radius <= 0. {
+            None
+        } else {
+            Some((centroid2d(points), radius))
+        }
+    }
+
+    fn update_ewr_coverage(&mut self) {
+        let mut present: FxHashSet<GroupId> = FxHashSet::default();
+        let ewrs: SmallVec<[(GroupId, Vector2, Side, f64); 32]> = self
+            .ewrs()
+            .map(|(gid, pos, side, ewr)| (gid, Vector2::new(pos.x, pos.z), side, ewr.range as f64))
+            .collect();
+        for (gid, pos, side, radius) in ewrs {
+            present.insert(gid);
+            if let Some(ring) = self.ephemeral.threat_map.ewr.get(&gid) {
+                let moved = na::distance(&ring.pos.into(), &pos.into()) > MOVE_THRESHOLD;
+                if !moved && ring.radius == radius {
+                    continue;
+                }
+                self.ephemeral.msgs.delete_mark(ring.id);
+            }
+            let id = self.draw_ring(side, pos, radius, ewr_color(FULL_ALPHA));
+            let ring = Ring {
+                id,
+                pos,
+                radius,
+                alpha: FULL_ALPHA,
+            };
+            self.ephemeral.threat_map.ewr.insert(gid, ring);
+        }
+        let msgs = &mut self.ephemeral.msgs;
+        self.ephemeral.threat_map.ewr.retain(|gid, ring| {
+            present.contains(gid) || {
+                msgs.delete_mark(ring.id);
+                false
+            }
+        });
+    }
+
+    fn update_sam_rings(
+        &mut self,
+        cfg: &ThreatMapCfg,
+        now: DateTime<Utc>,
+        spotted: FxHashSet<(Side, GroupId)>,
+    ) {
+        let stale = (cfg.stale_after * 60) as f64;
+        let expire = (cfg.expire_after * 60) as f64;
+        let keys: SmallVec<[(Side, GroupId); 64]> = self
+            .ephemeral
+            .threat_map
+            .sam
+            .keys()
+            .copied()
+            .chain(spotted.iter().copied())
+            .collect::<FxHashSet<_>>()
+            .into_iter()
+            .collect();
+        for (side, gid) in keys {
+            let seen = spotted.contains(&(side, gid));
+            let geometry = self.sam_ring(&gid);
+            let intel = self.ephemeral.threat_map.sam.get(&(side, gid)).copied();
+            let (pos, radius) = match (geometry, intel) {
+                (None, None) => continue,
+                (None, Some(intel)) => {
+                    // the site was destroyed
+                    self.ephemeral.msgs.delete_mark(intel.ring.id);
+                    self.ephemeral.threat_map.sam.remove(&(side, gid));
+                    continue;
+                }
+                (Some(g), _) => g,
+            };
+            let last_seen = match (seen, intel) {
+                (true, _) => now,
+                (false, Some(intel)) => intel.last_seen,
+                (false, None) => continue,
+            };
+            let age = (now - last_seen).num_seconds() as f64;
+            if age >= expire {
+                if let Some(intel) = intel {
+                    self.ephemeral.msgs.delete_mark(intel.ring.id);
+                }
+                self.ephemeral.threat_map.sam.remove(&(side, gid));
+                continue;
+            }
+            let alpha = if age <= stale {
+                FULL_ALPHA
+            } else {
+                let fade = 1. - (age - stale) / (expire - stale).max(1.);
+                // fade in steps of 5% so the ring isn't updated every tick
+                (((FULL_ALPHA as f64 * fade) / 5.).round() * 5.) as u8
+            };
+            // a ring only moves when the site is spotted again
+            let pos = match intel {
+                Some(intel) if !seen => intel.ring.pos,
+                Some(_) | None => pos,
+            };
+            let ring = match intel {
+                Some(intel)
+                    if intel.ring.radius == radius
+                        && na::distance(&intel.ring.pos.into(), &pos.into()) <= MOVE_THRESHOLD =>
+                {
+                    if intel.ring.alpha != alpha {
+                        self.ephemeral
+                            .msgs
+                            .set_markup_color(intel.ring.id, sam_color(alpha));
+                    }
+                    Ring {
+                        alpha,
+                        ..intel.ring
+                    }
+                }
+                Some(_) | None => {
+                    if let Some(intel) = intel {
+                        self.ephemeral.msgs.delete_mark(intel.ring.id);
+                    }
+                    let id = self.draw_ring(side, pos, radius, sam_color(alpha));
+                    Ring {
+                        id,
+                        pos,
+                        radius,
+                        alpha,
+                    }
+                }
+            };
+            self.ephemeral
+                .threat_map
+                .sam
+                .insert((side, gid), SamIntel { ring, last_seen });
+        }
+    }
+
+    /// Update the F10 threat map. Spotted is the set of enemy units
+    /// each side's jtacs can currently see, recon marks are also
+    /// counted as spotted.
+    pub fn update_threat_map(
+        &mut self,
+        now: DateTime<Utc>,
+        spotted: impl IntoIterator<Item = (Side, EnId)>,
+    ) {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let tmcfg = match cfg.threat_map.as_ref() {
+            Some(tmcfg) => tmcfg,
+            None => return,
+        };
+        if tmcfg.ewr_coverage {
+            self.update_ewr_coverage();
+        }
+        if tmcfg.sam_rings {
+            let mut groups: FxHashSet<(Side, GroupId)> = self.ephemeral.recon.marked().collect();
+            for (side, id) in spotted {
+                if let EnId::Unit(uid) = id {
+                    if let Some(unit) = self.persisted.units.get(&uid) {
+                        groups.insert((side, unit.group));
+                    }
+                }
+            }
+            self.update_sam_rings(tmcfg, now, groups);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{Db, MapS};
+use anyhow::{Result, bail};
+use bfprotocols::cfg::ActionThrottle;
+use chrono::{Duration, prelude::*};
+use compact_str::{CompactString, format_compact};
+use dcso3::{String, coalition::Side, net::Ucid};
+use serde_derive::{Deserialize, Serialize};
+use smallvec::{SmallVec, smallvec};
+use std::sync::Arc;
+
+/// When an action was used, for enforcing cooldowns and rolling
+/// window limits
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActionUses {
+    /// the last time anyone on the side used the action
+    #[serde(default)]
+    pub last: Option<DateTime<Utc>>,
+    /// the last time each player used the action
+    #[serde(default)]
+    pub by_player: MapS<Ucid, DateTime<Utc>>,
+    /// uses inside the rolling window, oldest first
+    #[serde(default)]
+    pub recent: Vec<DateTime<Utc>>,
+}
+
+fn minutes_left(ready: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    (ready - now).num_minutes() + 1
+}
+
+impl Db {
+    fn action_uses(&self, side: Side, name: &str) -> Option<&ActionUses> {
+        self.persisted
+            .action_uses
+            .get(&side)
+            .and_then(|uses| uses.get(name))
+    }
+
+    /// Fail if the action is on cooldown, has reached its rolling
+    /// limit, or the side can't afford its command budget cost. The
+    /// command budget only applies to players, the ai commander has
+    /// its own budget.
+    pub(super) fn check_action_throttle(
+        &self,
+        side: Side,
+        ucid: Option<&Ucid>,
+        name: &str,
+        throttle: &ActionThrottle,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        if let Some(uses) = self.action_uses(side, name) {
+            if let Some(cd) = throttle.side_cooldown
+                && let Some(last) = uses.last
+            {
+                let ready = last + Duration::minutes(cd as i64);
+                if ready > now {
+                    bail!(
+                        "{name} is on cooldown for {side} for {} more minutes",
+                        minutes_left(ready, now)
+                    )
+                }
+            }
+            if let Some(cd) = throttle.player_cooldown
+                && let Some(ucid) = ucid
+                && let Some(last) = uses.by_player.get(ucid)
+            {
+                let ready = *last + Duration::minutes(cd as i64);
+                if ready > now {
+                    bail!(
+                        "you can use {name} again in {} minutes",
+                        minutes_left(ready, now)
+                    )
+                }
+            }
+            if let Some(wl) = throttle.window_limit {
+                let start = now - Duration::minutes(wl.window as i64);
+                let n = uses.recent.iter().filter(|ts| **ts > start).count() as u32;
+                if n >= wl.limit {
+                    bail!(
+                        "{side} has used {name} {n} times in the last {} minutes",
+                        wl.window
+                    )
+                }
+            }
+        }
+        if ucid.is_some() && throttle.budget > 0 && self.ephemeral.cfg.command_budget.is_some() {
+            let available = self
+                .persisted
+                .command_budget
+                .get(&side)
+                .copied()
+                .unwrap_or(0.);
+            if throttle.budget as f64 > available {
+                bail!(
+                    "{name} uses {} command budget and {side} has {}",
+                    throttle.budget,
+                    available.floor()
+                )
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn record_action_use(
+        &mut self,
+        side: Side,
+        ucid: Option<&Ucid>,
+        name: &str,
+        throttle: &ActionThrottle,
+        now: DateTime<Utc>,
+    ) {
+        let uses = self
+            .persisted
+            .action_uses
+            .get_or_default_cow(side)
+            .get_or_default_cow(String::from(name));
+        uses.last = Some(now);
+        if let Some(ucid) = ucid {
+            uses.by_player.insert_cow(*ucid, now);
+        }
+        if let Some(wl) = throttle.window_limit {
+            let start = now - Duration::minutes(wl.window as i64);
+            uses.recent.retain(|ts| *ts > start);
+            uses.recent.push(now);
+        }
+        if ucid.is_some()
+            && throttle.budget > 0
+            && self.ephemeral.cfg.command_budget.is_some()
+            && let Some(budget) = self.persisted.command_budget.get_mut_cow(&side)
+        {
+            *budget = f64::max(0., *budget - throttle.budget as f64);
+        }
+        self.ephemeral.dirty();
+    }
+
+    /// Regenerate each side's command budget. Sides start with the max.
+    pub fn regen_command_budget(&mut self, now: DateTime<Utc>) {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let bcfg = match cfg.command_budget.as_ref() {
+            Some(bcfg) => bcfg,
+            None => return,
+        };
+        let elapsed = match self.ephemeral.last_budget_regen.replace(now) {
+            Some(last) => now - last,
+            None => Duration::zero(),
+        };
+        let earned = bcfg.regen as f64 * elapsed.num_seconds() as f64 / 3600.;
+        for side in [Side::Red, Side::Blue] {
+            match self.persisted.command_budget.get_mut_cow(&side) {
+                Some(budget) => *budget = f64::min(bcfg.max as f64, *budget + earned),
+                None => {
+                    self.persisted
+                        .command_budget
+                        .insert_cow(side, bcfg.max as f64);
+                }
+            }
+        }
+        self.ephemeral.dirty();
+    }
+
+    pub fn command_budget(&self, side: Side) -> Option<(u32, u32)> {
+        let bcfg = self.ephemeral.cfg.command_budget.as_ref()?;
+        let available = self
+            .persisted
+            .command_budget
+            .get(&side)
+            .copied()
+            .unwrap_or(0.);
+        Some((available.floor() as u32, bcfg.max))
+    }
+
+    /// Describe the throttle of an action and any cooldowns remaining
+    /// for the player, for display in the action help
+    pub fn action_throttle_status(
+        &self,
+        side: Side,
+        ucid: &Ucid,
+        name: &str,
+        throttle: &ActionThrottle,
+        now: DateTime<Utc>,
+    ) -> Option<CompactString> {
+        let uses = self.action_uses(side, name);
+        let mut parts: SmallVec<[CompactString; 4]> = smallvec![];
+        if throttle.budget > 0 && self.ephemeral.cfg.command_budget.is_some() {
+            parts.push(format_compact!("budget {}", throttle.budget));
+        }
+        if let Some(cd) = throttle.side_cooldown {
+            let ready = uses
+                .and_then(|u| u.last)
+                .map(|last| last + Duration::minutes(cd as i64))
+                .filter(|ready| *ready > now);
+            match ready {
+                Some(ready) => parts.push(format_compact!(
+                    "side cooldown {} min left",
+                    minutes_left(ready, now)
+                )),
+                None => parts.push(format_compact!("side cooldown {cd} min")),
+            }
+        }
+        if let Some(cd) = throttle.player_cooldown {
+            let ready = uses
+                .and_then(|u| u.by_player.get(ucid))
+                .map(|last| *last + Duration::minutes(cd as i64))
+                .filter(|ready| *ready > now);
+            match ready {
+                Some(ready) => parts.push(format_compact!(
+                    "your cooldown {} min left",
+                    minutes_left(ready, now)
+                )),
+                None => parts.push(format_compact!("player cooldown {cd} min")),
+            }
+        }
+        if let Some(wl) = throttle.window_limit {
+            let start = now - Duration::minutes(wl.window as i64);
+            let n = uses
+                .map(|u| u.recent.iter().filter(|ts| **ts > start).count() as u32)
+                .unwrap_or(0);
+            parts.push(format_compact!(
+                "{n}/{} used in {} min",
+                wl.limit,
+                wl.window
+            ));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(", ").into())
+        }
+    }
+}
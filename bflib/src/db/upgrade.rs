@@ -0,0 +1,284 @@
+/*
+Copyright 2024 Eric Stokes.
+
+This file is part of bflib.
+
+bflib is free software: you can redistribute it and/or modify it under
+the terms of the GNU Affero Public License as published by the Free
+Software Foundation, either version 3 of the License, or (at your
+option) any later version.
+
+bflib is distributed in the hope that it will be useful, but WITHOUT
+ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
+for more details.
+*/
+
+use super::{Db, Set, group::DeployKind, objective::Objective};
+use crate::{
+    objective, objective_mut,
+    spawnctx::{SpawnCtx, SpawnLoc},
+};
+use anyhow::{Result, anyhow, bail};
+use bfprotocols::{
+    cfg::{Cfg, ObjectiveUpgrade},
+    db::{group::GroupId, objective::ObjectiveId},
+};
+use chrono::prelude::*;
+use compact_str::format_compact;
+use dcso3::{String, Vector2, coalition::Side, env::miz::MizIndex, net::Ucid};
+use enumflags2::BitFlags;
+use log::error;
+use smallvec::{SmallVec, smallvec};
+use std::{cmp::min, f64::consts::PI, mem, sync::Arc};
+
+/// scale a base warehouse capacity by an upgrade capacity bonus in percent
+pub(super) fn upgraded_capacity(capacity: u32, bonus: u32) -> u32 {
+    capacity * (100 + bonus) / 100
+}
+
+impl Objective {
+    pub fn tier(&self) -> u8 {
+        self.upgrades.len() as u8
+    }
+
+    /// the upgrade tiers this objective has purchased
+    pub(super) fn tiers<'a>(&self, cfg: &'a Cfg) -> &'a [ObjectiveUpgrade] {
+        match cfg.objective_upgrades.as_ref() {
+            None => &[],
+            Some(ucfg) => &ucfg.tiers[..min(self.upgrades.len(), ucfg.tiers.len())],
+        }
+    }
+
+    /// percent reduction in repair time from upgrades, at most 90%
+    pub(super) fn repair_speedup(&self, cfg: &Cfg) -> u32 {
+        min(
+            90,
+            self.tiers(cfg)
+                .iter()
+                .map(|t| t.repair_speedup as u32)
+                .sum(),
+        )
+    }
+
+    /// percent increase in warehouse capacity from upgrades
+    pub(super) fn capacity_bonus(&self, cfg: &Cfg) -> u32 {
+        self.tiers(cfg).iter().map(|t| t.warehouse_capacity).sum()
+    }
+}
+
+fn template_name(side: Side, name: &str) -> String {
+    let pfx = match side {
+        Side::Red => "R",
+        Side::Blue => "B",
+        Side::Neutral => "N",
+    };
+    String::from(format_compact!("{pfx}{name}"))
+}
+
+impl Db {
+    fn spawn_upgrade_defenses(
+        &mut self,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        oid: ObjectiveId,
+        tier: usize,
+    ) -> Result<Set<GroupId>> {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let upgrade = cfg
+            .objective_upgrades
+            .as_ref()
+            .and_then(|ucfg| ucfg.tiers.get(tier))
+            .ok_or_else(|| anyhow!("no such upgrade tier {}", tier + 1))?;
+        let obj = objective!(self, oid)?;
+        let side = obj.owner;
+        let pos = obj.zone.pos();
+        let mut groups: Set<GroupId> = Set::new();
+        // spread the groups evenly around the objective
+        let n = upgrade.defenses.len();
+        for (i, name) in upgrade.defenses.iter().enumerate() {
+            let angle = 2. * PI * i as f64 / n as f64;
+            let location = SpawnLoc::AtPos {
+                pos,
+                offset_direction: Vector2::new(angle.cos(), angle.sin()),
+                group_heading: angle,
+            };
+            let template = template_name(side, name);
+            match self.add_group(
+                spctx,
+                idx,
+                side,
+                location,
+                &template,
+                DeployKind::Objective { origin: oid },
+                BitFlags::empty(),
+            ) {
+                Ok(gid) => {
+                    groups.insert_cow(gid);
+                }
+                Err(e) => {
+                    for gid in &groups {
+                        let _ = self.delete_group(gid);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        let obj = objective_mut!(self, oid)?;
+        let spawned = obj.spawned;
+        let owned = obj.groups.get_or_default_cow(side);
+        for gid in &groups {
+            owned.insert_cow(*gid);
+        }
+        for gid in &groups {
+            self.persisted.objectives_by_group.insert_cow(*gid, oid);
+            if spawned {
+                self.ephemeral.push_spawn(*gid);
+            }
+        }
+        Ok(groups)
+    }
+
+    fn update_upgrade_capacity(&mut self, oid: &ObjectiveId) -> Result<()> {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let whcfg = match cfg.warehouse.as_ref() {
+            Some(whcfg) => whcfg,
+            None => return Ok(()),
+        };
+        let obj = objective_mut!(self, oid)?;
+        let production = match self.ephemeral.production_by_side.get(&obj.owner) {
+            Some(p) => Arc::clone(p),
+            None => return Ok(()),
+        };
+        let hub = obj.kind.is_hub();
+        let bonus = obj.capacity_bonus(&cfg);
+        for (name, equip) in &production.equipment {
+            if let Some(inv) = obj.warehouse.equipment.get_mut_cow(name) {
+                inv.capacity = upgraded_capacity(whcfg.capacity(hub, equip.production), bonus);
+            }
+        }
+        for (name, qty) in &production.liquids {
+            if let Some(inv) = obj.warehouse.liquids.get_mut_cow(name) {
+                inv.capacity = upgraded_capacity(whcfg.capacity(hub, *qty), bonus);
+            }
+        }
+        Ok(())
+    }
+
+    /// Purchase the next upgrade tier for an objective using the
+    /// objective's points. Returns the new tier.
+    pub fn upgrade_objective(
+        &mut self,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        ucid: &Ucid,
+        oid: ObjectiveId,
+        now: DateTime<Utc>,
+    ) -> Result<u8> {
+        let cfg = Arc::clone(&self.ephemeral.cfg);
+        let ucfg = match cfg.objective_upgrades.as_ref() {
+            Some(ucfg) => ucfg,
+            None => bail!("objective upgrades are not enabled"),
+        };
+        let side = match self.persisted.players.get(ucid) {
+            Some(player) => player.side,
+            None => bail!("you must be registered to upgrade objectives"),
+        };
+        let obj = objective!(self, oid)?;
+        if obj.owner != side {
+            bail!("{} is not owned by {side}", obj.name)
+        }
+        let tier = obj.upgrades.len();
+        let upgrade = match ucfg.tiers.get(tier) {
+            Some(upgrade) => upgrade,
+            None => bail!("{} is already fully upgraded", obj.name),
+        };
+        if obj.points < upgrade.cost as i32 {
+            bail!(
+                "{} has {} points, tier {} costs {}",
+                obj.name,
+                obj.points,
+                tier + 1,
+                upgrade.cost
+            )
+        }
+        let groups = self.spawn_upgrade_defenses(spctx, idx, oid, tier)?;
+        let obj = objective_mut!(self, oid)?;
+        obj.points -= upgrade.cost as i32;
+        obj.upgrades.push(groups);
+        self.update_objective_status(&oid, now)?;
+        self.update_upgrade_capacity(&oid)?;
+        self.ephemeral.dirty();
+        Ok(tier as u8 + 1)
+    }
+
+    /// Called when an objective is captured. Drops the configured
+    /// number of tiers and deletes the previous owner's upgrade
+    /// defenses. The defenses of the remaining tiers are queued to be
+    /// respawned for the new owner.
+    pub(super) fn downgrade_captured_objective(&mut self, oid: ObjectiveId) -> Result<()> {
+        let lost = match self.ephemeral.cfg.objective_upgrades.as_ref() {
+            Some(ucfg) => ucfg.tiers_lost_on_capture as usize,
+            None => usize::MAX,
+        };
+        let obj = objective_mut!(self, oid)?;
+        if obj.upgrades.is_empty() {
+            return Ok(());
+        }
+        let keep = obj.upgrades.len().saturating_sub(lost);
+        let mut deleted: SmallVec<[GroupId; 8]> = smallvec![];
+        for groups in obj.upgrades.iter_mut() {
+            deleted.extend(groups.into_iter().copied());
+            *groups = Set::new();
+        }
+        obj.upgrades.truncate(keep);
+        for (_, groups) in obj.groups.iter_mut_cow() {
+            for gid in &deleted {
+                groups.remove_cow(gid);
+            }
+        }
+        for gid in &deleted {
+            self.persisted.objectives_by_group.remove_cow(gid);
+            if let Err(e) = self.delete_group(gid) {
+                error!("could not delete upgrade group {gid} {e:?}")
+            }
+        }
+        if keep > 0 {
+            self.ephemeral.upgrade_respawns.push(oid);
+        }
+        self.ephemeral.dirty();
+        Ok(())
+    }
+
+    /// Spawn the defenses of upgrade tiers that have none, e.g. after
+    /// the objective was captured
+    fn respawn_upgrade_defenses(
+        &mut self,
+        spctx: &SpawnCtx,
+        idx: &MizIndex,
+        oid: ObjectiveId,
+    ) -> Result<()> {
+        let obj = objective!(self, oid)?;
+        let missing = obj
+            .upgrades
+            .iter()
+            .enumerate()
+            .filter(|(_, groups)| groups.len() == 0)
+            .map(|(i, _)| i)
+            .collect::<SmallVec<[usize; 4]>>();
+        for tier in missing {
+            let groups = self.spawn_upgrade_defenses(spctx, idx, oid, tier)?;
+            objective_mut!(self, oid)?.upgrades[tier] = groups;
+            self.ephemeral.dirty();
+        }
+        Ok(())
+    }
+
+    pub fn process_upgrade_respawns(&mut self, spctx: &SpawnCtx, idx: &MizIndex) {
+        for oid in mem::take(&mut self.ephemeral.upgrade_respawns) {
+            if let Err(e) = self.respawn_upgrade_defenses(spctx, idx, oid) {
+                error!("failed to respawn upgrade defenses for {oid} {e:?}")
+            }
+        }
+    }
+}
@@ -20,28 +20,51 @@ The EWR system supports two modes controlled by the 'ewr_mode' configuration opt
 
 The delay is controlled by the 'ewr_delay' configuration option (in seconds, default: 60).
 The default mode is EwrMode::Original to maintain backward compatibility.
+
+If 'ewr_radar' is configured detection is probabilistic. Targets below the
+radar horizon, beyond the range of their size class, or masked by terrain
+are not seen, and targets in the doppler notch are rarely seen. Track
+quality rises with each detection and decays with each miss.
+
+Players may choose BRAA or picture reports. A picture clusters the
+enemy tracks into groups and calls each group relative to the side's
+bullseye, with altitude, aspect to the player, and contact count.
+
+If 'ewr_threat' is configured players are warned when an enemy track is
+closing on them with hot aspect, and when an enemy track merges with
+them. Players may turn these warnings off.
+
+If 'link16' is configured the side's tracks are also shared with the
+flights of link16 capable players as F10 marks, which are moved and
+relabeled as their tracks change.
 */
 
 use crate::{
     db::{
         Db,
+        group::DeployKind,
         player::{InstancedPlayer, Player},
     },
     landcache::LandCache,
 };
 use anyhow::Result;
 use bfprotocols::{
-    cfg::EwrMode,
+    cfg::{ActionKind, EwrMode, EwrRadarCfg, EwrThreatCfg, RadarSizeClass, Vehicle},
     stats::{DetectionSource, EnId, Stat},
 };
 use chrono::prelude::*;
+use compact_str::{CompactString, format_compact};
 use dcso3::{
-    MizLua, Position3, Vector2, Vector3, azumith2d_to, azumith3d, coalition::Side, land::Land,
-    net::Ucid, radians_to_degrees,
+    LuaVec2, LuaVec3, MizLua, Position3, Vector2, Vector3, azumith2d_to, azumith3d,
+    coalition::Side, env::miz, land::Land, net::Ucid, radians_to_degrees, trigger::MarkId,
 };
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
+use rand::{Rng, thread_rng};
 use smallvec::{SmallVec, smallvec};
-use std::fmt;
+use std::{
+    fmt::{self, Write},
+    sync::Arc,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct GibBraa {
@@ -51,11 +74,12 @@ pub struct GibBraa {
     pub heading: u16,
     pub speed: u16,
     pub age: u16,
+    pub quality: u8,
     pub units: EwrUnits,
     converted: bool,
 }
 
-pub const HEADER: &'static str = "BRG      RNG      ALT      SPD        HDG      AGE";
+pub const HEADER: &'static str = "BRG      RNG      ALT      SPD        HDG      AGE    QLY";
 
 impl fmt::Display for GibBraa {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -65,7 +89,7 @@ impl fmt::Display for GibBraa {
         };
         write!(
             f,
-            "{:>6} {:>6}{} {:>6}{} {:>6}{} {:>6} {:>6}s",
+            "{:>6} {:>6}{} {:>6}{} {:>6}{} {:>6} {:>6}s {:>5}%",
             self.bearing,
             self.range,
             range_u,
@@ -74,7 +98,8 @@ impl fmt::Display for GibBraa {
             self.speed,
             _u,
             self.heading,
-            self.age
+            self.age,
+            self.quality
         )
     }
 }
@@ -114,12 +139,54 @@ impl GibBraa {
     }
 }
 
+/// How much track quality is gained on an update where the track is
+/// detected, and lost on an update where it isn't
+const TRACK_QUALITY_GAIN: u8 = 34;
+const TRACK_QUALITY_LOSS: u8 = 20;
+
+/// The distance to the radar horizon between an antenna and a target
+/// at the given heights above the ground, accounting for refraction
+/// (Meters)
+fn radar_horizon(antenna_agl: f64, target_agl: f64) -> f64 {
+    4120. * (antenna_agl.max(0.).sqrt() + target_agl.max(0.).sqrt())
+}
+
+/// The probability that an ewr with the given range detects a target
+/// on one update. It is 0 if the target is beyond the range of its
+/// size class or below the radar horizon.
+fn detection_probability(
+    radar: &EwrRadarCfg,
+    range: f64,
+    ewr_pos: Vector3,
+    ewr_agl: f64,
+    class: RadarSizeClass,
+    pos: Vector3,
+    velocity: Vector3,
+    target_agl: f64,
+) -> f64 {
+    let range = range * radar.class_range.get(&class).copied().unwrap_or(100) as f64 / 100.;
+    let los = pos - ewr_pos;
+    let dist = los.magnitude().max(1.);
+    if dist > range || dist > radar_horizon(ewr_agl, target_agl) {
+        return 0.;
+    }
+    // the return falls off with the fourth power of the distance
+    let mut pd = (radar.edge_pd as f64 / 100. * (range / dist).powi(4)).min(1.);
+    if (velocity.dot(&los) / dist).abs() < radar.notch_speed as f64 {
+        pd *= radar.notch_pd as f64 / 100.;
+    }
+    pd.clamp(0., 1.)
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 struct Track {
     pos: Position3,
     velocity: Vector3,
     last: DateTime<Utc>,
     side: Side,
+    quality: u8,
+    /// whether any radar saw the track on this update
+    seen: bool,
     was_detected: bool,
     detected: bool,
 }
@@ -136,11 +203,238 @@ impl Default for EwrUnits {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EwrFormat {
+    Braa,
+    Picture,
+}
+
+impl Default for EwrFormat {
+    fn default() -> Self {
+        Self::Braa
+    }
+}
+
+/// Tracks within this horizontal distance (meters) and altitude
+/// difference (meters) of another track in a group are called as part
+/// of that group
+const GROUP_RANGE: f64 = 5556.;
+const GROUP_ALT: f64 = 3000.;
+
+/// Tracks older than this are not used for threat warnings (Seconds)
+const THREAT_TRACK_AGE: i64 = 20;
+
+/// How far a track may be from the point a player asks to declare and
+/// still be declared (Meters)
+const DECLARE_RANGE: f64 = 5556.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aspect {
+    Hot,
+    Flank,
+    Beam,
+    Drag,
+}
+
+impl Aspect {
+    /// The aspect of a target on heading hdg at pos relative to a
+    /// player at ownship
+    fn new(pos: Vector2, hdg: f64, ownship: Vector2) -> Self {
+        let to_ownship = radians_to_degrees(azumith2d_to(pos, ownship));
+        let off = (radians_to_degrees(hdg) - to_ownship).rem_euclid(360.);
+        let off = if off > 180. { 360. - off } else { off };
+        if off <= 30. {
+            Self::Hot
+        } else if off <= 70. {
+            Self::Flank
+        } else if off <= 110. {
+            Self::Beam
+        } else {
+            Self::Drag
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hot => "hot",
+            Self::Flank => "flank",
+            Self::Beam => "beam",
+            Self::Drag => "drag",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PictureGroup {
+    pos: Vector2,
+    altitude: f64,
+    heading: f64,
+    count: usize,
+}
+
+impl PictureGroup {
+    fn new(tracks: &[&Track]) -> Self {
+        let n = tracks.len() as f64;
+        let (mut pos, mut altitude, mut velocity) = (Vector2::zeros(), 0., Vector3::zeros());
+        for track in tracks {
+            pos += Vector2::new(track.pos.p.x, track.pos.p.z);
+            altitude += track.pos.p.y;
+            velocity += track.velocity;
+        }
+        Self {
+            pos: pos / n,
+            altitude: altitude / n,
+            heading: azumith3d(velocity),
+            count: tracks.len(),
+        }
+    }
+
+    fn write(
+        &self,
+        report: &mut CompactString,
+        label: &str,
+        bullseye: Vector2,
+        ownship: Option<Vector2>,
+        units: EwrUnits,
+    ) -> fmt::Result {
+        let bearing = radians_to_degrees(azumith2d_to(bullseye, self.pos)) as u16;
+        let range = na::distance(&bullseye.into(), &self.pos.into());
+        let (range, range_u) = match units {
+            EwrUnits::Imperial => ((range / 1852.).round() as u32, "nm"),
+            EwrUnits::Metric => ((range / 1000.).round() as u32, "km"),
+        };
+        let altitude = altitude_call(self.altitude, units);
+        let aspect = match ownship {
+            Some(ownship) => {
+                CompactString::from(Aspect::new(self.pos, self.heading, ownship).as_str())
+            }
+            None => format_compact!("track {}", cardinal(self.heading)),
+        };
+        let contacts = match self.count {
+            1 => CompactString::from("single"),
+            2 => CompactString::from("2 contacts"),
+            n => format_compact!("heavy {n} contacts"),
+        };
+        write!(
+            report,
+            "{label} bullseye {bearing:03}/{range}{range_u}, {altitude}, {aspect}, {contacts}\n"
+        )
+    }
+}
+
+/// An altitude as it is called on the radio
+fn altitude_call(altitude: f64, units: EwrUnits) -> CompactString {
+    match units {
+        EwrUnits::Imperial => {
+            let ft = altitude * 3.28084;
+            if ft < 1000. {
+                CompactString::from("low")
+            } else {
+                format_compact!("{} thousand", (ft / 1000.).round() as u32)
+            }
+        }
+        EwrUnits::Metric => format_compact!("{}m", ((altitude / 100.).round() * 100.) as u32),
+    }
+}
+
+/// The nearest of the eight compass points to a heading in radians
+pub(crate) fn cardinal(heading: f64) -> &'static str {
+    const POINTS: [&str; 8] = [
+        "north",
+        "northeast",
+        "east",
+        "southeast",
+        "south",
+        "southwest",
+        "west",
+        "northwest",
+    ];
+    let i = (radians_to_degrees(heading).rem_euclid(360.) / 45.).round() as usize;
+    POINTS[i % 8]
+}
+
+fn count_word(n: usize) -> CompactString {
+    const WORDS: [&str; 11] = [
+        "no", "single", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    match WORDS.get(n) {
+        Some(w) => CompactString::from(*w),
+        None => format_compact!("{n}"),
+    }
+}
+
+/// Cluster tracks into groups. A track belongs to a group if it is
+/// close to any track already in the group.
+fn cluster<'a>(
+    tracks: impl IntoIterator<Item = &'a Track>,
+) -> SmallVec<[SmallVec<[&'a Track; 4]>; 16]> {
+    let close = |a: &Track, b: &Track| {
+        let pa = Vector2::new(a.pos.p.x, a.pos.p.z);
+        let pb = Vector2::new(b.pos.p.x, b.pos.p.z);
+        na::distance(&pa.into(), &pb.into()) <= GROUP_RANGE
+            && (a.pos.p.y - b.pos.p.y).abs() <= GROUP_ALT
+    };
+    let mut groups: SmallVec<[SmallVec<[&Track; 4]>; 16]> = smallvec![];
+    for track in tracks {
+        let mut joined: Option<usize> = None;
+        let mut i = 0;
+        while i < groups.len() {
+            if !groups[i].iter().any(|t| close(*t, track)) {
+                i += 1;
+                continue;
+            }
+            match joined {
+                None => {
+                    groups[i].push(track);
+                    joined = Some(i);
+                    i += 1;
+                }
+                Some(j) => {
+                    // the track bridges two groups, merge them
+                    let g = groups.swap_remove(i);
+                    groups[j].extend(g);
+                }
+            }
+        }
+        if joined.is_none() {
+            groups.push(smallvec![track]);
+        }
+    }
+    groups
+}
+
+/// The label of a track's link16 mark
+fn link16_label(track: &Track, side: Side, units: EwrUnits) -> CompactString {
+    let kind = if track.side == side {
+        "FRIEND"
+    } else {
+        "HOSTILE"
+    };
+    let heading = radians_to_degrees(azumith3d(track.velocity)) as u16;
+    let speed = track.velocity.magnitude();
+    let altitude = track.pos.p.y;
+    match units {
+        EwrUnits::Imperial => format_compact!(
+            "L16 {kind} {heading:03} {}kts {}ft",
+            (speed * 1.94384).round() as u32,
+            ((altitude * 3.28084 / 100.).round() * 100.) as u32
+        ),
+        EwrUnits::Metric => format_compact!(
+            "L16 {kind} {heading:03} {}km/h {}m",
+            (speed * 3.6).round() as u32,
+            ((altitude / 100.).round() * 100.) as u32
+        ),
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct PlayerState {
     enabled: bool,
     units: EwrUnits,
+    format: EwrFormat,
+    threat_warnings: bool,
     last: DateTime<Utc>,
+    last_threat: DateTime<Utc>,
 }
 
 impl Default for PlayerState {
@@ -148,15 +442,59 @@ impl Default for PlayerState {
         Self {
             enabled: true,
             units: EwrUnits::default(),
+            format: EwrFormat::default(),
+            threat_warnings: true,
             last: DateTime::default(),
+            last_threat: DateTime::default(),
         }
     }
 }
 
+impl PlayerState {
+    /// Whether a report is due given the range (meters) and age
+    /// (seconds) of the closest track
+    fn due(
+        &self,
+        now: DateTime<Utc>,
+        force: bool,
+        range: u32,
+        age: u16,
+        ewr_mode: EwrMode,
+        ewr_delay: u32,
+    ) -> bool {
+        let since_last = (now - self.last).num_seconds();
+        match ewr_mode {
+            EwrMode::Original => {
+                // Original reporting logic with complex timing rules
+                force
+                    || since_last >= 60
+                    || (range <= 20000 && age <= 10)
+                    || (range <= 40000 && age <= 10 && since_last >= 30)
+            }
+            EwrMode::Delayed => {
+                // With configurable track update delay, we can simplify the reporting logic
+                // Reports are sent every delay period or when forced
+                force || since_last >= ewr_delay as i64
+            }
+        }
+    }
+}
+
+/// A track shown to a flight as an F10 mark
+#[derive(Debug, Clone)]
+struct Link16Mark {
+    id: MarkId,
+    pos: Vector2,
+    label: CompactString,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Ewr {
     tracks: FxHashMap<Side, FxHashMap<EnId, Track>>,
     player_state: FxHashMap<Ucid, PlayerState>,
+    bullseye: FxHashMap<Side, Vector2>,
+    merged: FxHashMap<Ucid, FxHashSet<EnId>>,
+    link16: FxHashMap<miz::GroupId, FxHashMap<EnId, Link16Mark>>,
 }
 
 impl Ewr {
@@ -170,7 +508,13 @@ impl Ewr {
         ewr_delay: u32,
     ) -> Result<()> {
         let land = Land::singleton(lua)?;
-        let aircraft: SmallVec<[(EnId, Side, Position3, Vector3); 128]> = {
+        let radar = db.ephemeral.cfg.ewr_radar.as_ref();
+        let class = |typ: &Vehicle| {
+            radar
+                .and_then(|r| r.size_class.get(typ).copied())
+                .unwrap_or_default()
+        };
+        let mut aircraft: SmallVec<[(EnId, Side, Position3, Vector3, RadarSizeClass, f64); 128]> = {
             let players = db
                 .instanced_players()
                 .filter(|(_, _, inst)| inst.in_air)
@@ -180,6 +524,8 @@ impl Ewr {
                         player.side,
                         inst.position,
                         inst.velocity,
+                        class(&inst.typ),
+                        0.,
                     )
                 });
             let actions = db
@@ -188,31 +534,99 @@ impl Ewr {
                 .into_iter()
                 .filter_map(|gid| db.persisted.groups.get(gid))
                 .flat_map(|sg| {
+                    let missile = match &sg.origin {
+                        DeployKind::Action { spec, .. } => {
+                            matches!(spec.kind, ActionKind::CruiseMissileSpawn(_))
+                        }
+                        _ => false,
+                    };
                     sg.units
                         .into_iter()
                         .filter_map(|uid| db.persisted.units.get(uid).map(|u| (*uid, u)))
-                        .filter_map(|(uid, su)| {
+                        .filter_map(move |(uid, su)| {
+                            let class = if missile {
+                                RadarSizeClass::CruiseMissile
+                            } else {
+                                class(&su.typ)
+                            };
                             su.airborne_velocity
-                                .map(|v| (EnId::Unit(uid), sg.side, su.position, v))
+                                .map(|v| (EnId::Unit(uid), sg.side, su.position, v, class, 0.))
                         })
                 });
             players.chain(actions).collect()
         };
+        if radar.is_some() {
+            for (_, _, pos, _, _, agl) in aircraft.iter_mut() {
+                let ground = land.get_height(LuaVec2(Vector2::new(pos.p.x, pos.p.z)))?;
+                *agl = pos.p.y - ground;
+            }
+        }
         for tracks in self.tracks.values_mut() {
             for track in tracks.values_mut() {
                 track.detected = false;
+                track.seen = false;
             }
         }
-        for (mut ewr_pos, ewr_side, ewr) in db.ewrs() {
-            let range = (ewr.range as f64).powi(2);
+        let mut rng = thread_rng();
+        for (_, mut ewr_pos, ewr_side, ewr) in db.ewrs() {
+            let jammer = db.jammed(ewr_side, ewr_pos);
+            let range = match jammer {
+                None => (ewr.range as f64).powi(2),
+                Some(j) => (ewr.range as f64 * j.ewr_range as f64 / 100.).powi(2),
+            };
             let tracks = self.tracks.entry(ewr_side).or_default();
-            ewr_pos.y += 10.; // factor in antenna height
-            for (id, obj_side, pos, velocity) in &aircraft {
+            ewr_pos.y += ewr.antenna_height as f64;
+            let ewr_agl = match radar {
+                None => 0.,
+                Some(_) => {
+                    ewr_pos.y - land.get_height(LuaVec2(Vector2::new(ewr_pos.x, ewr_pos.z)))?
+                }
+            };
+            for (id, obj_side, pos, velocity, class, agl) in &aircraft {
                 let track = tracks.entry(*id).or_default();
                 if track.last != now {
                     let dist = na::distance_squared(&ewr_pos.into(), &pos.p.0.into());
                     if dist <= range {
+                        if let Some(radar) = radar {
+                            let pd = detection_probability(
+                                radar,
+                                range.sqrt(),
+                                ewr_pos,
+                                ewr_agl,
+                                *class,
+                                pos.p.0,
+                                *velocity,
+                                *agl,
+                            );
+                            if !rng.gen_bool(pd) {
+                                continue;
+                            }
+                        }
                         if landcache.is_visible(&land, dist.sqrt(), ewr_pos, pos.p.0)? {
+                            if let Some(j) = jammer {
+                                // a jammed radar reports late and noisy tracks, on
+                                // top of the delay of the ewr mode
+                                let delay = match ewr_mode {
+                                    EwrMode::Original => 0,
+                                    EwrMode::Delayed => ewr_delay as i64,
+                                };
+                                let delay = delay + j.track_delay as i64;
+                                if (now - track.last).num_seconds() >= delay {
+                                    let e = j.track_error as f64;
+                                    let mut pos = *pos;
+                                    if e > 0. {
+                                        pos.p.0.x += rng.gen_range(-e..=e);
+                                        pos.p.0.z += rng.gen_range(-e..=e);
+                                    }
+                                    track.pos = pos;
+                                    track.velocity = *velocity;
+                                    track.last = now;
+                                }
+                                track.side = *obj_side;
+                                track.seen = true;
+                                track.detected |= ewr_side != *obj_side;
+                                continue;
+                            }
                             match ewr_mode {
                                 EwrMode::Original => {
                                     // Original implementation: update track data immediately
@@ -233,6 +647,7 @@ impl Ewr {
                             }
                             track.last = now;
                             track.side = *obj_side;
+                            track.seen = true;
                             track.detected |= ewr_side != *obj_side;
                         }
                     }
@@ -241,6 +656,11 @@ impl Ewr {
         }
         for tracks in self.tracks.values_mut() {
             for (id, track) in tracks.iter_mut() {
+                if track.seen {
+                    track.quality = track.quality.saturating_add(TRACK_QUALITY_GAIN).min(100);
+                } else {
+                    track.quality = track.quality.saturating_sub(TRACK_QUALITY_LOSS);
+                }
                 if track.was_detected != track.detected {
                     track.was_detected = track.detected;
                     db.ephemeral.stat(Stat::Detected {
@@ -260,10 +680,68 @@ impl Ewr {
         st.enabled
     }
 
+    pub fn toggle_threat_warnings(&mut self, ucid: &Ucid) -> bool {
+        let st = self.player_state.entry(ucid.clone()).or_default();
+        st.threat_warnings = !st.threat_warnings;
+        st.threat_warnings
+    }
+
     pub fn set_units(&mut self, ucid: &Ucid, units: EwrUnits) {
         self.player_state.entry(ucid.clone()).or_default().units = units;
     }
 
+    pub fn set_format(&mut self, ucid: &Ucid, format: EwrFormat) {
+        self.player_state.entry(ucid.clone()).or_default().format = format;
+    }
+
+    pub fn format(&self, ucid: &Ucid) -> EwrFormat {
+        self.player_state
+            .get(ucid)
+            .map(|st| st.format)
+            .unwrap_or_default()
+    }
+
+    pub fn units(&self, ucid: &Ucid) -> EwrUnits {
+        self.player_state
+            .get(ucid)
+            .map(|st| st.units)
+            .unwrap_or_default()
+    }
+
+    pub fn set_bullseye(&mut self, side: Side, pos: Vector2) {
+        self.bullseye.insert(side, pos);
+    }
+
+    pub fn bullseye(&self, side: Side) -> Option<Vector2> {
+        self.bullseye.get(&side).copied()
+    }
+
+    /// The number of enemy aircraft currently tracked by side's ewrs
+    /// within range meters of pos
+    pub fn enemy_tracks_near(
+        &self,
+        now: DateTime<Utc>,
+        side: Side,
+        pos: Vector2,
+        range: f64,
+    ) -> usize {
+        let range = range.powi(2);
+        self.tracks
+            .get(&side)
+            .map(|tracks| {
+                tracks
+                    .values()
+                    .filter(|track| {
+                        let cpos = Vector2::new(track.pos.p.x, track.pos.p.z);
+                        track.side != side
+                            && (now - track.last).num_seconds() <= 120
+                            && na::distance_squared(&pos.into(), &cpos.into()) <= range
+                    })
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+
     pub fn where_chicken(
         &mut self,
         now: DateTime<Utc>,
@@ -303,6 +781,7 @@ impl Ewr {
                     altitude: altitude as u32,
                     bearing: bearing as u16,
                     age: age as u16,
+                    quality: track.quality,
                     speed: speed as u16,
                     units: EwrUnits::Metric,
                     converted: false,
@@ -317,33 +796,380 @@ impl Ewr {
         while reports.len() > 10 {
             reports.pop();
         }
-        let since_last = (now - state.last).num_seconds();
-        match ewr_mode {
-            EwrMode::Original => {
-                // Original reporting logic with complex timing rules
-                if force
-                    || since_last >= 60
-                    || (reports[0].range <= 20000 && reports[0].age <= 10)
-                    || (reports[0].range <= 40000 && reports[0].age <= 10 && since_last >= 30)
-                {
-                    state.last = now;
-                    reports.iter_mut().for_each(|r| r.convert(state.units));
-                    reports
+        if state.due(
+            now,
+            force,
+            reports[0].range,
+            reports[0].age,
+            ewr_mode,
+            ewr_delay,
+        ) {
+            state.last = now;
+            reports.iter_mut().for_each(|r| r.convert(state.units));
+            reports
+        } else {
+            smallvec![]
+        }
+    }
+
+    /// Build a bullseye referenced picture of the enemy aircraft
+    /// tracked by the player's side, or None if there is nothing to
+    /// report
+    pub fn picture(
+        &mut self,
+        now: DateTime<Utc>,
+        force: bool,
+        ucid: &Ucid,
+        player: &Player,
+        inst: &InstancedPlayer,
+        ewr_mode: EwrMode,
+        ewr_delay: u32,
+    ) -> Option<CompactString> {
+        let side = player.side;
+        let ownship = Vector2::new(inst.position.p.x, inst.position.p.z);
+        let bullseye = *self.bullseye.get(&side)?;
+        let tracks = self.tracks.get(&side)?;
+        let state = self.player_state.entry(ucid.clone()).or_default();
+        if !force && !state.enabled {
+            return None;
+        }
+        let ownid = EnId::Player(*ucid);
+        let live = tracks
+            .iter()
+            .filter(|(id, track)| {
+                track.side != side && *id != &ownid && (now - track.last).num_seconds() <= 120
+            })
+            .map(|(_, track)| track);
+        let mut groups: SmallVec<[PictureGroup; 16]> =
+            cluster(live).iter().map(|g| PictureGroup::new(g)).collect();
+        if groups.is_empty() {
+            return None;
+        }
+        let range = |g: &PictureGroup| na::distance(&ownship.into(), &g.pos.into());
+        groups.sort_by(|g0, g1| range(g0).total_cmp(&range(g1)));
+        let closest = range(&groups[0]) as u32;
+        let age = tracks
+            .values()
+            .filter(|t| t.side != side)
+            .map(|t| (now - t.last).num_seconds().max(0))
+            .min()
+            .unwrap_or(0) as u16;
+        if !state.due(now, force, closest, age, ewr_mode, ewr_delay) {
+            return None;
+        }
+        state.last = now;
+        let units = state.units;
+        let dist = |d: f64| match units {
+            EwrUnits::Imperial => format_compact!("{}nm", (d / 1852.).round() as u32),
+            EwrUnits::Metric => format_compact!("{}km", (d / 1000.).round() as u32),
+        };
+        let mut report = format_compact!("Picture\n");
+        let n = groups.len();
+        let plural = if n == 1 { "group" } else { "groups" };
+        let labels: SmallVec<[CompactString; 16]> = if n == 2 {
+            // two groups are called by their separation across or
+            // along the line of sight from the player
+            let (g0, g1) = (&groups[0], &groups[1]);
+            let sep = g1.pos - g0.pos;
+            let los = ((g0.pos + g1.pos) / 2. - ownship).normalize();
+            let along = sep.dot(&los).abs();
+            let across = (sep - los * sep.dot(&los)).magnitude();
+            if across >= along {
+                let _ = write!(report, "two groups azimuth {}\n", dist(across));
+                // DCS x is north, z is east
+                let (a, b) = if sep.x.abs() >= sep.y.abs() {
+                    if sep.x > 0. {
+                        ("south", "north")
+                    } else {
+                        ("north", "south")
+                    }
+                } else if sep.y > 0. {
+                    ("west", "east")
                 } else {
-                    smallvec![]
+                    ("east", "west")
+                };
+                smallvec![format_compact!("{a} group"), format_compact!("{b} group")]
+            } else {
+                let _ = write!(report, "two groups range {}\n", dist(along));
+                smallvec!["lead group".into(), "trail group".into()]
+            }
+        } else {
+            let _ = write!(report, "{} {plural}\n", count_word(n));
+            (1..=n).map(|i| format_compact!("group {i}")).collect()
+        };
+        for (g, label) in groups.iter().zip(labels.iter()) {
+            let _ = g.write(&mut report, label, bullseye, Some(ownship), units);
+        }
+        Some(report)
+    }
+
+    /// Check for enemy tracks threatening the player. Returns a merge
+    /// call the first time an enemy track comes inside merge range,
+    /// otherwise a threat call for the closest enemy track that is
+    /// closing with hot aspect, at most once per cadence.
+    pub fn threats(
+        &mut self,
+        now: DateTime<Utc>,
+        cfg: &EwrThreatCfg,
+        ucid: &Ucid,
+        player: &Player,
+        inst: &InstancedPlayer,
+    ) -> Option<CompactString> {
+        let side = player.side;
+        let state = self.player_state.entry(ucid.clone()).or_default();
+        if !state.threat_warnings {
+            return None;
+        }
+        let tracks = self.tracks.get(&side)?;
+        let merged = self.merged.entry(ucid.clone()).or_default();
+        merged.retain(|id| tracks.contains_key(id));
+        let ownship = Vector2::new(inst.position.p.x, inst.position.p.z);
+        let ownid = EnId::Player(*ucid);
+        let merge_range = cfg.merge_range as f64;
+        let mut merges = 0;
+        let mut threat: Option<(f64, Vector2)> = None;
+        for (id, track) in tracks {
+            if track.side == side
+                || id == &ownid
+                || (now - track.last).num_seconds() > THREAT_TRACK_AGE
+            {
+                merged.remove(id);
+                continue;
+            }
+            let pos = Vector2::new(track.pos.p.x, track.pos.p.z);
+            let range = na::distance(&ownship.into(), &pos.into());
+            if range <= merge_range {
+                if merged.insert(*id) {
+                    merges += 1;
                 }
+                continue;
             }
-            EwrMode::Delayed => {
-                // With configurable track update delay, we can simplify the reporting logic
-                // Reports are sent every delay period or when forced
-                if force || since_last >= ewr_delay as i64 {
-                    state.last = now;
-                    reports.iter_mut().for_each(|r| r.convert(state.units));
-                    reports
-                } else {
-                    smallvec![]
+            // don't call the same merge again if the track drifts
+            // just outside merge range
+            if range > merge_range * 1.5 {
+                merged.remove(id);
+            }
+            if range <= cfg.threat_range as f64 {
+                let los = inst.position.p.0 - track.pos.p.0;
+                let closing = (track.velocity - inst.velocity).dot(&los) > 0.;
+                let hot = Aspect::new(pos, azumith3d(track.velocity), ownship) == Aspect::Hot;
+                if closing && hot && threat.map(|(r, _)| range < r).unwrap_or(true) {
+                    threat = Some((range, pos));
+                }
+            }
+        }
+        if merges > 0 {
+            state.last_threat = now;
+            return Some(match merges {
+                1 => CompactString::from("merged"),
+                n => format_compact!("merged, {n} bandits"),
+            });
+        }
+        let (range, pos) = threat?;
+        if (now - state.last_threat).num_seconds() < cfg.cadence as i64 {
+            return None;
+        }
+        state.last_threat = now;
+        let bearing = radians_to_degrees(azumith2d_to(ownship, pos)) as u16;
+        let (range, range_u) = match state.units {
+            EwrUnits::Imperial => ((range / 1852.).round() as u32, "miles"),
+            EwrUnits::Metric => ((range / 1000.).round() as u32, "km"),
+        };
+        Some(format_compact!(
+            "threat, bearing {bearing:03}, {range} {range_u}, hot"
+        ))
+    }
+
+    /// Share the side's tracks with the flights of link16 capable
+    /// players. Each flight gets marks for the closest tracks, visible
+    /// only to that flight. Marks are only moved or relabeled when
+    /// their track changes.
+    pub fn update_link16(&mut self, db: &mut Db, now: DateTime<Utc>) {
+        let cfg = Arc::clone(&db.ephemeral.cfg);
+        let l16 = match cfg.link16.as_ref() {
+            Some(l16) => l16,
+            None => return,
+        };
+        let mut flights: FxHashMap<miz::GroupId, FxHashMap<EnId, (Vector2, CompactString)>> =
+            FxHashMap::default();
+        for (ucid, player, inst) in db.instanced_players() {
+            if !l16.airframes.contains(&inst.typ) {
+                continue;
+            }
+            let gid = match player
+                .current_slot
+                .as_ref()
+                .and_then(|(slot, _)| db.ephemeral.get_slot_info(slot))
+            {
+                Some(ifo) => ifo.miz_gid,
+                None => continue,
+            };
+            // every member of a flight shares the same marks
+            if flights.contains_key(&gid) {
+                continue;
+            }
+            let units = self
+                .player_state
+                .get(ucid)
+                .map(|st| st.units)
+                .unwrap_or_default();
+            let pos = Vector2::new(inst.position.p.x, inst.position.p.z);
+            let ownship = EnId::Player(*ucid);
+            let mut tracks: SmallVec<[(f64, EnId, Vector2, CompactString); 64]> = smallvec![];
+            for (id, track) in self.tracks.get(&player.side).into_iter().flatten() {
+                if id == &ownship || (now - track.last).num_seconds() > l16.max_age as i64 {
+                    continue;
                 }
+                let tpos = Vector2::new(track.pos.p.x, track.pos.p.z);
+                let range = na::distance_squared(&pos.into(), &tpos.into());
+                tracks.push((range, *id, tpos, link16_label(track, player.side, units)));
             }
+            tracks.sort_by(|(r0, _, _, _), (r1, _, _, _)| r0.total_cmp(r1));
+            let tracks = tracks
+                .into_iter()
+                .take(l16.max_tracks as usize)
+                .map(|(_, id, pos, label)| (id, (pos, label)))
+                .collect();
+            flights.insert(gid, tracks);
         }
+        let msgs = db.ephemeral.msgs();
+        self.link16.retain(|gid, marks| {
+            let tracks = flights.get(gid);
+            marks.retain(|id, mark| {
+                let keep = tracks.map(|t| t.contains_key(id)).unwrap_or(false);
+                if !keep {
+                    msgs.delete_mark(mark.id)
+                }
+                keep
+            });
+            !marks.is_empty()
+        });
+        for (gid, tracks) in flights {
+            let marks = self.link16.entry(gid).or_default();
+            for (id, (pos, label)) in tracks {
+                match marks.get_mut(&id) {
+                    None => {
+                        let mark = Link16Mark {
+                            id: msgs.mark_to_group(gid, pos, true, label.clone()),
+                            pos,
+                            label,
+                        };
+                        marks.insert(id, mark);
+                    }
+                    Some(mark) => {
+                        if mark.pos != pos {
+                            let v3 = LuaVec3(Vector3::new(pos.x, 0., pos.y));
+                            msgs.set_markup_pos_start(mark.id, v3);
+                            mark.pos = pos;
+                        }
+                        if mark.label != label {
+                            msgs.set_markup_text(mark.id, label.clone().into());
+                            mark.label = label;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// A bullseye referenced picture of all the enemy aircraft tracked
+    /// by a side, as broadcast by an awacs. Unlike a player's picture
+    /// it has no aspect, groups are called with their track direction.
+    pub fn side_picture(&self, now: DateTime<Utc>, side: Side) -> Option<CompactString> {
+        let bullseye = *self.bullseye.get(&side)?;
+        let live = self
+            .tracks
+            .get(&side)?
+            .values()
+            .filter(|track| track.side != side && (now - track.last).num_seconds() <= 120);
+        let mut groups: SmallVec<[PictureGroup; 16]> =
+            cluster(live).iter().map(|g| PictureGroup::new(g)).collect();
+        if groups.is_empty() {
+            return None;
+        }
+        let range = |g: &PictureGroup| na::distance(&bullseye.into(), &g.pos.into());
+        groups.sort_by(|g0, g1| range(g0).total_cmp(&range(g1)));
+        let n = groups.len();
+        let plural = if n == 1 { "group" } else { "groups" };
+        let mut report = format_compact!("{} {plural}\n", count_word(n));
+        for (i, g) in groups.iter().enumerate() {
+            let label = format_compact!("group {}", i + 1);
+            let _ = g.write(&mut report, &label, bullseye, None, EwrUnits::Imperial);
+        }
+        Some(report)
+    }
+
+    /// A BRAA call from the player to the closest enemy track. Returns
+    /// None if the side has no enemy track.
+    pub fn braa_call(
+        &self,
+        now: DateTime<Utc>,
+        ucid: &Ucid,
+        player: &Player,
+        inst: &InstancedPlayer,
+    ) -> Option<CompactString> {
+        let side = player.side;
+        let ownship = Vector2::new(inst.position.p.x, inst.position.p.z);
+        let (_, track) = self
+            .tracks
+            .get(&side)?
+            .values()
+            .filter(|track| track.side != side && (now - track.last).num_seconds() <= 120)
+            .map(|track| {
+                let pos = Vector2::new(track.pos.p.x, track.pos.p.z);
+                (na::distance(&ownship.into(), &pos.into()), track)
+            })
+            .min_by(|(r0, _), (r1, _)| r0.total_cmp(r1))?;
+        Some(self.braa(ucid, side, ownship, track))
+    }
+
+    /// Declare the track nearest point as a BRAA call from the
+    /// player. Returns None if the side has no track near point.
+    pub fn declare(
+        &self,
+        now: DateTime<Utc>,
+        ucid: &Ucid,
+        player: &Player,
+        inst: &InstancedPlayer,
+        point: Vector2,
+    ) -> Option<CompactString> {
+        let side = player.side;
+        let ownship = Vector2::new(inst.position.p.x, inst.position.p.z);
+        let ownid = EnId::Player(*ucid);
+        let (dist, track) = self
+            .tracks
+            .get(&side)?
+            .iter()
+            .filter(|(id, track)| *id != &ownid && (now - track.last).num_seconds() <= 120)
+            .map(|(_, track)| {
+                let pos = Vector2::new(track.pos.p.x, track.pos.p.z);
+                (na::distance(&point.into(), &pos.into()), track)
+            })
+            .min_by(|(r0, _), (r1, _)| r0.total_cmp(r1))?;
+        if dist > DECLARE_RANGE {
+            return None;
+        }
+        Some(self.braa(ucid, side, ownship, track))
+    }
+
+    /// The BRAA from ownship to track in the player's units, with the
+    /// track identified as friendly or hostile
+    fn braa(&self, ucid: &Ucid, side: Side, ownship: Vector2, track: &Track) -> CompactString {
+        let units = self.units(ucid);
+        let pos = Vector2::new(track.pos.p.x, track.pos.p.z);
+        let range = na::distance(&ownship.into(), &pos.into());
+        let bearing = radians_to_degrees(azumith2d_to(ownship, pos)) as u16;
+        let (range, range_u) = match units {
+            EwrUnits::Imperial => ((range / 1852.).round() as u32, "nm"),
+            EwrUnits::Metric => ((range / 1000.).round() as u32, "km"),
+        };
+        let altitude = altitude_call(track.pos.p.y, units);
+        let aspect = Aspect::new(pos, azumith3d(track.velocity), ownship).as_str();
+        let ident = if track.side == side {
+            "friendly"
+        } else {
+            "hostile"
+        };
+        format_compact!("BRAA {bearing:03}/{range}{range_u}, {altitude}, {aspect}, {ident}")
     }
 }
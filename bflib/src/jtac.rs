@@ -16,26 +16,30 @@ for more details.
 
 use crate::{
     db::{Db, JtDesc, group::SpawnedUnit, player::InstancedPlayer},
+    ewr::cardinal,
     landcache::LandCache,
+    shots::ShotDb,
 };
 use anyhow::{Context, Result, anyhow, bail};
 use bfprotocols::{
-    cfg::{UnitTag, UnitTags, Vehicle},
+    cfg::{JtacScoringCfg, UnitTag, UnitTags, Vehicle},
     db::{
         group::{GroupId, UnitId},
         objective::ObjectiveId,
     },
-    stats::{DetectionSource, EnId, Stat},
+    shots::{Dead, Who},
+    stats::{BdaCount, DetectionSource, EnId, Stat},
 };
 use chrono::{Duration, prelude::*};
 use compact_str::{CompactString, format_compact};
 use dcso3::{
-    LuaVec2, LuaVec3, MizLua, String, Vector2, Vector3,
+    LuaVec2, LuaVec3, MizLua, String, Vector2, Vector3, azumith2d_to,
     coalition::Side,
     controller::{
         ActionTyp, AltType, AttackParams, Command, MissionPoint, PointType, Task, TurnMethod,
         VehicleFormation, WeaponExpend,
     },
+    coord::{Coord, LLPos},
     cvt_err, err,
     group::Group,
     land::Land,
@@ -53,11 +57,11 @@ use indexmap::IndexMap;
 use log::{info, warn};
 use mlua::{FromLua, IntoLua, Lua, Table, Value, prelude::LuaResult};
 use rand::{Rng, thread_rng};
-use serde::{Deserialize, Serialize};
+use serde_derive::{Deserialize, Serialize};
 use smallvec::{SmallVec, smallvec};
 use std::{collections::hash_map::Entry, fmt, str::FromStr};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum JtId {
     Group(GroupId),
     Slot(SlotId),
@@ -128,6 +132,67 @@ simple_enum!(AdjustmentDir, u8, [
     Right => 3
 ]);
 
+simple_enum!(BriefFormat, u8, [
+    Mgrs => 0,
+    Dms => 1,
+    Ddm => 2
+]);
+
+impl FromStr for BriefFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "mgrs" => Ok(Self::Mgrs),
+            "dms" => Ok(Self::Dms),
+            "ddm" => Ok(Self::Ddm),
+            s => bail!("unknown brief format {s}, expected mgrs, dms, or ddm"),
+        }
+    }
+}
+
+impl BriefFormat {
+    fn format(&self, coord: &Coord, ll: LLPos) -> Result<CompactString> {
+        fn dms(v: f64, pos: char, neg: char) -> CompactString {
+            let h = if v < 0. { neg } else { pos };
+            let v = v.abs();
+            let d = v.trunc();
+            let m = ((v - d) * 60.).trunc();
+            let s = ((v - d) * 60. - m) * 60.;
+            format_compact!("{h} {d:.0}°{m:02.0}'{s:04.1}\"")
+        }
+        fn ddm(v: f64, pos: char, neg: char) -> CompactString {
+            let h = if v < 0. { neg } else { pos };
+            let v = v.abs();
+            let d = v.trunc();
+            let m = (v - d) * 60.;
+            format_compact!("{h} {d:.0}°{m:06.3}'")
+        }
+        Ok(match self {
+            Self::Mgrs => {
+                let mgrs = coord.ll_to_mgrs(ll.latitude, ll.longitude)?;
+                format_compact!(
+                    "{} {} {:05} {:05}",
+                    mgrs.utm_zone,
+                    mgrs.mgrs_digraph,
+                    mgrs.easting as u32,
+                    mgrs.northing as u32
+                )
+            }
+            Self::Dms => format_compact!(
+                "{} {}",
+                dms(ll.latitude, 'N', 'S'),
+                dms(ll.longitude, 'E', 'W')
+            ),
+            Self::Ddm => format_compact!(
+                "{} {}",
+                ddm(ll.latitude, 'N', 'S'),
+                ddm(ll.longitude, 'E', 'W')
+            ),
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ArtilleryAdjustment {
     adjust: Vector2,
@@ -144,6 +209,7 @@ pub struct Contact {
     pub typ: Vehicle,
     pub tags: UnitTags,
     pub last_move: Option<DateTime<Utc>>,
+    pub score: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -249,6 +315,8 @@ pub struct Jtac {
     nearby_alcm: SmallVec<[(GroupId, i32); 8]>,
     menu_dirty: bool,
     air: bool,
+    brief: Option<(Option<Ucid>, BriefFormat)>,
+    taken: FxHashSet<EnId>,
 }
 
 impl Jtac {
@@ -276,6 +344,8 @@ impl Jtac {
             nearby_alcm: smallvec![],
             menu_dirty: false,
             air,
+            brief: None,
+            taken: FxHashSet::default(),
         }
     }
 
@@ -365,6 +435,15 @@ impl Jtac {
                     }
                 }
             }
+            if db.ephemeral.cfg.jtac_scoring.is_some() {
+                write!(msg, "\nPriority: ")?;
+                for (i, (id, ct)) in self.contacts.iter().take(5).enumerate() {
+                    if i > 0 {
+                        write!(msg, ", ")?;
+                    }
+                    write!(msg, "{} {:.0}", get_typ(db, id)?, ct.score)?;
+                }
+            }
         }
         write!(
             msg,
@@ -405,6 +484,95 @@ impl Jtac {
         Ok(msg)
     }
 
+    /// Generate a nine line style CAS brief for the current target.
+    /// The initial point is the objective the jtac is reported from.
+    pub fn nineline(&self, db: &Db, lua: MizLua, fmt: BriefFormat) -> Result<CompactString> {
+        use std::fmt::Write;
+        let target = self
+            .target
+            .as_ref()
+            .ok_or_else(|| anyhow!("jtac {} has no target", self.gid))?;
+        let tpos = Vector2::new(target.pos.x, target.pos.z);
+        let ip = db.objective(&self.location.oid)?;
+        let ip_pos = ip.zone.pos();
+        let elevation = Land::singleton(lua)?.get_height(LuaVec2(tpos))?;
+        let coord = Coord::singleton(lua)?;
+        let ll = coord.lo_to_ll(LuaVec3(Vector3::new(tpos.x, elevation, tpos.y)))?;
+        let near_target = self
+            .contacts
+            .values()
+            .filter(|ct| {
+                na::distance_squared(&Vector2::new(ct.pos.x, ct.pos.z).into(), &tpos.into())
+                    <= 250_000.
+            })
+            .count();
+        let mut mark = format_compact!("laser {}", self.code);
+        if self.ir_pointer {
+            mark.push_str(", IR pointer");
+        }
+        if Utc::now() - self.last_smoke < Duration::minutes(5) {
+            mark.push_str(", smoke");
+        }
+        let friendlies = db
+            .persisted
+            .units
+            .into_iter()
+            .filter(|(_, u)| u.side == self.side && !u.dead && !u.tags.contains(UnitTag::Aircraft))
+            .map(|(_, u)| (na::distance(&u.pos.into(), &tpos.into()), u.pos))
+            .min_by(|(d0, _), (d1, _)| d0.total_cmp(d1));
+        let egress =
+            Db::objective_near_point(&db.persisted.objectives, tpos, |o| o.owner == self.side);
+        let mut msg = CompactString::new("");
+        write!(msg, "JTAC {} nine line\n", self.gid)?;
+        write!(msg, "1. IP: {}\n", ip.name)?;
+        write!(
+            msg,
+            "2. Heading: {:03}\n",
+            radians_to_degrees(azumith2d_to(ip_pos, tpos)) as u32
+        )?;
+        write!(
+            msg,
+            "3. Distance: {:.1}nm\n",
+            na::distance(&ip_pos.into(), &tpos.into()) / 1852.
+        )?;
+        write!(
+            msg,
+            "4. Elevation: {}ft MSL\n",
+            (elevation * 3.28084) as i32
+        )?;
+        if near_target > 1 {
+            write!(
+                msg,
+                "5. Target: {} in a group of {near_target}\n",
+                target.typ
+            )?;
+        } else {
+            write!(msg, "5. Target: {}\n", target.typ)?;
+        }
+        write!(msg, "6. Location: {}\n", fmt.format(&coord, ll)?)?;
+        write!(msg, "7. Mark: {mark}\n")?;
+        match friendlies {
+            Some((dist, pos)) if dist <= 10_000. => write!(
+                msg,
+                "8. Friendlies: {} {}m\n",
+                cardinal(azumith2d_to(tpos, pos)),
+                dist as u32
+            )?,
+            Some(_) | None => write!(msg, "8. Friendlies: none within 10km\n")?,
+        }
+        match egress {
+            Some((_, _, obj)) => write!(
+                msg,
+                "9. Egress: {} toward {}\n",
+                cardinal(azumith2d_to(tpos, obj.zone.pos())),
+                obj.name
+            )?,
+            None => write!(msg, "9. Egress: reverse of ingress\n")?,
+        }
+        write!(msg, "Remarks: laser code {}", self.code)?;
+        Ok(msg)
+    }
+
     fn add_unit_contact(&mut self, unit: &SpawnedUnit) {
         let ct = self.contacts.entry(EnId::Unit(unit.id)).or_default();
         ct.pos = unit.position.p.0;
@@ -603,18 +771,92 @@ impl Jtac {
         Ok(false)
     }
 
-    fn sort_contacts(&mut self, db: &Db, lua: MizLua) -> Result<bool> {
-        let plist = &self.priority;
-        let priority = |tags: UnitTags| {
-            plist
-                .iter()
-                .enumerate()
-                .find(|(_, p)| tags.contains(p.0))
-                .map(|(i, _)| i)
-                .unwrap_or(plist.len())
+    fn score_contacts(&mut self, db: &Db, cfg: &JtacScoringCfg) {
+        let now = Utc::now();
+        let threat_dist = (cfg.threat_distance as f64).powi(2);
+        let near_contact = |pos: Vector2| {
+            self.contacts.values().any(|ct| {
+                na::distance_squared(&Vector2::new(ct.pos.x, ct.pos.z).into(), &pos.into())
+                    <= threat_dist
+            })
         };
-        self.contacts
-            .sort_by(|_, ct0, _, ct1| priority(ct0.tags).cmp(&priority(ct1.tags)));
+        let friendlies: SmallVec<[Vector2; 64]> = db
+            .persisted
+            .objectives
+            .into_iter()
+            .filter(|(_, o)| o.owner == self.side)
+            .map(|(_, o)| o.zone.pos())
+            .chain(
+                db.persisted
+                    .units
+                    .into_iter()
+                    .filter(|(_, u)| {
+                        u.side == self.side && !u.dead && !u.tags.contains(UnitTag::Aircraft)
+                    })
+                    .map(|(_, u)| u.pos),
+            )
+            .filter(|pos| near_contact(*pos))
+            .collect();
+        let max_stationary = cfg.max_stationary_minutes as f32;
+        for ct in self.contacts.values_mut() {
+            let pos = Vector2::new(ct.pos.x, ct.pos.z);
+            let mut score = cfg
+                .tag_weights
+                .iter()
+                .filter(|(tag, _)| ct.tags.contains(**tag))
+                .map(|(_, w)| *w)
+                .sum::<f32>();
+            if friendlies
+                .iter()
+                .any(|fpos| na::distance_squared(&(*fpos).into(), &pos.into()) <= threat_dist)
+            {
+                score += cfg.threat;
+            }
+            if ct.tags.contains(UnitTag::SAM | UnitTag::TrackRadar) {
+                score += cfg.track_radar;
+            } else if ct.tags.contains(UnitTag::SAM | UnitTag::Launcher) {
+                score += cfg.launcher;
+            }
+            let km = na::distance(&self.location.pos.into(), &pos.into()) / 1000.;
+            score -= cfg.distance_per_km * km as f32;
+            let stationary = match ct.last_move {
+                None => max_stationary,
+                Some(ts) => ((now - ts).num_seconds() as f32 / 60.).min(max_stationary),
+            };
+            score += cfg.stationary_per_minute * stationary;
+            ct.score = score;
+        }
+    }
+
+    fn sort_contacts(&mut self, db: &Db, lua: MizLua) -> Result<bool> {
+        match db.ephemeral.cfg.jtac_scoring.as_ref() {
+            Some(cfg) => {
+                self.score_contacts(db, cfg);
+                let taken = &self.taken;
+                self.contacts.sort_by(|id0, ct0, id1, ct1| {
+                    taken
+                        .contains(id0)
+                        .cmp(&taken.contains(id1))
+                        .then(ct1.score.total_cmp(&ct0.score))
+                });
+            }
+            None => {
+                let taken = &self.taken;
+                let plist = &self.priority;
+                let priority = |tags: UnitTags| {
+                    plist
+                        .iter()
+                        .enumerate()
+                        .find(|(_, p)| tags.contains(p.0))
+                        .map(|(i, _)| i)
+                        .unwrap_or(plist.len())
+                };
+                self.contacts.sort_by(|id0, ct0, id1, ct1| {
+                    (taken.contains(id0), priority(ct0.tags))
+                        .cmp(&(taken.contains(id1), priority(ct1.tags)))
+                });
+            }
+        }
         if self.autoshift.is_none() && !self.contacts.is_empty() {
             return self.set_target(db, lua, 0).context("setting target");
         }
@@ -1150,6 +1392,105 @@ impl Jtac {
     pub fn nearby_alcm(&self) -> &[(GroupId, i32)] {
         &self.nearby_alcm
     }
+
+    /// Who asked for the last nine line brief (None for the whole
+    /// side) and its format, if any. The brief is sent again to the
+    /// same audience when the target is shifted.
+    pub fn brief(&self) -> Option<(Option<Ucid>, BriefFormat)> {
+        self.brief
+    }
+
+    pub fn set_brief(&mut self, to: Option<Ucid>, fmt: BriefFormat) {
+        self.brief = Some((to, fmt));
+    }
+}
+
+/// A burst of kills on a jtac's contacts is reported once there have
+/// been no more kills for this long
+const BDA_QUIET: Duration = Duration::seconds(60);
+
+/// How long a strike request through a jtac is credited in its
+/// battle damage assessment
+const BDA_REQUEST_TTL: Duration = Duration::minutes(30);
+
+/// The categories reported in a battle damage assessment, a unit is
+/// counted in the first category it matches
+const BDA_CATEGORIES: [UnitTag; 10] = [
+    UnitTag::SAM,
+    UnitTag::AAA,
+    UnitTag::EWR,
+    UnitTag::Armor,
+    UnitTag::APC,
+    UnitTag::Artillery,
+    UnitTag::Logistics,
+    UnitTag::Infantry,
+    UnitTag::Boat,
+    UnitTag::Aircraft,
+];
+
+fn bda_count(
+    by_category: &mut SmallVec<[(Option<UnitTag>, BdaCount); 4]>,
+    tags: UnitTags,
+) -> &mut BdaCount {
+    let cat = BDA_CATEGORIES.iter().copied().find(|t| tags.contains(*t));
+    let i = match by_category.iter().position(|(c, _)| *c == cat) {
+        Some(i) => i,
+        None => {
+            by_category.push((cat, BdaCount::default()));
+            by_category.len() - 1
+        }
+    };
+    &mut by_category[i].1
+}
+
+#[derive(Debug, Clone)]
+struct BdaKill {
+    id: EnId,
+    tags: UnitTags,
+    scored_by: Option<Ucid>,
+}
+
+#[derive(Debug, Clone)]
+struct Bda {
+    side: Side,
+    last: DateTime<Utc>,
+    kills: Vec<BdaKill>,
+    requested_by: Option<(Ucid, DateTime<Utc>)>,
+}
+
+impl Bda {
+    fn new(side: Side, now: DateTime<Utc>) -> Self {
+        Self {
+            side,
+            last: now,
+            kills: vec![],
+            requested_by: None,
+        }
+    }
+}
+
+/// How long a player's claim on a jtac target lasts
+const CLAIM_TTL: Duration = Duration::minutes(15);
+
+/// How far from the requested point to look for a target to assign
+const TARGET_REQUEST_RANGE: f64 = 15_000.;
+
+#[derive(Debug, Clone)]
+struct Claim {
+    ucid: Ucid,
+    ts: DateTime<Utc>,
+}
+
+/// A lased target on a side's target board
+#[derive(Debug, Clone)]
+pub struct BoardEntry {
+    pub jtac: JtId,
+    pub oid: ObjectiveId,
+    pub code: u16,
+    pub target: EnId,
+    pub typ: Vehicle,
+    pub pos: Vector3,
+    pub claimed_by: Option<Ucid>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -1165,6 +1506,8 @@ pub struct Jtacs {
     artillery_adjustment: FxHashMap<GroupId, ArtilleryAdjustment>,
     code_by_location: LocByCode,
     menu_dirty: FxHashMap<Side, FxHashSet<ObjectiveId>>,
+    bda: FxHashMap<JtId, Bda>,
+    claims: FxHashMap<EnId, Claim>,
 }
 
 impl Jtacs {
@@ -1350,6 +1693,14 @@ impl Jtacs {
         })
     }
 
+    /// The enemy units each side's jtacs can currently see
+    pub fn spotted<'a>(&'a self) -> impl Iterator<Item = (Side, EnId)> + 'a {
+        self.jtacs.iter().flat_map(|(side, jtx)| {
+            jtx.values()
+                .flat_map(move |jt| jt.contacts.keys().map(move |id| (*side, *id)))
+        })
+    }
+
     pub fn contacts_near_point<'a>(
         &'a self,
         side: Side,
@@ -1411,6 +1762,302 @@ impl Jtacs {
         &self.code_by_location
     }
 
+    pub fn claimed_by(&self, id: &EnId) -> Option<&Ucid> {
+        self.claims.get(id).map(|c| &c.ucid)
+    }
+
+    /// Claim the jtac's current target for ucid so the rest of the
+    /// side can see who is working it, or release the claim if ucid
+    /// already holds it. Returns true if the target is now claimed.
+    pub fn toggle_claim(&mut self, jtid: &JtId, ucid: &Ucid) -> Result<bool> {
+        let id = self
+            .get(jtid)?
+            .target
+            .as_ref()
+            .map(|t| t.id)
+            .ok_or_else(|| anyhow!("jtac {jtid} has no target"))?;
+        let now = Utc::now();
+        match self.claims.get(&id) {
+            Some(claim) if &claim.ucid == ucid => {
+                self.claims.remove(&id);
+                Ok(false)
+            }
+            Some(claim) if now - claim.ts < CLAIM_TTL => bail!("the target is already claimed"),
+            Some(_) | None => {
+                self.claims.insert(
+                    id,
+                    Claim {
+                        ucid: *ucid,
+                        ts: now,
+                    },
+                );
+                Ok(true)
+            }
+        }
+    }
+
+    /// The side's target board, every lased target along with the
+    /// jtac and code lasing it and who claimed it
+    pub fn board<'a>(&'a self, side: Side) -> impl Iterator<Item = BoardEntry> + 'a {
+        self.jtacs
+            .get(&side)
+            .into_iter()
+            .flat_map(|jtx| jtx.values())
+            .filter_map(|jt| {
+                let target = jt.target.as_ref()?;
+                Some(BoardEntry {
+                    jtac: jt.gid,
+                    oid: jt.location.oid,
+                    code: jt.code,
+                    target: target.id,
+                    typ: target.typ.clone(),
+                    pos: target.pos,
+                    claimed_by: self.claimed_by(&target.id).copied(),
+                })
+            })
+    }
+
+    pub fn board_msg(&self, db: &Db, side: Side) -> Result<CompactString> {
+        use std::fmt::Write;
+        let mut board: SmallVec<[(String, BoardEntry); 32]> = self
+            .board(side)
+            .map(|e| {
+                let near = db
+                    .objective(&e.oid)
+                    .map(|o| o.name.clone())
+                    .unwrap_or_else(|_| "unknown".into());
+                (near, e)
+            })
+            .collect();
+        board.sort_by(|(n0, _), (n1, _)| n0.cmp(n1));
+        let lased: FxHashSet<EnId> = board.iter().map(|(_, e)| e.target).collect();
+        let unlased = self
+            .jtacs
+            .get(&side)
+            .into_iter()
+            .flat_map(|jtx| jtx.values())
+            .flat_map(|jt| jt.contacts.keys())
+            .filter(|id| !lased.contains(*id))
+            .collect::<FxHashSet<_>>()
+            .len();
+        let mut msg = CompactString::new("TARGET BOARD\n");
+        if board.is_empty() {
+            msg.push_str("no targets are lased\n");
+        }
+        for (near, e) in &board {
+            write!(msg, "{near} jtac {} code {}: {}", e.jtac, e.code, e.typ)?;
+            match e.claimed_by.as_ref().and_then(|ucid| db.player(ucid)) {
+                Some(player) => write!(msg, ", claimed by {}\n", player.name)?,
+                None => write!(msg, ", unclaimed\n")?,
+            }
+        }
+        write!(msg, "{unlased} other contacts are not lased")?;
+        Ok(msg)
+    }
+
+    /// Find the closest unclaimed contact matching any of tags near
+    /// point, prefering targets that are already lased, shift the best
+    /// placed jtac onto it, and claim it for ucid. Returns the jtac
+    /// lasing the target.
+    pub fn request_target(
+        &mut self,
+        db: &Db,
+        lua: MizLua,
+        side: Side,
+        ucid: &Ucid,
+        point: Vector2,
+        tags: UnitTags,
+    ) -> Result<JtId> {
+        let now = Utc::now();
+        let lased: FxHashSet<EnId> = self.jtac_targets().collect();
+        let claimed = |id: &EnId| {
+            self.claims
+                .get(id)
+                .map(|c| &c.ucid != ucid && now - c.ts < CLAIM_TTL)
+                .unwrap_or(false)
+        };
+        let (id, _) = self
+            .contacts_near_point(side, point, TARGET_REQUEST_RANGE)
+            .filter(|(id, ct)| ct.tags.intersects(tags.0) && !claimed(id))
+            .map(|(id, ct)| {
+                let pos = Vector2::new(ct.pos.x, ct.pos.z);
+                let dist = na::distance_squared(&pos.into(), &point.into());
+                (*id, (!lased.contains(id), dist))
+            })
+            .min_by(|(_, (l0, d0)), (_, (l1, d1))| l0.cmp(l1).then(d0.total_cmp(d1)))
+            .ok_or_else(|| {
+                anyhow!(
+                    "no unclaimed {tags} contacts within {}km",
+                    TARGET_REQUEST_RANGE / 1000.
+                )
+            })?;
+        let claims = &self.claims;
+        let jtx = self
+            .jtacs
+            .get_mut(&side)
+            .ok_or_else(|| anyhow!("no jtacs on {side}"))?;
+        let lasing = jtx
+            .values()
+            .find(|jt| jt.target.as_ref().map(|t| t.id) == Some(id))
+            .map(|jt| jt.gid);
+        let jtid = match lasing {
+            Some(jtid) => jtid,
+            None => {
+                // prefer a jtac that isn't busy with a claimed target
+                let jt = jtx
+                    .values_mut()
+                    .filter(|jt| jt.contacts.contains_key(&id))
+                    .min_by_key(|jt| {
+                        let busy = jt
+                            .target
+                            .as_ref()
+                            .map(|t| claims.contains_key(&t.id))
+                            .unwrap_or(false);
+                        (busy, jt.target.is_some())
+                    })
+                    .ok_or_else(|| anyhow!("no jtac can see the target"))?;
+                let i = jt
+                    .contacts
+                    .get_index_of(&id)
+                    .ok_or_else(|| anyhow!("missing contact"))?;
+                jt.autoshift = Some(i);
+                jt.set_target(db, lua, i).context("setting target")?;
+                jt.gid
+            }
+        };
+        self.claims.insert(
+            id,
+            Claim {
+                ucid: *ucid,
+                ts: now,
+            },
+        );
+        Ok(jtid)
+    }
+
+    /// Record that ucid requested a strike directed by the jtac, so
+    /// they are credited in the battle damage assessment
+    pub fn bda_requested(&mut self, jtid: &JtId, ucid: &Ucid) {
+        let now = Utc::now();
+        if let Ok(jt) = self.get(jtid) {
+            let side = jt.side;
+            let bda = self.bda.entry(*jtid).or_insert_with(|| Bda::new(side, now));
+            bda.requested_by = Some((*ucid, now));
+        }
+    }
+
+    /// Credit the shooter of a kill that is part of a pending battle
+    /// damage assessment
+    pub fn bda_credit_kill(&mut self, dead: &Dead) {
+        let id = match &dead.victim {
+            Who::AI { uid, .. } => EnId::Unit(*uid),
+            Who::Player { ucid, .. } => EnId::Player(*ucid),
+        };
+        let shooter = dead
+            .shots
+            .iter()
+            .rev()
+            .find(|s| s.hit)
+            .or_else(|| dead.shots.last())
+            .and_then(|s| s.shooter.ucid().copied());
+        for bda in self.bda.values_mut() {
+            for kill in bda.kills.iter_mut().filter(|k| k.id == id) {
+                kill.scored_by = shooter;
+            }
+        }
+    }
+
+    /// Report the battle damage assessment of bursts of kills on jtac
+    /// contacts that have gone quiet
+    pub fn report_bda(&mut self, db: &mut Db, shots: &ShotDb, now: DateTime<Utc>) -> Result<()> {
+        use std::fmt::Write;
+        let ready: SmallVec<[JtId; 4]> = self
+            .bda
+            .iter()
+            .filter(|(_, bda)| !bda.kills.is_empty() && now - bda.last >= BDA_QUIET)
+            .map(|(id, _)| *id)
+            .collect();
+        for jtid in ready {
+            let bda = match self.bda.remove(&jtid) {
+                Some(bda) => bda,
+                None => continue,
+            };
+            let mut by_category: SmallVec<[(Option<UnitTag>, BdaCount); 4]> = smallvec![];
+            for kill in &bda.kills {
+                bda_count(&mut by_category, kill.tags).destroyed += 1;
+            }
+            if let Ok(jt) = self.get(&jtid) {
+                for (id, ct) in &jt.contacts {
+                    if bda.kills.iter().any(|k| &k.id == id) {
+                        continue;
+                    }
+                    let damaged = match id {
+                        EnId::Unit(uid) => db
+                            .ephemeral
+                            .get_object_id_by_uid(uid)
+                            .map(|oid| shots.damaged(oid))
+                            .unwrap_or(false),
+                        EnId::Player(_) => false,
+                    };
+                    let c = bda_count(&mut by_category, ct.tags);
+                    if damaged {
+                        c.damaged += 1
+                    } else {
+                        c.remaining += 1
+                    }
+                }
+            }
+            let mut scored_by: SmallVec<[(Ucid, u32); 4]> = smallvec![];
+            for ucid in bda.kills.iter().filter_map(|k| k.scored_by) {
+                match scored_by.iter_mut().find(|(u, _)| *u == ucid) {
+                    Some((_, n)) => *n += 1,
+                    None => scored_by.push((ucid, 1)),
+                }
+            }
+            let requested_by = bda
+                .requested_by
+                .filter(|(_, ts)| now - *ts <= BDA_REQUEST_TTL)
+                .map(|(ucid, _)| ucid);
+            let mut msg = format_compact!("BATTLE DAMAGE ASSESSMENT jtac {jtid}\n");
+            if let Some(ucid) = &requested_by
+                && let Some(p) = db.player(ucid)
+            {
+                write!(msg, "strike requested by {}\n", p.name)?;
+            }
+            for (cat, c) in &by_category {
+                let cat = match cat {
+                    Some(tag) => format_compact!("{tag:?}"),
+                    None => format_compact!("Other"),
+                };
+                write!(
+                    msg,
+                    "{cat}: {} destroyed, {} damaged, {} remaining\n",
+                    c.destroyed, c.damaged, c.remaining
+                )?;
+            }
+            for (ucid, n) in &scored_by {
+                if let Some(p) = db.player(ucid) {
+                    write!(msg, "{} scored {n}\n", p.name)?;
+                }
+            }
+            db.ephemeral.msgs().panel_to_side(20, false, bda.side, msg);
+            db.ephemeral.stat(Stat::Bda {
+                side: bda.side,
+                requested_by,
+                by_category,
+                scored_by,
+            });
+        }
+        self.bda.retain(|_, bda| {
+            !bda.kills.is_empty()
+                || bda
+                    .requested_by
+                    .map(|(_, ts)| now - ts <= BDA_REQUEST_TTL)
+                    .unwrap_or(false)
+        });
+        Ok(())
+    }
+
     pub fn unit_dead(&mut self, lua: MizLua, db: &mut Db, id: &DcsOid<ClassUnit>) -> Result<()> {
         let ctid = db
             .ephemeral
@@ -1421,6 +2068,39 @@ impl Jtacs {
                     .get_uid_by_object_id(id)
                     .map(|uid| EnId::Unit(*uid))
             });
+        if let Some(ctid) = ctid {
+            let now = Utc::now();
+            self.claims.remove(&ctid);
+            // credit the kill to the jtac lasing it, or else the
+            // nearest jtac that had it as a contact
+            let not_lasing = |jt: &Jtac| jt.target.as_ref().map(|t| t.id != ctid).unwrap_or(true);
+            let dist = |jt: &Jtac, ct: &Contact| {
+                let pos = Vector2::new(ct.pos.x, ct.pos.z);
+                na::distance_squared(&jt.location.pos.into(), &pos.into())
+            };
+            let credit = self
+                .jtacs
+                .values()
+                .flat_map(|jtx| jtx.values())
+                .filter_map(|jt| jt.contacts.get(&ctid).map(|ct| (jt, ct)))
+                .min_by(|(jt0, ct0), (jt1, ct1)| {
+                    not_lasing(jt0)
+                        .cmp(&not_lasing(jt1))
+                        .then(dist(jt0, ct0).total_cmp(&dist(jt1, ct1)))
+                });
+            if let Some((jt, ct)) = credit {
+                let bda = self
+                    .bda
+                    .entry(jt.gid)
+                    .or_insert_with(|| Bda::new(jt.side, now));
+                bda.last = now;
+                bda.kills.push(BdaKill {
+                    id: ctid,
+                    tags: ct.tags,
+                    scored_by: None,
+                });
+            }
+        }
         let jtid = {
             let sl = db.ephemeral.get_slot_by_object_id(id).map(|sl| *sl);
             match &ctid {
@@ -1776,11 +2456,36 @@ impl Jtacs {
                 }
             }
         }
+        let now = Utc::now();
+        self.claims
+            .retain(|id, claim| saw_units.contains(id) && now - claim.ts < CLAIM_TTL);
+        let claims = &self.claims;
         let mut new_contacts: SmallVec<[&Jtac; 32]> = smallvec![];
         for j in self.jtacs.values_mut() {
+            // targets held by manually shifted jtacs or claimed by a
+            // player stay put, the rest of the jtacs deconflict around
+            // them and around each other
+            let mut held: FxHashMap<EnId, JtId> = j
+                .values()
+                .filter_map(|jt| {
+                    let target = jt.target.as_ref()?;
+                    (jt.autoshift.is_some() || claims.contains_key(&target.id))
+                        .then_some((target.id, jt.gid))
+                })
+                .collect();
             for (_, jtac) in j.iter_mut() {
                 jtac.nearby_alcm = db.alcm_near_point(jtac.side, lua, jtac.location().pos);
-                match jtac.sort_contacts(db, lua) {
+                jtac.taken.clear();
+                jtac.taken.extend(
+                    held.iter()
+                        .filter(|(_, gid)| **gid != jtac.gid)
+                        .map(|(id, _)| *id),
+                );
+                let res = jtac.sort_contacts(db, lua);
+                if let Some(target) = &jtac.target {
+                    held.entry(target.id).or_insert(jtac.gid);
+                }
+                match res {
                     Ok(false) => (),
                     Ok(true) => new_contacts.push(jtac),
                     Err(e) => warn!("could not sort contacts for jtac {}, {:?}", jtac.gid, e),
@@ -15,6 +15,7 @@ for more details.
 */
 
 mod admin;
+mod awacs;
 mod bg;
 mod chatcmd;
 mod db;
@@ -30,6 +31,7 @@ extern crate nalgebra as na;
 use crate::db::player::SlotAuth;
 use admin::{AdminCommand, AdminResult, run_admin_commands};
 use anyhow::{Context as AnyhowContext, Result, anyhow, bail};
+use awacs::Awacs;
 use bfprotocols::{
     cfg::{Cfg, LifeType},
     db::objective::ObjectiveId,
@@ -37,7 +39,7 @@ use bfprotocols::{
     stats::Stat,
 };
 use bg::Task;
-use chatcmd::{run_action_commands, run_jtac_commands};
+use chatcmd::{run_action_commands, run_jtac_commands, run_upgrade_commands};
 use chrono::{Duration, prelude::*};
 use compact_str::{CompactString, format_compact};
 use crossbeam::queue::SegQueue;
@@ -47,8 +49,9 @@ use db::{
     player::{RegErr, TakeoffRes},
 };
 use dcso3::{
-    HooksLua, LuaEnv, MizLua, String,
+    HooksLua, LuaEnv, LuaVec3, MizLua, String, Vector2,
     coalition::Side,
+    coord::Coord,
     env::{
         self, Env,
         miz::{Miz, UnitId},
@@ -64,7 +67,7 @@ use dcso3::{
     unit::{ClassUnit, Unit},
     world::{HandlerId, MarkPanel, World},
 };
-use ewr::Ewr;
+use ewr::{Ewr, EwrFormat};
 use fxhash::{FxBuildHasher, FxHashMap, FxHashSet};
 use indexmap::IndexSet;
 use jtac::{JtId, Jtacs};
@@ -240,7 +243,9 @@ struct Context {
     external_admin_commands: Arc<SegQueue<(AdminCommand, oneshot::Sender<Value>)>>,
     admin_commands: Vec<(admin::Caller, AdminCommand)>,
     action_commands: Vec<(PlayerId, String)>,
+    upgrade_commands: Vec<(PlayerId, String)>,
     jtac_commands: Vec<(PlayerId, JtId, String)>,
+    target_requests: Vec<(PlayerId, String)>,
     to_background: Option<UnboundedSender<bg::Task>>,
     recently_landed: FxHashMap<DcsOid<ClassUnit>, DateTime<Utc>>,
     recently_born: FxHashMap<DcsOid<ClassUnit>, DateTime<Utc>>,
@@ -258,6 +263,7 @@ struct Context {
     connected: Connected,
     landcache: LandCache,
     ewr: Ewr,
+    awacs: Awacs,
     jtac: Jtacs,
 }
 
@@ -451,6 +457,36 @@ fn process_slot_rejection(ctx: &mut Context, id: PlayerId, ucid: Ucid, rej: Slot
             let msg = format_compact!("Objective does not have any {} in stock", vehicle.0);
             ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
         }
+        SlotAuth::LowFuel {
+            life_type,
+            fuel,
+            required,
+        } => {
+            let msg = format_compact!(
+                "Objective fuel is at {fuel}%, {life_type} slots require at least {required}%"
+            );
+            ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
+        }
+        SlotAuth::LowSupply {
+            life_type,
+            supply,
+            required,
+        } => {
+            let msg = format_compact!(
+                "Objective supply is at {supply}%, {life_type} slots require at least {required}%"
+            );
+            ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
+        }
+        SlotAuth::UpgradeRequired(tier) => {
+            let msg = format_compact!("This slot requires objective upgrade tier {tier}");
+            ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
+        }
+        SlotAuth::CarrierDamaged { health, required } => {
+            let msg = format_compact!(
+                "The carrier is at {health}% health, its slots require at least {required}%"
+            );
+            ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
+        }
         SlotAuth::ObjectiveHasNoLogistics => {
             let msg = format_compact!("Objective is capturable");
             ctx.db.ephemeral.msgs().send(MsgTyp::Chat(Some(id)), msg);
@@ -664,6 +700,14 @@ fn on_event(lua: MizLua, ev: Event) -> Result<()> {
         Event::Ejection(e) => {
             if let Ok(unit) = e.initiator.as_unit() {
                 let id = unit.object_id()?;
+                if ctx.db.ephemeral.cfg.csar.is_some() {
+                    let pos = unit.get_point()?;
+                    let spctx = SpawnCtx::new(lua)?;
+                    let pos = Vector2::new(pos.x, pos.z);
+                    if let Err(e) = ctx.db.pilot_ejected(&spctx, &ctx.idx, &id, pos, start_ts) {
+                        error!("failed to spawn downed pilot {:?} {:?}", id, e)
+                    }
+                }
                 if let Err(e) = unit_killed(lua, ctx, id, start_ts) {
                     error!("2 unit killed failed {}", e)
                 }
@@ -687,6 +731,23 @@ fn on_event(lua: MizLua, ev: Event) -> Result<()> {
                             }
                             let _ = menu::cargo::list_cargo_for_slot(ctx, &slot);
                         }
+                        Ok(TakeoffRes::WeaponRestricted {
+                            weapon,
+                            supply,
+                            required,
+                        }) => {
+                            let msg = format_compact!(
+                                "Objective supply is at {supply}%, {weapon} requires at least {required}%"
+                            );
+                            if let Some(uid) = slot.as_unit_id() {
+                                ctx.db.ephemeral.msgs().panel_to_unit(10, false, uid, msg);
+                            }
+                            if let Err(e) = unit.destroy() {
+                                error!(
+                                    "failed to destroy unit that took off with restricted weapons {e:?}"
+                                )
+                            }
+                        }
                         Ok(TakeoffRes::OutOfLives | TakeoffRes::OutOfPoints) => {
                             if let Err(e) = unit.destroy() {
                                 error!(
@@ -850,6 +911,19 @@ fn generate_ewr_reports(ctx: &mut Context, now: DateTime<Utc>) -> Result<()> {
             Some(uid) => uid,
             None => continue,
         };
+        let (ewr_mode, ewr_delay) = (
+            ctx.db.ephemeral.cfg.ewr_mode,
+            ctx.db.ephemeral.cfg.ewr_delay,
+        );
+        if ctx.ewr.format(ucid) == EwrFormat::Picture {
+            if let Some(report) = ctx
+                .ewr
+                .picture(now, false, ucid, player, inst, ewr_mode, ewr_delay)
+            {
+                msgs.push((uid, report));
+            }
+            continue;
+        }
         let braa_to_chickens = ctx.ewr.where_chicken(now, false, false, ucid, player, inst, ctx.db.ephemeral.cfg.ewr_mode, ctx.db.ephemeral.cfg.ewr_delay);
         if !braa_to_chickens.is_empty() {
             let mut report = format_compact!("Bandits BRAA\n");
@@ -866,6 +940,30 @@ fn generate_ewr_reports(ctx: &mut Context, now: DateTime<Utc>) -> Result<()> {
     Ok(())
 }
 
+fn generate_threat_warnings(ctx: &mut Context, now: DateTime<Utc>) {
+    let cfg = match ctx.db.ephemeral.cfg.ewr_threat.as_ref() {
+        Some(cfg) => cfg,
+        None => return,
+    };
+    let mut msgs: SmallVec<[(UnitId, CompactString); 16]> = smallvec![];
+    for (ucid, player, inst) in ctx.db.instanced_players() {
+        let uid = match player
+            .current_slot
+            .as_ref()
+            .and_then(|(sl, _)| sl.as_unit_id())
+        {
+            Some(uid) => uid,
+            None => continue,
+        };
+        if let Some(msg) = ctx.ewr.threats(now, cfg, ucid, player, inst) {
+            msgs.push((uid, msg));
+        }
+    }
+    for (uid, msg) in msgs {
+        ctx.db.ephemeral.msgs().panel_to_unit(10, true, uid, msg)
+    }
+}
+
 fn check_auto_shutdown(ctx: &mut Context, lua: MizLua, now: DateTime<Utc>) -> Result<AdminResult> {
     if let Some(asd) = ctx.shutdown.as_mut() {
         if asd.when - now <= Duration::minutes(30) && !asd.thirty_minute_warning {
@@ -1008,6 +1106,37 @@ fn award_periodic_points(ctx: &mut Context, ts: DateTime<Utc>) {
     }
 }
 
+fn publish_target_board(ctx: &mut Context, lua: MizLua) -> Result<()> {
+    if ctx.db.ephemeral.cfg.netidx_base.is_none() {
+        return Ok(());
+    }
+    let coord = Coord::singleton(lua)?;
+    for side in [Side::Red, Side::Blue] {
+        let mut board = vec![];
+        for e in ctx.jtac.board(side) {
+            let ll = coord.lo_to_ll(LuaVec3(e.pos))?;
+            board.push(bg::BoardRow {
+                jtac: e.jtac.to_string(),
+                objective: ctx
+                    .db
+                    .objective(&e.oid)
+                    .map(|o| o.name.to_string())
+                    .unwrap_or_default(),
+                code: e.code,
+                target: e.typ.to_string(),
+                lat: ll.latitude,
+                lon: ll.longitude,
+                claimed_by: e
+                    .claimed_by
+                    .and_then(|ucid| ctx.db.player(&ucid))
+                    .map(|p| p.name.to_string()),
+            });
+        }
+        ctx.do_bg_task(Task::TargetBoard { side, board });
+    }
+    Ok(())
+}
+
 fn run_slow_timed_events(
     lua: MizLua,
     ctx: &mut Context,
@@ -1043,25 +1172,44 @@ fn run_slow_timed_events(
                 if let Some(points) = cfg.points.as_ref() {
                     ctx.db.award_kill_points(points, &dead)
                 }
+                ctx.jtac.bda_credit_kill(&dead);
                 ctx.do_bg_task(Task::Stat(Stat::Kill(dead)));
             }
+            if let Err(e) = ctx.jtac.report_bda(&mut ctx.db, &ctx.shots_out, ts) {
+                error!("could not report battle damage {e:?}")
+            }
+        }
+        if let Err(e) = publish_target_board(ctx, lua) {
+            error!("could not publish the target board {e:?}")
         }
         if let Err(e) = ctx.db.maybe_do_repairs(ts) {
             error!("error doing repairs {:?}", e)
         }
         record_perf(&mut perf.do_repairs, start_ts);
+        ctx.db.expire_supply_requests(ts);
+        ctx.db.regen_command_budget(ts);
         if let Err(e) = ctx.db.advance_actions(lua, &ctx.idx, &ctx.jtac, start_ts) {
             error!("could not advance actions {e:?}")
         }
         let ts = Utc::now();
+        if let Err(e) = ctx.db.run_jammers(lua) {
+            error!("could not run jammers {e:?}")
+        }
         if let Err(e) = ctx.ewr.update_tracks(lua, &mut ctx.landcache, &ctx.db, ts, ctx.db.ephemeral.cfg.ewr_mode, ctx.db.ephemeral.cfg.ewr_delay) {
             error!("could not update ewr tracks {e}")
         }
+        if let Err(e) = ctx.db.run_recon(lua, &mut ctx.landcache, ts) {
+            error!("could not run recon {e:?}")
+        }
+        ctx.db.update_threat_map(ts, ctx.jtac.spotted());
         record_perf(&mut perf.ewr_tracks, ts);
         let ts = Utc::now();
         if let Err(e) = generate_ewr_reports(ctx, ts) {
             error!("could not generate ewr reports {e}")
         }
+        generate_threat_warnings(ctx, ts);
+        ctx.ewr.update_link16(&mut ctx.db, ts);
+        ctx.awacs.update(&mut ctx.db, &ctx.ewr, ts);
         record_perf(&mut perf.ewr_reports, ts);
         let ts = Utc::now();
         match ctx
@@ -1093,6 +1241,36 @@ fn run_slow_timed_events(
         let ts = Utc::now();
         update_jtac_contacts(ctx, lua);
         record_perf(&mut perf.update_jtac_contacts, ts);
+        if let Err(e) = ctx.db.run_csar(lua, start_ts) {
+            error!("could not run csar {e:?}")
+        }
+        if let Err(e) = ctx.db.run_minefields(lua, start_ts) {
+            error!("could not run minefields {e:?}")
+        }
+        let cfg = &ctx.db.ephemeral.cfg;
+        let spawns = cfg.ground_offensive.is_some()
+            || !cfg.ai_commander.is_empty()
+            || cfg.action_queue.is_some();
+        if spawns {
+            match SpawnCtx::new(lua) {
+                Err(e) => error!("could not create spawn context {e:?}"),
+                Ok(spctx) => {
+                    if let Err(e) = ctx
+                        .db
+                        .run_ground_offensives(lua, &spctx, &ctx.idx, start_ts)
+                    {
+                        error!("could not run ground offensives {e:?}")
+                    }
+                    if !ctx.db.ephemeral.cfg.ai_commander.is_empty() {
+                        ctx.db.run_ai_commanders(
+                            lua, perf, &spctx, &ctx.idx, &ctx.jtac, &ctx.ewr, start_ts,
+                        );
+                    }
+                    ctx.db
+                        .run_action_queue(lua, perf, &spctx, &ctx.idx, &ctx.jtac, start_ts);
+                }
+            }
+        }
         let now = Utc::now();
         if let Some(snap) = ctx.db.maybe_snapshot() {
             ctx.do_bg_task(bg::Task::SaveState(path.clone(), snap));
@@ -1202,6 +1380,9 @@ fn run_timed_events(ctx: &mut Context, lua: MizLua, path: &PathBuf) -> Result<Ad
     if let Err(e) = run_jtac_commands(ctx, lua) {
         error!("failed to run jtac commands {e:?}")
     }
+    if let Err(e) = run_upgrade_commands(ctx, lua) {
+        error!("failed to run upgrade commands {e:?}")
+    }
     ctx.load_state.step();
     record_perf(&mut perf.timed_events, ts);
     ctx.log_perf(now);
@@ -1292,6 +1473,10 @@ fn delayed_init_miz(lua: MizLua) -> Result<()> {
         stop: ctx.shutdown.map(|a| a.when),
         cfg: Box::new((*ctx.db.ephemeral.cfg).clone()),
     }));
+    for side in [Side::Red, Side::Blue] {
+        let bullseye = miz.coalition(side)?.bullseye()?;
+        ctx.ewr.set_bullseye(side, bullseye.0);
+    }
     info!("spawning units");
     ctx.respawn_groups(lua, &miz)
         .context("setting up the mission after load")?;
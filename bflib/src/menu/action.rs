@@ -2,7 +2,7 @@ use super::{ArgPent, ArgQuad, ArgTriple};
 use crate::{
     Context,
     db::{
-        actions::{ActionArgs, ActionCmd, WithObj, WithPos, WithPosAndGroup},
+        actions::{ActionArgs, ActionCmd, WithObj, WithPos, WithPosAndGroup, WithPosAndPackages},
         group::DeployKind,
     },
     spawnctx::SpawnCtx,
@@ -108,9 +108,27 @@ fn do_pos_action(
             cfg: cfg.clone(),
             pos,
         }),
+        ActionKind::Recon(cfg) => ActionArgs::Recon(WithPos {
+            cfg: cfg.clone(),
+            pos,
+        }),
+        ActionKind::Jammer(cfg) => ActionArgs::Jammer(WithPos {
+            cfg: cfg.clone(),
+            pos,
+        }),
+        ActionKind::NavalPatrol(cfg) => ActionArgs::NavalPatrol(WithPos {
+            cfg: cfg.clone(),
+            pos,
+        }),
+        ActionKind::AntiShip(cfg) => ActionArgs::AntiShip(WithPos {
+            cfg: cfg.clone(),
+            pos,
+        }),
         ActionKind::Bomber(_)
+        | ActionKind::ShoreBombardment(_)
         | ActionKind::LogisticsTransfer(_)
         | ActionKind::LogisticsRepair(_)
+        | ActionKind::Airdrop(_)
         | ActionKind::Move(_)
         | ActionKind::Rtb
         | ActionKind::TankerWaypoint
@@ -118,6 +136,7 @@ fn do_pos_action(
         | ActionKind::AwacsWaypoint
         | ActionKind::FighersWaypoint
         | ActionKind::DroneWaypoint
+        | ActionKind::JammerWaypoint
         | ActionKind::AttackersWaypoint
         | ActionKind::SeadWaypoint => bail!("invalid action type for this menu item"),
     };
@@ -231,6 +250,11 @@ fn do_pos_group_action(
             pos,
             group,
         }),
+        ActionKind::JammerWaypoint => ActionArgs::JammerWaypoint(WithPosAndGroup {
+            cfg: (),
+            pos,
+            group,
+        }),
         ActionKind::Move(cfg) => ActionArgs::Move(WithPosAndGroup {
             cfg: cfg.clone(),
             pos,
@@ -251,9 +275,15 @@ fn do_pos_group_action(
         | ActionKind::Tanker(_)
         | ActionKind::Paratrooper(_)
         | ActionKind::Nuke(_)
+        | ActionKind::Recon(_)
+        | ActionKind::Jammer(_)
+        | ActionKind::NavalPatrol(_)
+        | ActionKind::ShoreBombardment(_)
+        | ActionKind::AntiShip(_)
         | ActionKind::Bomber(_)
         | ActionKind::LogisticsTransfer(_)
-        | ActionKind::LogisticsRepair(_) => bail!("invalid action type for this menu item"),
+        | ActionKind::LogisticsRepair(_)
+        | ActionKind::Airdrop(_) => bail!("invalid action type for this menu item"),
     };
     let cmd = ActionCmd { name, action, args };
     run_action(ctx, perf, lua, side, slot, ucid, Some(mark), cmd)
@@ -311,6 +341,11 @@ fn do_objective_action(
             cfg: cfg.clone(),
             oid,
         }),
+        ActionKind::Airdrop(cfg) => ActionArgs::Airdrop(WithPosAndPackages {
+            cfg: cfg.clone(),
+            pos: ctx.db.objective(&oid)?.zone.pos(),
+            packages: 1,
+        }),
         ActionKind::TankerWaypoint
         | ActionKind::AwacsWaypoint
         | ActionKind::CruiseMissileWaypoint
@@ -327,6 +362,12 @@ fn do_objective_action(
         | ActionKind::Tanker(_)
         | ActionKind::Paratrooper(_)
         | ActionKind::Nuke(_)
+        | ActionKind::Recon(_)
+        | ActionKind::Jammer(_)
+        | ActionKind::JammerWaypoint
+        | ActionKind::NavalPatrol(_)
+        | ActionKind::ShoreBombardment(_)
+        | ActionKind::AntiShip(_)
         | ActionKind::Bomber(_)
         | ActionKind::LogisticsTransfer(_)
         | ActionKind::Rtb
@@ -473,7 +514,9 @@ fn add_action_menu(lua: MizLua, arg: ArgTriple<Ucid, GroupId, SlotId>) -> Result
                 }
                 DeployKind::Crate { .. }
                 | DeployKind::Objective { .. }
-                | DeployKind::ObjectiveDeprecated => None,
+                | DeployKind::ObjectiveDeprecated
+                | DeployKind::Offensive { .. }
+                | DeployKind::DownedPilot { .. } => None,
             };
             if let Some(key) = key {
                 let root = mc.add_submenu_for_group(
@@ -537,7 +580,9 @@ fn add_action_menu(lua: MizLua, arg: ArgTriple<Ucid, GroupId, SlotId>) -> Result
             name.clone()
         };
         match &action.kind {
-            ActionKind::Bomber(_) | ActionKind::LogisticsTransfer(_) => (),
+            ActionKind::Bomber(_)
+            | ActionKind::ShoreBombardment(_)
+            | ActionKind::LogisticsTransfer(_) => (),
             ActionKind::AttackersWaypoint
             | ActionKind::SeadWaypoint
             | ActionKind::AwacsWaypoint
@@ -545,7 +590,8 @@ fn add_action_menu(lua: MizLua, arg: ArgTriple<Ucid, GroupId, SlotId>) -> Result
             | ActionKind::CruiseMissileWaypoint
             | ActionKind::FighersWaypoint
             | ActionKind::TankerWaypoint
-            | ActionKind::DroneWaypoint => {
+            | ActionKind::DroneWaypoint
+            | ActionKind::JammerWaypoint => {
                 let root = mc.add_submenu_for_group(arg.snd, title, Some(root.clone()))?;
                 add_pos_group(root.clone(), name.clone(), true)?
             }
@@ -562,11 +608,15 @@ fn add_action_menu(lua: MizLua, arg: ArgTriple<Ucid, GroupId, SlotId>) -> Result
             | ActionKind::Fighters(_)
             | ActionKind::Tanker(_)
             | ActionKind::Paratrooper(_)
+            | ActionKind::Recon(_)
+            | ActionKind::Jammer(_)
+            | ActionKind::NavalPatrol(_)
+            | ActionKind::AntiShip(_)
             | ActionKind::Nuke(_) => {
                 let root = mc.add_submenu_for_group(arg.snd, title, Some(root.clone()))?;
                 add_pos(root.clone(), name.clone())?
             }
-            ActionKind::LogisticsRepair(_) => {
+            ActionKind::LogisticsRepair(_) | ActionKind::Airdrop(_) => {
                 let root = mc.add_submenu_for_group(arg.snd, title, Some(root.clone()))?;
                 add_objective(root.clone(), name.clone())?
             }
@@ -21,6 +21,7 @@ use crate::{
 };
 use anyhow::{Context as ErrContext, Result, anyhow};
 use bfprotocols::cfg::{Cfg, LimitEnforceTyp};
+use chrono::prelude::*;
 use compact_str::{CompactString, ToCompactString, format_compact};
 use dcso3::{
     MizLua, String,
@@ -30,7 +31,7 @@ use dcso3::{
     net::SlotId,
 };
 use fxhash::FxHashMap;
-use std::collections::hash_map::Entry;
+use std::{cmp::max, collections::hash_map::Entry};
 
 fn unpakistan(lua: MizLua, gid: GroupId) -> Result<()> {
     let ctx = unsafe { Context::get_mut() };
@@ -80,6 +81,12 @@ fn load_crate(lua: MizLua, gid: GroupId) -> Result<()> {
                             oid
                         )
                     }
+                    Some(Oldest::Minefield(id)) => {
+                        format_compact!(
+                            "unpacking will delete oldest, minefield {}, when the limit is exceeded",
+                            id
+                        )
+                    }
                     None => {
                         format_compact!("unpacking will delete oldest when the limit is exceeded")
                     }
@@ -167,6 +174,14 @@ pub(crate) fn list_cargo_for_slot(ctx: &mut Context, slot: &SlotId) -> Result<()
         ));
         total += it.troop.weight
     }
+    for p in &cargo.pilots {
+        msg.push_str(&format_compact!(
+            "rescued pilot {} weighing {} kg\n",
+            p.name,
+            p.weight
+        ));
+        total += p.weight
+    }
     if total > 0 {
         msg.push_str("----------------------------\n");
     }
@@ -257,6 +272,27 @@ fn spawn_crate(lua: MizLua, arg: ArgTuple<GroupId, String>) -> Result<()> {
     Ok(())
 }
 
+fn list_supply_requests(lua: MizLua, gid: GroupId) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let (side, _slot) = slot_for_group(lua, ctx, &gid).context("getting slot for group")?;
+    let now = Utc::now();
+    let mut msg = CompactString::new("");
+    for (obj, req) in ctx.db.supply_requests(side) {
+        let mins = max(0, (req.expires - now).num_minutes());
+        msg.push_str(&format_compact!(
+            "{}: {}, {} priority, expires in {mins} minutes\n",
+            obj.name(),
+            req.items(),
+            req.priority
+        ));
+    }
+    if msg.is_empty() {
+        msg.push_str("No open supply requests");
+    }
+    ctx.db.ephemeral.msgs().panel_to_group(10, false, gid, msg);
+    Ok(())
+}
+
 pub(super) fn add_cargo_menu_for_group(
     cfg: &Cfg,
     mc: &MissionCommands,
@@ -306,6 +342,15 @@ pub(super) fn add_cargo_menu_for_group(
         destroy_nearby_crate,
         group,
     )?;
+    if cfg.warehouse.is_some() {
+        mc.add_command_for_group(
+            group,
+            "List Supply Requests".into(),
+            Some(root.clone()),
+            list_supply_requests,
+            group,
+        )?;
+    }
     let root = mc.add_submenu_for_group(group, "Crates".into(), Some(root.clone()))?;
     let rep = &cfg.repair_crate[side];
     let logi = mc.add_submenu_for_group(group, "Logistics".into(), Some(root.clone()))?;
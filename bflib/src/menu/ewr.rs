@@ -14,9 +14,10 @@ FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero Public License
 for more details.
 */
 
-use super::slot_for_group;
+use super::{slot_for_group, ArgTuple};
 use crate::{
-    ewr::{self, EwrUnits},
+    awacs::{self, AwacsRequest},
+    ewr::{self, EwrFormat, EwrUnits},
     Context,
 };
 use anyhow::{Context as ErrContext, Result};
@@ -51,6 +52,21 @@ fn ewr_report(lua: MizLua, gid: GroupId) -> Result<()> {
     if let Some(ucid) = ctx.db.ephemeral.player_in_slot(&slot) {
         if let Some(player) = ctx.db.player(ucid) {
             if let Some((_, Some(inst))) = &player.current_slot {
+                let (ewr_mode, ewr_delay) = (
+                    ctx.db.ephemeral.cfg.ewr_mode,
+                    ctx.db.ephemeral.cfg.ewr_delay,
+                );
+                if ctx.ewr.format(ucid) == EwrFormat::Picture {
+                    report = ctx
+                        .ewr
+                        .picture(Utc::now(), true, ucid, player, inst, ewr_mode, ewr_delay)
+                        .unwrap_or_else(|| "Picture clean".into());
+                    ctx.db
+                        .ephemeral
+                        .msgs()
+                        .panel_to_group(10, false, gid, report);
+                    return Ok(());
+                }
                 let chickens = ctx
                     .ewr
                     .where_chicken(Utc::now(), false, true, ucid, player, inst, ctx.db.ephemeral.cfg.ewr_mode, ctx.db.ephemeral.cfg.ewr_delay);
@@ -68,6 +84,25 @@ fn ewr_report(lua: MizLua, gid: GroupId) -> Result<()> {
     Ok(())
 }
 
+fn toggle_threat_warnings(lua: MizLua, gid: GroupId) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let (_, slot) = slot_for_group(lua, ctx, &gid).context("getting slot for group")?;
+    if let Some(ucid) = ctx.db.ephemeral.player_in_slot(&slot) {
+        let st = if ctx.ewr.toggle_threat_warnings(ucid) {
+            "enabled"
+        } else {
+            "disabled"
+        };
+        ctx.db.ephemeral.msgs().panel_to_group(
+            5,
+            false,
+            gid,
+            format_compact!("threat warnings are {st}"),
+        )
+    }
+    Ok(())
+}
+
 fn friendly_ewr_report(lua: MizLua, gid: GroupId) -> Result<()> {
     let ctx = unsafe { Context::get_mut() };
     let (_, slot) = slot_for_group(lua, ctx, &gid).context("getting slot for group")?;
@@ -118,6 +153,48 @@ fn ewr_units_metric(lua: MizLua, gid: GroupId) -> Result<()> {
     Ok(())
 }
 
+fn ewr_format_picture(lua: MizLua, gid: GroupId) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let (_, slot) = slot_for_group(lua, ctx, &gid).context("getting slot for group")?;
+    if let Some(ucid) = ctx.db.ephemeral.player_in_slot(&slot) {
+        ctx.ewr.set_format(ucid, EwrFormat::Picture);
+        ctx.db.ephemeral.msgs().panel_to_group(
+            5,
+            false,
+            gid,
+            "EWR reports are now bullseye pictures",
+        );
+    }
+    Ok(())
+}
+
+fn ewr_format_braa(lua: MizLua, gid: GroupId) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let (_, slot) = slot_for_group(lua, ctx, &gid).context("getting slot for group")?;
+    if let Some(ucid) = ctx.db.ephemeral.player_in_slot(&slot) {
+        ctx.ewr.set_format(ucid, EwrFormat::Braa);
+        ctx.db
+            .ephemeral
+            .msgs()
+            .panel_to_group(5, false, gid, "EWR reports are now BRAA");
+    }
+    Ok(())
+}
+
+fn awacs_request(lua: MizLua, arg: ArgTuple<GroupId, AwacsRequest>) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let gid = arg.fst;
+    let (_, slot) = slot_for_group(lua, ctx, &gid).context("getting slot for group")?;
+    if let Some(ucid) = ctx.db.ephemeral.player_in_slot(&slot) {
+        let msg = match awacs::request(&ctx.db, &ctx.ewr, Utc::now(), ucid, arg.snd) {
+            Ok(msg) => msg,
+            Err(e) => format_compact!("{e}"),
+        };
+        ctx.db.ephemeral.msgs().panel_to_group(15, false, gid, msg);
+    }
+    Ok(())
+}
+
 pub(super) fn add_ewr_menu_for_group(mc: &MissionCommands, group: GroupId) -> Result<()> {
     let root = mc.add_submenu_for_group(group, "EWR".into(), None)?;
     mc.add_command_for_group(
@@ -134,6 +211,13 @@ pub(super) fn add_ewr_menu_for_group(mc: &MissionCommands, group: GroupId) -> Re
         toggle_ewr,
         group,
     )?;
+    mc.add_command_for_group(
+        group,
+        "Toggle Threat Warnings".into(),
+        Some(root.clone()),
+        toggle_threat_warnings,
+        group,
+    )?;
     mc.add_command_for_group(
         group,
         "Friendly Report".into(),
@@ -155,5 +239,36 @@ pub(super) fn add_ewr_menu_for_group(mc: &MissionCommands, group: GroupId) -> Re
         ewr_units_metric,
         group,
     )?;
+    mc.add_command_for_group(
+        group,
+        "Picture Mode".into(),
+        Some(root.clone()),
+        ewr_format_picture,
+        group,
+    )?;
+    mc.add_command_for_group(
+        group,
+        "BRAA Mode".into(),
+        Some(root.clone()),
+        ewr_format_braa,
+        group,
+    )?;
+    let awacs_root = mc.add_submenu_for_group(group, "AWACS".into(), Some(root.clone()))?;
+    for (title, req) in [
+        ("Picture", AwacsRequest::Picture),
+        ("Bogey Dope", AwacsRequest::BogeyDope),
+        ("Vector to Tanker", AwacsRequest::Tanker),
+    ] {
+        mc.add_command_for_group(
+            group,
+            title.into(),
+            Some(awacs_root.clone()),
+            awacs_request,
+            ArgTuple {
+                fst: group,
+                snd: req,
+            },
+        )?;
+    }
     Ok(())
 }
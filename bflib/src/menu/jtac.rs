@@ -22,16 +22,16 @@ use crate::{
         actions::{ActionArgs, ActionCmd, WithJtac},
         group::DeployKind,
     },
-    jtac::{JtId, Jtac, Jtacs},
+    jtac::{BriefFormat, JtId, Jtac, Jtacs},
     spawnctx::SpawnCtx,
 };
 use anyhow::{Context as ErrContext, Result, anyhow, bail};
 use bfprotocols::{
-    cfg::{ActionKind, UnitTag, Vehicle},
+    cfg::{ActionKind, UnitTag, UnitTags, Vehicle},
     db::{group::GroupId as DbGid, objective::ObjectiveId},
     perf::Perf,
 };
-use compact_str::format_compact;
+use compact_str::{CompactString, format_compact};
 use dcso3::{
     MizLua, String,
     coalition::Side,
@@ -67,6 +67,25 @@ pub fn jtac_status(_: MizLua, arg: ArgTuple<Option<Ucid>, JtId>) -> Result<()> {
     Ok(())
 }
 
+pub fn jtac_nineline(lua: MizLua, arg: ArgTriple<Option<Ucid>, JtId, BriefFormat>) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let jtac = get_jtac_mut(&mut ctx.jtac, &arg.snd)?;
+    jtac.set_brief(arg.fst, arg.trd);
+    let msg = match jtac.nineline(&ctx.db, lua, arg.trd) {
+        Ok(msg) => msg,
+        Err(e) => format_compact!("jtac {} could not generate a brief {e:?}", arg.snd),
+    };
+    send_brief(&mut ctx.db, jtac.side(), &arg.fst, msg);
+    Ok(())
+}
+
+fn send_brief(db: &mut Db, side: Side, to: &Option<Ucid>, msg: CompactString) {
+    match to {
+        None => db.ephemeral.msgs().panel_to_side(30, false, side, msg),
+        Some(ucid) => db.ephemeral.panel_to_player(&db.persisted, 30, ucid, msg),
+    }
+}
+
 fn change_info(jtac: &Jtac, db: &Db, ucid: &Ucid) -> (String, String) {
     let near = db
         .objective(&jtac.location().oid)
@@ -176,8 +195,113 @@ fn jtac_msg_shift(db: &mut Db, jtid: JtId, jtac: &Jtac, ucid: &Ucid) {
 pub fn jtac_shift(lua: MizLua, arg: ArgTuple<Ucid, JtId>) -> Result<()> {
     let ctx = unsafe { Context::get_mut() };
     let jtac = get_jtac_mut(&mut ctx.jtac, &arg.snd)?;
-    jtac.shift(&ctx.db, lua).context("shifting jtac target")?;
+    let changed = jtac.shift(&ctx.db, lua).context("shifting jtac target")?;
     jtac_msg_shift(&mut ctx.db, arg.snd, jtac, &arg.fst);
+    if changed
+        && let Some((to, fmt)) = jtac.brief()
+        && let Ok(msg) = jtac.nineline(&ctx.db, lua, fmt)
+    {
+        send_brief(&mut ctx.db, jtac.side(), &to, msg)
+    }
+    Ok(())
+}
+
+pub fn jtac_claim_target(_: MizLua, arg: ArgTuple<Ucid, JtId>) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let jtac = get_jtac(&ctx.jtac, &arg.snd)?;
+    let side = jtac.side();
+    let (near, name) = change_info(jtac, &ctx.db, &arg.fst);
+    let target = jtac.target().as_ref().map(|t| (t.id, t.typ.clone()));
+    let msg = match ctx.jtac.toggle_claim(&arg.snd, &arg.fst) {
+        Ok(true) => format_compact!(
+            "TARGET {} CLAIMED\njtac {} near {}\nclaimed by {}",
+            target.map(|(_, typ)| typ).unwrap_or_default(),
+            arg.snd,
+            near,
+            name
+        ),
+        Ok(false) => format_compact!(
+            "TARGET {} RELEASED\njtac {} near {}\nreleased by {}",
+            target.map(|(_, typ)| typ).unwrap_or_default(),
+            arg.snd,
+            near,
+            name
+        ),
+        Err(e) => {
+            let holder = target
+                .and_then(|(id, _)| ctx.jtac.claimed_by(&id))
+                .and_then(|ucid| ctx.db.player(ucid))
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| "unknown".into());
+            let msg = format_compact!("COULD NOT CLAIM TARGET\njtac {}\n{e}, {holder}", arg.snd);
+            ctx.db
+                .ephemeral
+                .panel_to_player(&ctx.db.persisted, 10, &arg.fst, msg);
+            return Ok(());
+        }
+    };
+    ctx.db.ephemeral.msgs().panel_to_side(10, false, side, msg);
+    Ok(())
+}
+
+pub fn jtac_target_board(_: MizLua, arg: Ucid) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let side = ctx
+        .db
+        .player(&arg)
+        .map(|p| p.side)
+        .ok_or_else(|| anyhow!("missing player"))?;
+    let msg = ctx
+        .jtac
+        .board_msg(&ctx.db, side)
+        .context("generate target board")?;
+    ctx.db
+        .ephemeral
+        .panel_to_player(&ctx.db.persisted, 30, &arg, msg);
+    Ok(())
+}
+
+pub fn jtac_request_target(lua: MizLua, arg: ArgTriple<Ucid, ObjectiveId, u64>) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let tags = BitFlags::<UnitTag>::from_bits(arg.trd).map_err(|_| anyhow!("invalid tag bits"))?;
+    let player = ctx
+        .db
+        .player(&arg.fst)
+        .ok_or_else(|| anyhow!("missing player"))?;
+    let (side, name) = (player.side, player.name.clone());
+    let obj = ctx.db.objective(&arg.snd)?;
+    let (near, pos) = (obj.name.clone(), obj.zone.pos());
+    let jtid = match ctx
+        .jtac
+        .request_target(&ctx.db, lua, side, &arg.fst, pos, UnitTags(tags))
+    {
+        Ok(jtid) => jtid,
+        Err(e) => {
+            let msg = format_compact!("NO TARGET ASSIGNED\nnear {near}\n{e}");
+            ctx.db
+                .ephemeral
+                .panel_to_player(&ctx.db.persisted, 10, &arg.fst, msg);
+            return Ok(());
+        }
+    };
+    let jtac = get_jtac(&ctx.jtac, &jtid)?;
+    let target = jtac
+        .target()
+        .as_ref()
+        .map(|t| t.typ.clone())
+        .unwrap_or_default();
+    let msg = format_compact!(
+        "TARGET {target} ASSIGNED TO {name}\njtac {jtid} near {near}\nlaser code {}",
+        jtac.code()
+    );
+    ctx.db.ephemeral.msgs().panel_to_side(10, false, side, msg);
+    if let Some((_, fmt)) = jtac.brief()
+        && let Ok(msg) = jtac.nineline(&ctx.db, lua, fmt)
+    {
+        ctx.db
+            .ephemeral
+            .panel_to_player(&ctx.db.persisted, 30, &arg.fst, msg)
+    }
     Ok(())
 }
 
@@ -200,7 +324,8 @@ pub fn jtac_artillery_mission(lua: MizLua, arg: ArgQuad<JtId, DbGid, u8, Ucid>)
             ctx.db
                 .ephemeral
                 .msgs()
-                .panel_to_side(10, false, jtac.side(), msg)
+                .panel_to_side(10, false, jtac.side(), msg);
+            ctx.jtac.bda_requested(&arg.fst, &arg.fth);
         }
         Err(e) => {
             let msg = format!("jtac {} could not start artillery mission {:?}", arg.fst, e);
@@ -231,7 +356,8 @@ pub fn jtac_artillery_fire_all(lua: MizLua, arg: ArgTriple<JtId, DbGid, Ucid>) -
             ctx.db
                 .ephemeral
                 .msgs()
-                .panel_to_side(10, false, jtac.side(), msg)
+                .panel_to_side(10, false, jtac.side(), msg);
+            ctx.jtac.bda_requested(&arg.fst, &arg.trd);
         }
         Err(e) => {
             let msg = format!("jtac {} could not start artillery fire all mission {:?}", arg.fst, e);
@@ -283,6 +409,7 @@ pub fn jtac_artillery_combo_mission(lua: MizLua, arg: ArgQuad<JtId, DbGid, Vec<u
                 .ephemeral
                 .msgs()
                 .panel_to_side(10, false, jtac.side(), msg);
+            ctx.jtac.bda_requested(&arg.fst, &arg.fth);
         }
         Err(e) => {
             let jtac = get_jtac(&ctx.jtac, &arg.fst).context("getting jtac")?;
@@ -315,7 +442,8 @@ pub fn jtac_alcm_mission(lua: MizLua, arg: ArgQuad<JtId, DbGid, Vec<u8>, Ucid>)
             ctx.db
                 .ephemeral
                 .msgs()
-                .panel_to_side(10, false, jtac.side(), msg)
+                .panel_to_side(10, false, jtac.side(), msg);
+            ctx.jtac.bda_requested(&arg.fst, &arg.fth);
         }
         Err(e) => {
             let msg = format!("jtac {} could not start ALCM mission {:?}", arg.fst, e);
@@ -651,7 +779,7 @@ pub fn call_bomber(lua: MizLua, arg: ArgTriple<JtId, Ucid, String>) -> Result<()
             args: ActionArgs::Bomber(WithJtac { jtac: arg.fst, cfg }),
         },
     ) {
-        Ok(()) => {
+        Ok(_) => {
             let msg = format_compact!(
                 "BOMBER MISSION STARTED\ntargeting by jtac {} near {}\nstarted by {}",
                 arg.fst,
@@ -661,7 +789,8 @@ pub fn call_bomber(lua: MizLua, arg: ArgTriple<JtId, Ucid, String>) -> Result<()
             ctx.db
                 .ephemeral
                 .msgs()
-                .panel_to_side(10, false, jtac.side(), msg)
+                .panel_to_side(10, false, jtac.side(), msg);
+            ctx.jtac.bda_requested(&arg.fst, &arg.snd);
         }
         Err(e) => ctx.db.ephemeral.panel_to_player(
             &ctx.db.persisted,
@@ -673,6 +802,47 @@ pub fn call_bomber(lua: MizLua, arg: ArgTriple<JtId, Ucid, String>) -> Result<()
     Ok(())
 }
 
+pub fn call_shore_bombardment(lua: MizLua, arg: ArgTriple<JtId, Ucid, String>) -> Result<()> {
+    let ctx = unsafe { Context::get_mut() };
+    let perf = Arc::make_mut(&mut unsafe { Perf::get_mut() }.inner);
+    let spctx = SpawnCtx::new(lua)?;
+    let jtac = get_jtac(&ctx.jtac, &arg.fst)?;
+    let action = ctx
+        .db
+        .ephemeral
+        .cfg
+        .actions
+        .get(&jtac.side())
+        .and_then(|acts| acts.get(&arg.trd))
+        .ok_or_else(|| anyhow!("no such action {}", arg.trd))?;
+    let cfg = match &action.kind {
+        ActionKind::ShoreBombardment(cfg) => cfg.clone(),
+        _ => bail!("not a shore bombardment action"),
+    };
+    if let Err(e) = ctx.db.start_action(
+        lua,
+        perf,
+        &spctx,
+        &ctx.idx,
+        &ctx.jtac,
+        jtac.side(),
+        Some(arg.snd.clone()),
+        ActionCmd {
+            name: arg.trd,
+            action: action.clone(),
+            args: ActionArgs::ShoreBombardment(WithJtac { jtac: arg.fst, cfg }),
+        },
+    ) {
+        ctx.db.ephemeral.panel_to_player(
+            &ctx.db.persisted,
+            10,
+            &arg.snd,
+            format_compact!("shore bombardment could not start {e:?}"),
+        )
+    }
+    Ok(())
+}
+
 fn toggle_pin_jtac(lua: MizLua, arg: ArgTuple<SlotId, JtId>) -> Result<()> {
     let ctx = unsafe { Context::get_mut() };
     let subd = ctx.subscribed_jtac_menus.entry(arg.fst).or_default();
@@ -718,6 +888,8 @@ pub(super) fn add_menu_for_jtac(
                 },
                 DeployKind::Objective { .. }
                 | DeployKind::ObjectiveDeprecated
+                | DeployKind::Offensive { .. }
+                | DeployKind::DownedPilot { .. }
                 | DeployKind::Crate { .. } => format_compact!("{gid}"),
             },
         },
@@ -762,6 +934,24 @@ pub(super) fn add_menu_for_jtac(
             snd: jtac.gid(),
         },
     )?;
+    let brief_root = mc.add_submenu_for_group(mizgid, "Nine Line".into(), Some(root.clone()))?;
+    for (title, fmt) in [
+        ("MGRS", BriefFormat::Mgrs),
+        ("Lat Lon DMS", BriefFormat::Dms),
+        ("Lat Lon DDM", BriefFormat::Ddm),
+    ] {
+        mc.add_command_for_group(
+            mizgid,
+            title.into(),
+            Some(brief_root.clone()),
+            jtac_nineline,
+            ArgTriple {
+                fst: Some(*ucid),
+                snd: jtac.gid(),
+                trd: fmt,
+            },
+        )?;
+    }
     mc.add_command_for_group(
         mizgid,
         "Toggle Auto Shift".into(),
@@ -802,6 +992,16 @@ pub(super) fn add_menu_for_jtac(
             snd: jtac.gid(),
         },
     )?;
+    mc.add_command_for_group(
+        mizgid,
+        "Claim Target".into(),
+        Some(root.clone()),
+        jtac_claim_target,
+        ArgTuple {
+            fst: *ucid,
+            snd: jtac.gid(),
+        },
+    )?;
     let mut filter_root = mc.add_submenu_for_group(mizgid, "Filter".into(), Some(root.clone()))?;
     mc.add_command_for_group(
         mizgid,
@@ -894,6 +1094,32 @@ pub(super) fn add_menu_for_jtac(
             },
         )?;
     }
+
+    let naval_missions = db.ephemeral.cfg.actions.get(&side);
+    let naval_missions = naval_missions.iter().flat_map(|acts| {
+        acts.iter().filter_map(|(n, a)| match a.kind {
+            ActionKind::ShoreBombardment(_) => Some(n.clone()),
+            _ => None,
+        })
+    });
+    for name in naval_missions {
+        let root = mc.add_submenu_for_group(
+            mizgid,
+            format_compact!("Naval Gunfire({name})").into(),
+            Some(root.clone()),
+        )?;
+        mc.add_command_for_group(
+            mizgid,
+            "Yes, do it!".into(),
+            Some(root.clone()),
+            call_shore_bombardment,
+            ArgTriple {
+                fst: jtac.gid(),
+                snd: ucid.clone(),
+                trd: name,
+            },
+        )?;
+    }
     Ok(())
 }
 
@@ -919,7 +1145,31 @@ fn add_jtacs_by_location(
         cmd.push(format_compact!("{name}>>").into());
         mc.remove_command_for_group(arg.snd, cmd.into())?;
         let mut root = mc.add_submenu_for_group(arg.snd, name, Some(arg.fth))?;
-        let mut n = 0;
+        let request_root =
+            mc.add_submenu_for_group(arg.snd, "Request Target".into(), Some(root.clone()))?;
+        for tag in [
+            UnitTag::SAM,
+            UnitTag::AAA,
+            UnitTag::EWR,
+            UnitTag::Armor,
+            UnitTag::APC,
+            UnitTag::Artillery,
+            UnitTag::Logistics,
+            UnitTag::Infantry,
+        ] {
+            mc.add_command_for_group(
+                arg.snd,
+                format_compact!("Next {:?}", tag).into(),
+                Some(request_root.clone()),
+                jtac_request_target,
+                ArgTriple {
+                    fst: arg.fst,
+                    snd: arg.trd,
+                    trd: BitFlags::from(tag).bits(),
+                },
+            )?;
+        }
+        let mut n = 1;
         for jtac in ctx.jtac.jtacs() {
             if jtac.side() == player.side && jtac.location().oid == arg.trd {
                 if n >= 8 {
@@ -975,7 +1225,14 @@ fn add_jtac_locations(lua: MizLua, arg: ArgTriple<Ucid, GroupId, SlotId>) -> Res
         jtac_refresh_locations,
         arg.fst,
     )?;
-    let mut n = 0;
+    mc.add_command_for_group(
+        arg.snd,
+        "Target Board".into(),
+        Some(root.clone()),
+        jtac_target_board,
+        arg.fst,
+    )?;
+    let mut n = 1;
     macro_rules! handle_submenu {
         () => {
             if n >= 8 {
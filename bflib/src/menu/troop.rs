@@ -15,7 +15,7 @@ for more details.
 */
 
 use super::{cargo, player_name, slot_for_group, ArgTuple};
-use crate::{jtac::JtId, Context};
+use crate::{db::csar::PilotPickup, jtac::JtId, Context};
 use anyhow::{Context as ErrContext, Result};
 use bfprotocols::cfg::{Cfg, LimitEnforceTyp};
 use compact_str::format_compact;
@@ -74,6 +74,27 @@ fn load_troops(lua: MizLua, arg: ArgTuple<GroupId, String>) -> Result<()> {
 fn unload_troops(lua: MizLua, gid: GroupId) -> Result<()> {
     let ctx = unsafe { Context::get_mut() };
     let (side, slot) = slot_for_group(lua, ctx, &gid).context("getting slot for group")?;
+    match ctx.db.deliver_pilots(lua, &slot) {
+        Err(e) => {
+            let msg = format_compact!("{e}");
+            ctx.db.ephemeral.msgs().panel_to_group(10, false, gid, msg);
+            return Ok(());
+        }
+        Ok(delivered) if !delivered.is_empty() => {
+            let player = player_name(&ctx.db, &slot);
+            let msg = format_compact!("{player} rescued {}", delivered.join(", "));
+            ctx.db.ephemeral.msgs().panel_to_side(10, false, side, msg);
+            let has_troops = ctx
+                .db
+                .list_cargo(&slot)
+                .map(|c| !c.troops.is_empty())
+                .unwrap_or(false);
+            if !has_troops {
+                return Ok(());
+            }
+        }
+        Ok(_) => (),
+    }
     match ctx.db.unload_troops(lua, &ctx.idx, &slot) {
         Ok((tr, tgid, oid)) => {
             let player = player_name(&ctx.db, &slot);
@@ -98,6 +119,31 @@ fn unload_troops(lua: MizLua, gid: GroupId) -> Result<()> {
 fn extract_troops(lua: MizLua, gid: GroupId) -> Result<()> {
     let ctx = unsafe { Context::get_mut() };
     let (side, slot) = slot_for_group(lua, ctx, &gid).context("getting slot for group")?;
+    match ctx.db.pickup_pilot(lua, &slot) {
+        Err(e) => {
+            let msg = format_compact!("{e}");
+            ctx.db.ephemeral.msgs().panel_to_group(10, false, gid, msg);
+            return Ok(());
+        }
+        Ok(Some(PilotPickup::Rescued(name))) => {
+            let player = player_name(&ctx.db, &slot);
+            let msg = format_compact!("{player} picked up downed pilot {name}");
+            ctx.db.ephemeral.msgs().panel_to_side(10, false, side, msg);
+            return Ok(());
+        }
+        Ok(Some(PilotPickup::Captured(name))) => {
+            let player = player_name(&ctx.db, &slot);
+            let msg = format_compact!("{player} captured enemy pilot {name}");
+            ctx.db.ephemeral.msgs().panel_to_side(10, false, side, msg);
+            let msg = format_compact!("{name} has been captured by the enemy");
+            ctx.db
+                .ephemeral
+                .msgs()
+                .panel_to_side(10, false, side.opposite(), msg);
+            return Ok(());
+        }
+        Ok(None) => (),
+    }
     match ctx.db.extract_troops(lua, &slot) {
         Ok(tr) => {
             let player = player_name(&ctx.db, &slot);
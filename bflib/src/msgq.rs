@@ -19,7 +19,10 @@ use dcso3::{
     coalition::Side,
     env::miz::{GroupId, UnitId},
     net::{Net, PlayerId},
-    trigger::{Action, ArrowSpec, CircleSpec, MarkId, QuadSpec, RectSpec, SideFilter, TextSpec},
+    trigger::{
+        Action, ArrowSpec, CircleSpec, FreeformSpec, LineSpec, MarkId, QuadSpec, RectSpec,
+        SideFilter, TextSpec,
+    },
 };
 use log::error;
 use std::collections::VecDeque;
@@ -63,6 +66,12 @@ pub enum Msg {
         typ: MsgTyp,
         text: String,
     },
+    Line {
+        id: MarkId,
+        to: SideFilter,
+        spec: LineSpec,
+        message: Option<String>,
+    },
     Circle {
         id: MarkId,
         to: SideFilter,
@@ -81,6 +90,12 @@ pub enum Msg {
         spec: QuadSpec,
         message: Option<String>,
     },
+    Freeform {
+        id: MarkId,
+        to: SideFilter,
+        spec: FreeformSpec,
+        message: Option<String>,
+    },
     Text {
         id: MarkId,
         to: SideFilter,
@@ -311,6 +326,21 @@ impl MsgQ {
         )
     }
 
+    pub fn line_to_all(
+        &mut self,
+        to: SideFilter,
+        id: MarkId,
+        spec: LineSpec,
+        message: Option<String>,
+    ) {
+        self.0[2].push_back(Cmd::Send(Msg::Line {
+            id,
+            to,
+            spec,
+            message,
+        }))
+    }
+
     pub fn circle_to_all(
         &mut self,
         to: SideFilter,
@@ -357,6 +387,21 @@ impl MsgQ {
         }))
     }
 
+    pub fn freeform_to_all(
+        &mut self,
+        to: SideFilter,
+        id: MarkId,
+        spec: FreeformSpec,
+        message: Option<String>,
+    ) {
+        self.0[2].push_back(Cmd::Send(Msg::Freeform {
+            id,
+            to,
+            spec,
+            message,
+        }))
+    }
+
     pub fn text_to_all(&mut self, to: SideFilter, id: MarkId, spec: TextSpec) {
         self.0[1].push_back(Cmd::Send(Msg::Text { id, to, spec }))
     }
@@ -451,6 +496,12 @@ impl MsgQ {
                         }
                     },
                 },
+                Cmd::Send(Msg::Line {
+                    id,
+                    to,
+                    spec,
+                    message,
+                }) => act.line_to_all(to, id, spec, message),
                 Cmd::Send(Msg::Circle {
                     id,
                     to,
@@ -469,6 +520,12 @@ impl MsgQ {
                     spec,
                     message,
                 }) => act.quad_to_all(to, id, spec, message),
+                Cmd::Send(Msg::Freeform {
+                    id,
+                    to,
+                    spec,
+                    message,
+                }) => act.freeform_to_all(to, id, spec, message),
                 Cmd::Send(Msg::Text { id, to, spec }) => act.text_to_all(to, id, spec),
                 Cmd::Send(Msg::Arrow {
                     id,
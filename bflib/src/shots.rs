@@ -67,7 +67,9 @@ fn who(db: &Db, id: DcsOid<ClassUnit>) -> Option<Who> {
                 DeployKind::Troop { player, .. } => Some(*player),
                 DeployKind::Crate { .. }
                 | DeployKind::Objective { .. }
-                | DeployKind::ObjectiveDeprecated => None,
+                | DeployKind::ObjectiveDeprecated
+                | DeployKind::Offensive { .. }
+                | DeployKind::DownedPilot { .. } => None,
             }),
         }),
         None => db
@@ -149,6 +151,16 @@ impl ShotDb {
         Ok(())
     }
 
+    /// True if the unit has been hit but isn't dead
+    pub fn damaged(&self, target: &DcsOid<ClassUnit>) -> bool {
+        !self.dead.contains_key(target)
+            && self
+                .by_target
+                .get(target)
+                .map(|shots| shots.iter().any(|s| s.hit))
+                .unwrap_or(false)
+    }
+
     pub fn bring_out_your_dead(&mut self, now: DateTime<Utc>) -> Vec<Dead> {
         let mut dead = Vec::with_capacity(self.dead.len());
         for (target, time) in self.dead.drain() {
@@ -34,6 +34,7 @@ fn default_red_troops() -> Vec<Troop> {
             limit_enforce: LimitEnforceTyp::DeleteOldest,
             cost: 0,
             weight: 800,
+            can_clear_mines: true,
         },
         Troop {
             name: "Anti Tank".into(),
@@ -48,6 +49,7 @@ fn default_red_troops() -> Vec<Troop> {
             limit_enforce: LimitEnforceTyp::DeleteOldest,
             cost: 1,
             weight: 1000,
+            can_clear_mines: false,
         },
         Troop {
             name: "Mortar".into(),
@@ -62,6 +64,7 @@ fn default_red_troops() -> Vec<Troop> {
             limit_enforce: LimitEnforceTyp::DeleteOldest,
             cost: 5,
             weight: 1200,
+            can_clear_mines: false,
         },
         Troop {
             name: "Igla".into(),
@@ -73,6 +76,7 @@ fn default_red_troops() -> Vec<Troop> {
             limit_enforce: LimitEnforceTyp::DeleteOldest,
             cost: 5,
             weight: 500,
+            can_clear_mines: false,
         },
     ]
 }
@@ -92,6 +96,7 @@ fn default_blue_troops() -> Vec<Troop> {
             limit_enforce: LimitEnforceTyp::DeleteOldest,
             cost: 0,
             weight: 800,
+            can_clear_mines: true,
         },
         Troop {
             name: "Anti Tank".into(),
@@ -106,6 +111,7 @@ fn default_blue_troops() -> Vec<Troop> {
             limit_enforce: LimitEnforceTyp::DeleteOldest,
             cost: 1,
             weight: 1000,
+            can_clear_mines: false,
         },
         Troop {
             name: "Mortar".into(),
@@ -120,6 +126,7 @@ fn default_blue_troops() -> Vec<Troop> {
             limit_enforce: LimitEnforceTyp::DeleteOldest,
             cost: 5,
             weight: 1200,
+            can_clear_mines: false,
         },
         Troop {
             name: "Stinger".into(),
@@ -134,6 +141,7 @@ fn default_blue_troops() -> Vec<Troop> {
             limit_enforce: LimitEnforceTyp::DeleteOldest,
             cost: 5,
             weight: 500,
+            can_clear_mines: false,
         },
     ]
 }
@@ -176,7 +184,10 @@ fn default_red_deployables() -> Vec<Deployable> {
                 max_drop_speed: 13,
             }),
             repair_cost: 0,
-            ewr: Some(DeployableEwr { range: 30000 }),
+            ewr: Some(DeployableEwr {
+                range: 30000,
+                antenna_height: 10,
+            }),
             jtac: None,
             deprecated_logistics: None,
             deprecated_template: None,
@@ -225,7 +236,10 @@ fn default_red_deployables() -> Vec<Deployable> {
                 max_drop_speed: 13,
             }),
             repair_cost: 0,
-            ewr: Some(DeployableEwr { range: 60000 }),
+            ewr: Some(DeployableEwr {
+                range: 60000,
+                antenna_height: 10,
+            }),
             jtac: None,
             deprecated_logistics: None,
             deprecated_template: None,
@@ -249,7 +263,10 @@ fn default_red_deployables() -> Vec<Deployable> {
             }],
             repair_crate: None,
             repair_cost: 0,
-            ewr: Some(DeployableEwr { range: 20000 }),
+            ewr: Some(DeployableEwr {
+                range: 20000,
+                antenna_height: 10,
+            }),
             jtac: None,
             deprecated_logistics: None,
             deprecated_template: None,
@@ -495,7 +512,10 @@ fn default_red_deployables() -> Vec<Deployable> {
             }],
             repair_crate: None,
             repair_cost: 0,
-            ewr: Some(DeployableEwr { range: 500000 }),
+            ewr: Some(DeployableEwr {
+                range: 500000,
+                antenna_height: 10,
+            }),
             jtac: None,
             deprecated_logistics: None,
             deprecated_template: None,
@@ -533,6 +553,35 @@ fn default_red_deployables() -> Vec<Deployable> {
             deprecated_logistics: None,
             deprecated_template: None,
         },
+        Deployable {
+            path: vec!["Minefield".into()],
+            kind: DeployableKind::Minefield(MinefieldCfg {
+                length: 400,
+                depth: 100,
+                mines: 20,
+                hit_chance: 25,
+                power: 100,
+                clear_rate: 2,
+            }),
+            persist: PersistTyp::Forever,
+            limit: 4,
+            cost: 25,
+            limit_enforce: LimitEnforceTyp::DeleteOldest,
+            crates: vec![Crate {
+                name: "Mine Crate".into(),
+                weight: 1000,
+                required: 2,
+                pos_unit: None,
+                max_drop_height_agl: 10,
+                max_drop_speed: 13,
+            }],
+            repair_crate: None,
+            repair_cost: 0,
+            ewr: None,
+            jtac: None,
+            deprecated_logistics: None,
+            deprecated_template: None,
+        },
     ]
 }
 
@@ -557,7 +606,10 @@ fn default_blue_deployables() -> Vec<Deployable> {
             }],
             repair_crate: None,
             repair_cost: 0,
-            ewr: Some(DeployableEwr { range: 20000 }),
+            ewr: Some(DeployableEwr {
+                range: 20000,
+                antenna_height: 10,
+            }),
             jtac: None,
             deprecated_logistics: None,
             deprecated_template: None,
@@ -614,7 +666,10 @@ fn default_blue_deployables() -> Vec<Deployable> {
                 max_drop_speed: 13,
             }),
             repair_cost: 0,
-            ewr: Some(DeployableEwr { range: 60000 }),
+            ewr: Some(DeployableEwr {
+                range: 60000,
+                antenna_height: 10,
+            }),
             jtac: None,
             deprecated_logistics: None,
             deprecated_template: None,
@@ -836,7 +891,10 @@ fn default_blue_deployables() -> Vec<Deployable> {
             }],
             repair_crate: None,
             repair_cost: 0,
-            ewr: Some(DeployableEwr { range: 500000 }),
+            ewr: Some(DeployableEwr {
+                range: 500000,
+                antenna_height: 10,
+            }),
             jtac: None,
             deprecated_logistics: None,
             deprecated_template: None,
@@ -874,6 +932,35 @@ fn default_blue_deployables() -> Vec<Deployable> {
             deprecated_logistics: None,
             deprecated_template: None,
         },
+        Deployable {
+            path: vec!["Minefield".into()],
+            kind: DeployableKind::Minefield(MinefieldCfg {
+                length: 400,
+                depth: 100,
+                mines: 20,
+                hit_chance: 25,
+                power: 100,
+                clear_rate: 2,
+            }),
+            persist: PersistTyp::Forever,
+            limit: 4,
+            cost: 25,
+            limit_enforce: LimitEnforceTyp::DeleteOldest,
+            crates: vec![Crate {
+                name: "Mine Crate".into(),
+                weight: 1000,
+                required: 2,
+                pos_unit: None,
+                max_drop_height_agl: 10,
+                max_drop_speed: 13,
+            }],
+            repair_crate: None,
+            repair_cost: 0,
+            ewr: None,
+            jtac: None,
+            deprecated_logistics: None,
+            deprecated_template: None,
+        },
     ]
 }
 
@@ -1225,6 +1312,60 @@ fn default_jtac_priority() -> Vec<UnitTags> {
     .collect()
 }
 
+fn default_ewr_radar() -> EwrRadarCfg {
+    use RadarSizeClass::*;
+    EwrRadarCfg {
+        size_class: FxHashMap::from_iter([
+            ("B-1B".into(), Bomber),
+            ("Tu-22M3".into(), Bomber),
+            ("Tu-160".into(), Bomber),
+            ("UH-1H".into(), Helicopter),
+            ("Mi-8MT".into(), Helicopter),
+            ("Mi-24P".into(), Helicopter),
+            ("Ka-50".into(), Helicopter),
+            ("Ka-50_3".into(), Helicopter),
+            ("AH-64D_BLK_II".into(), Helicopter),
+            ("SA342M".into(), Helicopter),
+            ("SA342L".into(), Helicopter),
+        ]),
+        class_range: FxHashMap::from_iter([
+            (Fighter, 100),
+            (Bomber, 120),
+            (Helicopter, 60),
+            (CruiseMissile, 40),
+        ]),
+        edge_pd: 30,
+        notch_speed: 30,
+        notch_pd: 5,
+    }
+}
+
+fn default_jtac_scoring() -> JtacScoringCfg {
+    use UnitTag::*;
+    JtacScoringCfg {
+        tag_weights: FxHashMap::from_iter([
+            (SAM, 50.),
+            (LR, 30.),
+            (MR, 20.),
+            (RadarGuided, 10.),
+            (AAA, 25.),
+            (ATGM, 20.),
+            (Artillery, 15.),
+            (Logistics, 10.),
+            (Armor, 10.),
+            (APC, 5.),
+            (Infantry, 2.),
+        ]),
+        threat: 40.,
+        threat_distance: 10000,
+        track_radar: 40.,
+        launcher: 15.,
+        distance_per_km: 2.,
+        stationary_per_minute: 1.,
+        max_stationary_minutes: 10,
+    }
+}
+
 fn default_repair_crate() -> FxHashMap<Side, Crate> {
     FxHashMap::from_iter([
         (
@@ -1288,8 +1429,12 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Awacs(AwacsCfg {
-                    ewr: DeployableEwr { range: 400000 },
+                    ewr: DeployableEwr {
+                        range: 400000,
+                        antenna_height: 10,
+                    },
                     plane: AiPlaneCfg {
                         kind: AiPlaneKind::FixedWing,
                         duration: Some(8),
@@ -1299,6 +1444,8 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                         speed: 200.,
                         freq: Some(125000000),
                     },
+                    callsign: Some("Overlord".into()),
+                    picture_interval: 300,
                 }),
             },
         ),
@@ -1309,6 +1456,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::AwacsWaypoint,
             },
         ),
@@ -1319,6 +1467,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(50),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Tanker(AiPlaneCfg {
                     kind: AiPlaneKind::FixedWing,
                     duration: Some(8),
@@ -1337,6 +1486,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::TankerWaypoint,
             },
         ),
@@ -1347,6 +1497,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(25),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Drone(DroneCfg {
                     plane: AiPlaneCfg {
                         kind: AiPlaneKind::FixedWing,
@@ -1371,9 +1522,71 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::DroneWaypoint,
             },
         ),
+        (
+            "recon".into(),
+            Action {
+                cost: 20,
+                penalty: Some(20),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::Recon(ReconCfg {
+                    range: 12000,
+                    mark_duration: 30,
+                    plane: AiPlaneCfg {
+                        kind: AiPlaneKind::FixedWing,
+                        duration: None,
+                        template: "RRECON".into(),
+                        altitude: 6000.,
+                        altitude_typ: AltType::BARO,
+                        speed: 200.,
+                        freq: None,
+                    },
+                }),
+            },
+        ),
+        (
+            "jammer".into(),
+            Action {
+                cost: 50,
+                penalty: Some(50),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::Jammer(JammerCfg {
+                    range: 100000,
+                    beam_width: 30,
+                    ewr_range: 50,
+                    track_delay: 60,
+                    track_error: 5000,
+                    sam_range: 50,
+                    plane: AiPlaneCfg {
+                        kind: AiPlaneKind::FixedWing,
+                        duration: Some(4),
+                        template: "RJAMMER".into(),
+                        altitude: 9000.,
+                        altitude_typ: AltType::BARO,
+                        speed: 200.,
+                        freq: None,
+                    },
+                }),
+            },
+        ),
+        (
+            "jammer-waypoint".into(),
+            Action {
+                cost: 10,
+                penalty: None,
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::JammerWaypoint,
+            },
+        ),
         (
             "bomber".into(),
             Action {
@@ -1381,6 +1594,15 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle {
+                    player_cooldown: Some(60),
+                    side_cooldown: Some(15),
+                    window_limit: Some(ActionWindowLimit {
+                        limit: 3,
+                        window: 120,
+                    }),
+                    budget: 40,
+                },
                 kind: ActionKind::Bomber(BomberCfg {
                     targets: 15,
                     power: 1000,
@@ -1404,6 +1626,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Fighters(AiPlaneCfg {
                     kind: AiPlaneKind::FixedWing,
                     duration: Some(2),
@@ -1422,6 +1645,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::FighersWaypoint,
             },
         ),
@@ -1432,6 +1656,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Attackers(AiPlaneCfg {
                     kind: AiPlaneKind::Helicopter,
                     duration: Some(2),
@@ -1450,6 +1675,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::AttackersWaypoint,
             },
         ),
@@ -1460,6 +1686,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Paratrooper(DeployableCfg {
                     name: "Standard".into(),
                     plane: Some(AiPlaneCfg {
@@ -1481,6 +1708,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Deployable(DeployableCfg {
                     name: "1L13".into(),
                     plane: Some(AiPlaneCfg {
@@ -1502,6 +1730,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::LogisticsRepair(AiPlaneCfg {
                     kind: AiPlaneKind::Helicopter,
                     template: "RCARGOCARRIER".into(),
@@ -1520,6 +1749,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::LogisticsTransfer(AiPlaneCfg {
                     kind: AiPlaneKind::Helicopter,
                     template: "RCARGOCARRIER".into(),
@@ -1531,6 +1761,29 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 }),
             },
         ),
+        (
+            "airdrop".into(),
+            Action {
+                cost: 50,
+                penalty: Some(50),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::Airdrop(AirdropCfg {
+                    plane: AiPlaneCfg {
+                        kind: AiPlaneKind::FixedWing,
+                        template: "RAIRDROP".into(),
+                        altitude: 3000.,
+                        altitude_typ: AltType::BARO,
+                        duration: None,
+                        speed: 150.,
+                        freq: None,
+                    },
+                    max_packages: 4,
+                    supply: 10,
+                }),
+            },
+        ),
         (
             "nuke".into(),
             Action {
@@ -1538,6 +1791,7 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Nuke(NukeCfg {
                     cost_scale: 5,
                     power: 1000,
@@ -1551,9 +1805,60 @@ fn default_red_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(25),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Move(MoveCfg {
                     troop: 1000,
                     deployable: 1000,
+                    naval: Some(5000),
+                }),
+            },
+        ),
+        (
+            "naval-patrol".into(),
+            Action {
+                cost: 150,
+                penalty: Some(150),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::NavalPatrol(NavalGroupCfg {
+                    template: "RNAVAL".into(),
+                    duration: Some(6),
+                    speed: 12.,
+                }),
+            },
+        ),
+        (
+            "shore-bombardment".into(),
+            Action {
+                cost: 50,
+                penalty: None,
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::ShoreBombardment(ShoreBombardmentCfg {
+                    range: 25000,
+                    radius: 100,
+                    rounds: 20,
+                }),
+            },
+        ),
+        (
+            "anti-ship".into(),
+            Action {
+                cost: 100,
+                penalty: Some(100),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::AntiShip(AiPlaneCfg {
+                    kind: AiPlaneKind::FixedWing,
+                    duration: None,
+                    template: "RANTISHIP".into(),
+                    altitude: 3000.,
+                    altitude_typ: AltType::BARO,
+                    speed: 220.,
+                    freq: None,
                 }),
             },
         ),
@@ -1569,6 +1874,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Awacs(AwacsCfg {
                     plane: AiPlaneCfg {
                         kind: AiPlaneKind::FixedWing,
@@ -1579,7 +1885,12 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                         speed: 200.,
                         freq: Some(264000000),
                     },
-                    ewr: DeployableEwr { range: 400000 },
+                    ewr: DeployableEwr {
+                        range: 400000,
+                        antenna_height: 10,
+                    },
+                    callsign: Some("Magic".into()),
+                    picture_interval: 300,
                 }),
             },
         ),
@@ -1590,6 +1901,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::AwacsWaypoint,
             },
         ),
@@ -1600,6 +1912,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(50),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Tanker(AiPlaneCfg {
                     kind: AiPlaneKind::FixedWing,
                     duration: Some(8),
@@ -1618,6 +1931,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(50),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Tanker(AiPlaneCfg {
                     kind: AiPlaneKind::FixedWing,
                     duration: Some(8),
@@ -1636,6 +1950,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::TankerWaypoint,
             },
         ),
@@ -1646,6 +1961,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(25),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Drone(DroneCfg {
                     plane: AiPlaneCfg {
                         kind: AiPlaneKind::FixedWing,
@@ -1670,9 +1986,71 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::DroneWaypoint,
             },
         ),
+        (
+            "recon".into(),
+            Action {
+                cost: 20,
+                penalty: Some(20),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::Recon(ReconCfg {
+                    range: 12000,
+                    mark_duration: 30,
+                    plane: AiPlaneCfg {
+                        kind: AiPlaneKind::FixedWing,
+                        duration: None,
+                        template: "BRECON".into(),
+                        altitude: 6000.,
+                        altitude_typ: AltType::BARO,
+                        speed: 200.,
+                        freq: None,
+                    },
+                }),
+            },
+        ),
+        (
+            "jammer".into(),
+            Action {
+                cost: 50,
+                penalty: Some(50),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::Jammer(JammerCfg {
+                    range: 100000,
+                    beam_width: 30,
+                    ewr_range: 50,
+                    track_delay: 60,
+                    track_error: 5000,
+                    sam_range: 50,
+                    plane: AiPlaneCfg {
+                        kind: AiPlaneKind::FixedWing,
+                        duration: Some(4),
+                        template: "BJAMMER".into(),
+                        altitude: 9000.,
+                        altitude_typ: AltType::BARO,
+                        speed: 200.,
+                        freq: None,
+                    },
+                }),
+            },
+        ),
+        (
+            "jammer-waypoint".into(),
+            Action {
+                cost: 10,
+                penalty: None,
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::JammerWaypoint,
+            },
+        ),
         (
             "bomber".into(),
             Action {
@@ -1680,6 +2058,15 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle {
+                    player_cooldown: Some(60),
+                    side_cooldown: Some(15),
+                    window_limit: Some(ActionWindowLimit {
+                        limit: 3,
+                        window: 120,
+                    }),
+                    budget: 40,
+                },
                 kind: ActionKind::Bomber(BomberCfg {
                     targets: 15,
                     power: 1000,
@@ -1703,6 +2090,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Fighters(AiPlaneCfg {
                     kind: AiPlaneKind::FixedWing,
                     duration: Some(2),
@@ -1721,6 +2109,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::FighersWaypoint,
             },
         ),
@@ -1731,6 +2120,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Attackers(AiPlaneCfg {
                     kind: AiPlaneKind::Helicopter,
                     duration: Some(2),
@@ -1749,6 +2139,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::AttackersWaypoint,
             },
         ),
@@ -1759,6 +2150,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Paratrooper(DeployableCfg {
                     name: "Standard".into(),
                     plane: Some(AiPlaneCfg {
@@ -1780,6 +2172,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Deployable(DeployableCfg {
                     name: "AN/FPS-117".into(),
                     plane: Some(AiPlaneCfg {
@@ -1801,6 +2194,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::LogisticsRepair(AiPlaneCfg {
                     kind: AiPlaneKind::Helicopter,
                     template: "BCARGOCARRIER".into(),
@@ -1819,6 +2213,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(100),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::LogisticsTransfer(AiPlaneCfg {
                     kind: AiPlaneKind::Helicopter,
                     template: "BCARGOCARRIER".into(),
@@ -1830,6 +2225,29 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 }),
             },
         ),
+        (
+            "airdrop".into(),
+            Action {
+                cost: 50,
+                penalty: Some(50),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::Airdrop(AirdropCfg {
+                    plane: AiPlaneCfg {
+                        kind: AiPlaneKind::FixedWing,
+                        template: "BAIRDROP".into(),
+                        altitude: 3000.,
+                        altitude_typ: AltType::BARO,
+                        duration: None,
+                        speed: 150.,
+                        freq: None,
+                    },
+                    max_packages: 4,
+                    supply: 10,
+                }),
+            },
+        ),
         (
             "nuke".into(),
             Action {
@@ -1837,6 +2255,7 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: None,
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Nuke(NukeCfg {
                     cost_scale: 5,
                     power: 1000,
@@ -1850,9 +2269,60 @@ fn default_blue_actions() -> IndexMap<String, Action, FxBuildHasher> {
                 penalty: Some(25),
                 limit: None,
                 geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
                 kind: ActionKind::Move(MoveCfg {
                     troop: 1000,
                     deployable: 1000,
+                    naval: Some(5000),
+                }),
+            },
+        ),
+        (
+            "naval-patrol".into(),
+            Action {
+                cost: 150,
+                penalty: Some(150),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::NavalPatrol(NavalGroupCfg {
+                    template: "BNAVAL".into(),
+                    duration: Some(6),
+                    speed: 12.,
+                }),
+            },
+        ),
+        (
+            "shore-bombardment".into(),
+            Action {
+                cost: 50,
+                penalty: None,
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::ShoreBombardment(ShoreBombardmentCfg {
+                    range: 25000,
+                    radius: 100,
+                    rounds: 20,
+                }),
+            },
+        ),
+        (
+            "anti-ship".into(),
+            Action {
+                cost: 100,
+                penalty: Some(100),
+                limit: None,
+                geo_limit: ActionGeoLimit::Unlimited,
+                throttle: ActionThrottle::default(),
+                kind: ActionKind::AntiShip(AiPlaneCfg {
+                    kind: AiPlaneKind::FixedWing,
+                    duration: None,
+                    template: "BANTISHIP".into(),
+                    altitude: 3000.,
+                    altitude_typ: AltType::BARO,
+                    speed: 220.,
+                    freq: None,
                 }),
             },
         ),
@@ -1898,6 +2368,7 @@ impl Default for Cfg {
                 weapon_cost: FxHashMap::default(),
                 strict: false,
                 periodic_point_gain: (0, 0),
+                supply_request_fill: 10,
             }),
             warehouse: Some(WarehouseConfig {
                 hub_max: 25,
@@ -1911,6 +2382,10 @@ impl Default for Cfg {
                     (Side::Red, "RINVENTORY".into()),
                 ]),
                 exempt_airframes: FxHashSet::from_iter(["Su-30SM".into()]),
+                supply_request_lifetime: 60,
+                min_fuel: FxHashMap::from_iter([(LifeType::Intercept, 25)]),
+                min_supply: FxHashMap::default(),
+                weapon_min_supply: FxHashMap::default(),
             }),
             weapon_target_exclusions: FxHashSet::default(),
             logistics_exclusion: 10000,
@@ -1920,6 +2395,7 @@ impl Default for Cfg {
             slow_timed_events_freq: 10,
             threatened_distance: default_threatened_distance(),
             threatened_cooldown: 300,
+            carrier_min_health: 50,
             crate_load_distance: 50,
             crate_spread: 250,
             artillery_mission_range: 15000,
@@ -1971,9 +2447,147 @@ impl Default for Cfg {
                 ),
             ]),
             jtac_priority: default_jtac_priority(),
+            jtac_scoring: Some(default_jtac_scoring()),
             extra_fixed_wing_objectives: FxHashSet::default(),
             ewr_mode: EwrMode::Original,
             ewr_delay: 60,
+            ewr_radar: Some(default_ewr_radar()),
+            ewr_threat: Some(EwrThreatCfg {
+                threat_range: 37040, // 20 nm
+                merge_range: 5556,   // 3 nm
+                cadence: 30,
+            }),
+            threat_map: Some(ThreatMapCfg {
+                ewr_coverage: true,
+                sam_rings: true,
+                stale_after: 15,
+                expire_after: 60,
+            }),
+            link16: Some(Link16Cfg {
+                airframes: FxHashSet::from_iter([
+                    "FA-18C_hornet".into(),
+                    "F-16C_50".into(),
+                    "F-15ESE".into(),
+                    "F-14B".into(),
+                ]),
+                max_age: 30,
+                max_tracks: 16,
+            }),
+            front_line: Some(FrontLineCfg {
+                adjacency_distance: 74080, // 40 nm
+                links: vec![],
+                capture_front_only: false,
+                keep_front_spawned: false,
+                supply_weight: 2,
+                draw: true,
+            }),
+            timed_capture: Some(TimedCaptureCfg {
+                capture_time: 300,
+                troop_rate: FxHashMap::from_iter([("Standard".into(), 1.5)]),
+                max_rate: 4.,
+                decay_rate: 0.5,
+                ignore_dead: true,
+            }),
+            objective_upgrades: Some(ObjectiveUpgradeCfg {
+                tiers: vec![
+                    ObjectiveUpgrade {
+                        cost: 200,
+                        defenses: vec!["SR-UPGRADE".into()],
+                        repair_speedup: 25,
+                        warehouse_capacity: 0,
+                        slots: FxHashSet::default(),
+                    },
+                    ObjectiveUpgrade {
+                        cost: 500,
+                        defenses: vec!["MR-UPGRADE".into()],
+                        repair_speedup: 0,
+                        warehouse_capacity: 50,
+                        slots: FxHashSet::default(),
+                    },
+                ],
+                tiers_lost_on_capture: 1,
+            }),
+            ai_commander: FxHashMap::from_iter([
+                (
+                    Side::Red,
+                    AiCommanderCfg {
+                        points_per_hour: 100,
+                        max_budget: 500,
+                        decision_interval: 300,
+                        min_players: 2,
+                        max_players: 12,
+                        threat_range: 55560, // 30 nm
+                        repair_threshold: 50,
+                        supply_threshold: 25,
+                        cap: Some("fighters".into()),
+                        sead: None,
+                        attack: Some("attack-helicopters".into()),
+                        drone: Some("drone".into()),
+                        logistics_repair: Some("repair".into()),
+                        logistics_transfer: Some("transfer".into()),
+                    },
+                ),
+                (
+                    Side::Blue,
+                    AiCommanderCfg {
+                        points_per_hour: 100,
+                        max_budget: 500,
+                        decision_interval: 300,
+                        min_players: 2,
+                        max_players: 12,
+                        threat_range: 55560, // 30 nm
+                        repair_threshold: 50,
+                        supply_threshold: 25,
+                        cap: Some("fighters".into()),
+                        sead: None,
+                        attack: Some("attack-helicopters".into()),
+                        drone: Some("drone".into()),
+                        logistics_repair: Some("repair".into()),
+                        logistics_transfer: Some("transfer".into()),
+                    },
+                ),
+            ]),
+            ground_offensive: Some(GroundOffensiveCfg {
+                template: FxHashMap::from_iter([
+                    (Side::Red, "RARMOR-OFFENSIVE".into()),
+                    (Side::Blue, "BARMOR-OFFENSIVE".into()),
+                ]),
+                min_supply: 60,
+                min_fuel: 40,
+                supply_cost: 10,
+                interval: 60,
+                max_distance: 55560, // 30 nm
+                max_active: 2,
+                capture_rate: 1.,
+            }),
+            csar: Some(CsarCfg {
+                template: FxHashMap::from_iter([
+                    (Side::Red, "RPILOT".into()),
+                    (Side::Blue, "BPILOT".into()),
+                ]),
+                weight: 100,
+                rescue_points: 50,
+                capture_points: 25,
+                expire: 60,
+                beacon: Some(CsarBeaconCfg {
+                    file: "l10n/DEFAULT/beacon.ogg".into(),
+                    frequency: 121500000,
+                    power: 100,
+                }),
+            }),
+            action_queue: Some(ActionQueueCfg {
+                max_queued: 4,
+                max_wait: 240,
+            }),
+            player_recon: Some(PlayerReconCfg {
+                range: 10000,
+                mark_duration: 30,
+                points: 10,
+            }),
+            command_budget: Some(CommandBudgetCfg {
+                max: 100,
+                regen: 50,
+            }),
         }
     }
 }
@@ -329,7 +329,391 @@ pub struct DeployableObjective {
 pub struct DeployableEwr {
     /// range for likely detection (Meters)
     pub range: u32,
-    // CR estokes: Actual radar simulation ...
+    /// height of the antenna above the unit (Meters), it sets the
+    /// radar horizon when ewr_radar is configured
+    #[serde(default = "default_antenna_height")]
+    pub antenna_height: u32,
+}
+
+fn default_antenna_height() -> u32 {
+    10
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub enum RadarSizeClass {
+    #[default]
+    Fighter,
+    Bomber,
+    Helicopter,
+    CruiseMissile,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EwrRadarCfg {
+    /// The size class of each airframe. Airframes that aren't listed
+    /// are fighters, except cruise missile actions, which are always
+    /// cruise missiles.
+    pub size_class: FxHashMap<Vehicle, RadarSizeClass>,
+    /// How far away each size class can be detected as a percentage
+    /// of the ewr's range. Classes that aren't listed use 100.
+    pub class_range: FxHashMap<RadarSizeClass, u8>,
+    /// The chance (percent) of detecting a target at the edge of its
+    /// detection range on each update. It rises toward 100 as the
+    /// target gets closer.
+    pub edge_pd: u8,
+    /// Targets with a radial velocity below this are in the doppler
+    /// notch (Meters / Second)
+    pub notch_speed: u32,
+    /// The chance (percent) of detecting a target in the notch
+    pub notch_pd: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EwrThreatCfg {
+    /// Enemy tracks closing on a player with hot aspect inside this
+    /// range trigger a threat call (Meters)
+    pub threat_range: u32,
+    /// Enemy tracks inside this range trigger a merge call (Meters)
+    pub merge_range: u32,
+    /// The minimum time between threat calls to the same player
+    /// (Seconds)
+    pub cadence: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Link16Cfg {
+    /// Player airframes that receive the side's ewr and awacs tracks
+    /// over the datalink
+    pub airframes: FxHashSet<Vehicle>,
+    /// Tracks older than this are not shared (Seconds)
+    pub max_age: u32,
+    /// The maximum number of tracks shared with each flight, closest
+    /// first
+    pub max_tracks: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThreatMapCfg {
+    /// Draw each side's ewr and awacs coverage on that side's F10 map
+    pub ewr_coverage: bool,
+    /// Draw the engagement rings of enemy sam groups a side has
+    /// spotted with jtacs or recon
+    pub sam_rings: bool,
+    /// How long after a sam group was last spotted its ring begins to
+    /// fade (Minutes)
+    pub stale_after: u32,
+    /// How long after a sam group was last spotted its ring is
+    /// removed (Minutes)
+    pub expire_after: u32,
+}
+
+fn default_front_line_supply_weight() -> u32 {
+    2
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FrontLineCfg {
+    /// Objectives whose centers are within this many meters of each
+    /// other are adjacent. Every objective is also adjacent to its
+    /// nearest neighbor so that no objective is isolated. Farps are
+    /// not part of the graph.
+    pub adjacency_distance: u32,
+    /// Additional adjacency links by objective name, for connections
+    /// that distance alone doesn't capture (e.g. across a mountain
+    /// range or along a valley)
+    #[serde(default)]
+    pub links: Vec<(String, String)>,
+    /// If true then an objective can only be captured by a side
+    /// that owns an adjacent objective
+    #[serde(default)]
+    pub capture_front_only: bool,
+    /// If true then front line objectives are never culled
+    #[serde(default)]
+    pub keep_front_spawned: bool,
+    /// Front line objectives receive this many times the normal hub
+    /// supply allocation per round
+    #[serde(default = "default_front_line_supply_weight")]
+    pub supply_weight: u32,
+    /// Draw each side's front line on the F10 map as a polygon
+    /// around the front line objectives it owns
+    #[serde(default)]
+    pub draw: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectiveUpgrade {
+    /// The cost of the tier in objective points
+    pub cost: u32,
+    /// Additional defense groups spawned at the objective when the
+    /// tier is purchased. The side prefix is added automatically,
+    /// e.g. MR-UPGRADE spawns BMR-UPGRADE for blue. Name them like
+    /// objective groups (LR, MR, SR, AAA, ARMOR) so they are repaired
+    /// like the objective's own defenses.
+    #[serde(default)]
+    pub defenses: Vec<String>,
+    /// Percent reduction in the objective's repair time
+    #[serde(default)]
+    pub repair_speedup: u8,
+    /// Percent increase in the objective's warehouse capacity
+    #[serde(default)]
+    pub warehouse_capacity: u32,
+    /// Slots, by unit name, that can't be occupied until this tier
+    /// has been purchased
+    #[serde(default)]
+    pub slots: FxHashSet<String>,
+}
+
+fn default_tiers_lost_on_capture() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectiveUpgradeCfg {
+    /// The upgrade tiers in the order they must be purchased
+    pub tiers: Vec<ObjectiveUpgrade>,
+    /// How many tiers an objective loses when it is captured. The
+    /// defenses of remaining tiers are respawned for the new owner.
+    #[serde(default = "default_tiers_lost_on_capture")]
+    pub tiers_lost_on_capture: u8,
+}
+
+fn default_offensive_capture_rate() -> f32 {
+    1.
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroundOffensiveCfg {
+    /// The armor/APC group template each side's offensives are built
+    /// from
+    pub template: FxHashMap<Side, String>,
+    /// An offensive may only be launched from an objective whose
+    /// supply is at least this percentage
+    pub min_supply: u8,
+    /// An offensive may only be launched from an objective whose fuel
+    /// is at least this percentage
+    pub min_fuel: u8,
+    /// The percentage of the origin objective's warehouse inventory
+    /// consumed by launching an offensive
+    pub supply_cost: u8,
+    /// The minimum time in minutes between offensives launched from
+    /// an objective with full supply. It is scaled up as the
+    /// objective's supply falls.
+    pub interval: u32,
+    /// Offensives only target adjacent enemy objectives within this
+    /// many meters of the origin
+    pub max_distance: u32,
+    /// The maximum number of offensives a side can have underway at
+    /// once
+    pub max_active: u32,
+    /// The per unit capture rate of offensive groups when timed
+    /// capture is enabled
+    #[serde(default = "default_offensive_capture_rate")]
+    pub capture_rate: f32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommandBudgetCfg {
+    /// The most budget a side can accumulate. Sides start with the max.
+    pub max: u32,
+    /// How much budget each side regains per hour
+    pub regen: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JtacScoringCfg {
+    /// Score added to a contact for each of these tags it has
+    pub tag_weights: FxHashMap<UnitTag, f32>,
+    /// Score added to a contact that is threatening a friendly
+    /// objective or ground unit
+    pub threat: f32,
+    /// How close a contact must be to a friendly objective or ground
+    /// unit to threaten it (Meters)
+    pub threat_distance: u32,
+    /// Score added to the track radar of a SAM site
+    pub track_radar: f32,
+    /// Score added to a launcher of a SAM site
+    pub launcher: f32,
+    /// Score removed for each km between the contact and the jtac
+    pub distance_per_km: f32,
+    /// Score added for each minute the contact has been stationary
+    pub stationary_per_minute: f32,
+    /// Stationary time beyond this many minutes adds no more score
+    pub max_stationary_minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActionQueueCfg {
+    /// The maximum number of actions a player may have queued at once
+    pub max_queued: u32,
+    /// Actions waiting on a jtac that have not run after this many
+    /// minutes are canceled and refunded. Actions scheduled at a time,
+    /// or after another action, wait as long as they need to
+    pub max_wait: u32,
+}
+
+fn default_csar_expire() -> u32 {
+    60
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CsarBeaconCfg {
+    /// The sound file, which must be included in the miz, that the
+    /// downed pilot's beacon transmits in a loop
+    pub file: String,
+    /// The beacon frequency in Hz
+    pub frequency: u64,
+    /// The beacon power in watts
+    pub power: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerReconCfg {
+    /// How far a player in a recon life type aircraft can see enemy
+    /// ground units in meters
+    pub range: u32,
+    /// How long delivered intel stays marked on the F10 map in minutes
+    pub mark_duration: u32,
+    /// Points awarded for each enemy group delivered
+    #[serde(default)]
+    pub points: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CsarCfg {
+    /// The group template used to spawn each side's downed pilots
+    pub template: FxHashMap<Side, String>,
+    /// How much weight a rescued pilot adds to the carrier unit
+    pub weight: u32,
+    /// How many points are awarded for delivering a downed pilot to a
+    /// friendly objective
+    #[serde(default)]
+    pub rescue_points: u32,
+    /// How many points are awarded for capturing an enemy downed
+    /// pilot
+    #[serde(default)]
+    pub capture_points: u32,
+    /// How long in minutes a downed pilot waits for rescue before
+    /// they are removed
+    #[serde(default = "default_csar_expire")]
+    pub expire: u32,
+    /// The radio beacon transmitted by downed pilots
+    #[serde(default)]
+    pub beacon: Option<CsarBeaconCfg>,
+}
+
+fn default_ai_decision_interval() -> u32 {
+    300
+}
+
+fn default_ai_threat_range() -> u32 {
+    55560
+}
+
+fn default_ai_repair_threshold() -> u8 {
+    50
+}
+
+fn default_ai_supply_threshold() -> u8 {
+    25
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AiCommanderCfg {
+    /// The number of points added to the commander's budget per
+    /// hour at full strength
+    pub points_per_hour: u32,
+    /// The commander's budget will not grow beyond this
+    pub max_budget: u32,
+    /// How often in seconds the commander makes a decision. At most
+    /// one action is started per decision.
+    #[serde(default = "default_ai_decision_interval")]
+    pub decision_interval: u32,
+    /// The commander is at full strength while the number of human
+    /// players on its side is at or below this
+    #[serde(default)]
+    pub min_players: u32,
+    /// The commander's strength scales down linearly from
+    /// min_players, and it stops acting entirely when the number of
+    /// human players on its side reaches this
+    pub max_players: u32,
+    /// Enemy aircraft within this many meters of a friendly
+    /// objective, as seen by the side's EWRs, threaten it
+    #[serde(default = "default_ai_threat_range")]
+    pub threat_range: u32,
+    /// Objectives with logistics below this percentage are repaired
+    #[serde(default = "default_ai_repair_threshold")]
+    pub repair_threshold: u8,
+    /// Objectives with supply or fuel below this percentage are
+    /// resupplied from the nearest logistics hub
+    #[serde(default = "default_ai_supply_threshold")]
+    pub supply_threshold: u8,
+    /// The name of a Fighters action to send to threatened objectives
+    #[serde(default)]
+    pub cap: Option<String>,
+    /// The name of a Sead action to send at jtac spotted air defenses
+    #[serde(default)]
+    pub sead: Option<String>,
+    /// The name of an Attackers action to send at jtac spotted ground
+    /// units
+    #[serde(default)]
+    pub attack: Option<String>,
+    /// The name of a Drone action to send over the front line
+    #[serde(default)]
+    pub drone: Option<String>,
+    /// The name of a LogisticsRepair action
+    #[serde(default)]
+    pub logistics_repair: Option<String>,
+    /// The name of a LogisticsTransfer action
+    #[serde(default)]
+    pub logistics_transfer: Option<String>,
+}
+
+fn default_capture_max_rate() -> f32 {
+    4.
+}
+
+fn default_capture_decay_rate() -> f32 {
+    0.5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimedCaptureCfg {
+    /// How long in seconds it takes a single capture capable troop
+    /// unit to capture an objective
+    pub capture_time: u32,
+    /// Capture rate multiplier per unit by troop name. Troops that
+    /// aren't listed capture at a rate of 1 per unit in the zone.
+    #[serde(default)]
+    pub troop_rate: FxHashMap<String, f32>,
+    /// The maximum combined capture rate of all the troops in the zone
+    #[serde(default = "default_capture_max_rate")]
+    pub max_rate: f32,
+    /// The rate at which capture progress decays when no capture
+    /// capable troops are in the zone
+    #[serde(default = "default_capture_decay_rate")]
+    pub decay_rate: f32,
+    /// If true dead units that are still in the zone don't count
+    /// toward the capture rate or toward contesting the capture
+    #[serde(default)]
+    pub ignore_dead: bool,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -358,24 +742,53 @@ pub struct DeployableJtac {
     pub nolos: bool,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MinefieldCfg {
+    /// The length of the mine belt (Meters). The belt is laid across
+    /// the direction the deployer is facing.
+    pub length: u32,
+    /// The depth of the mine belt (Meters)
+    pub depth: u32,
+    /// How many mines are in the field. Each hit or cleared mine uses
+    /// one up, and the field is removed when none are left.
+    pub mines: u32,
+    /// The chance (percent) each slow tick that a moving enemy ground
+    /// unit inside the field hits a mine
+    pub hit_chance: u8,
+    /// The explosive power of a mine
+    pub power: u32,
+    /// How many mines an engineer squad inside the field clears each
+    /// slow tick
+    pub clear_rate: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DeployableKind {
     Group { template: String },
     Objective(DeployableObjective),
+    Minefield(MinefieldCfg),
 }
 
 impl DeployableKind {
     pub fn is_group(&self) -> bool {
         match self {
             Self::Group { .. } => true,
-            Self::Objective(_) => false,
+            Self::Objective(_) | Self::Minefield(_) => false,
         }
     }
 
     pub fn is_objective(&self) -> bool {
         match self {
             Self::Objective(_) => true,
-            Self::Group { .. } => false,
+            Self::Group { .. } | Self::Minefield(_) => false,
+        }
+    }
+
+    pub fn is_minefield(&self) -> bool {
+        match self {
+            Self::Minefield(_) => true,
+            Self::Group { .. } | Self::Objective(_) => false,
         }
     }
 }
@@ -446,6 +859,9 @@ pub struct Troop {
     pub cost: u32,
     /// Can laser designate and scout
     pub jtac: Option<DeployableJtac>,
+    /// Can the troops clear enemy minefields they are standing in
+    #[serde(default)]
+    pub can_clear_mines: bool,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -484,6 +900,25 @@ pub struct WarehouseConfig {
     /// warehouse check
     #[serde(default)]
     pub exempt_airframes: FxHashSet<String>,
+    /// How long in minutes a player placed supply request stays open
+    /// before it expires
+    #[serde(default = "default_supply_request_lifetime")]
+    pub supply_request_lifetime: u32,
+    /// The minimum objective fuel percentage required to occupy a
+    /// slot of the specified life type. Life types that are not
+    /// listed are never denied for lack of fuel.
+    #[serde(default)]
+    pub min_fuel: FxHashMap<LifeType, u8>,
+    /// The minimum objective supply percentage required to occupy a
+    /// slot of the specified life type. Life types that are not
+    /// listed are never denied for lack of supply.
+    #[serde(default)]
+    pub min_supply: FxHashMap<LifeType, u8>,
+    /// The minimum objective supply percentage required to take off
+    /// carrying the specified weapon type. Weapons that are not listed
+    /// are never restricted.
+    #[serde(default)]
+    pub weapon_min_supply: FxHashMap<String, u8>,
 }
 
 impl WarehouseConfig {
@@ -496,6 +931,10 @@ impl WarehouseConfig {
     }
 }
 
+fn default_supply_request_lifetime() -> u32 {
+    60
+}
+
 fn default_tk_window() -> u32 {
     24
 }
@@ -548,6 +987,10 @@ pub struct PointsCfg {
     /// interval must be positive. The default is (0, 0)
     #[serde(default)]
     pub periodic_point_gain: (i32, u32),
+    /// Bonus points awarded to the player whose supply transfer fills
+    /// an open supply request
+    #[serde(default)]
+    pub supply_request_fill: u32,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -568,10 +1011,20 @@ pub struct AiPlaneCfg {
     pub freq: Option<i64>,
 }
 
+fn default_awacs_picture_interval() -> u32 {
+    300
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AwacsCfg {
     pub ewr: DeployableEwr,
     pub plane: AiPlaneCfg,
+    /// The radio callsign the awacs uses in its calls
+    #[serde(default)]
+    pub callsign: Option<String>,
+    /// How often the awacs broadcasts a picture to its side (Seconds)
+    #[serde(default = "default_awacs_picture_interval")]
+    pub picture_interval: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -595,6 +1048,38 @@ pub struct DroneCfg {
     pub plane: AiPlaneCfg,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconCfg {
+    /// How far the recon aircraft can see enemy ground units in meters
+    pub range: u32,
+    /// How long spotted groups stay marked on the F10 map in minutes
+    pub mark_duration: u32,
+    pub plane: AiPlaneCfg,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JammerCfg {
+    /// How far from the jammer enemy radars are affected in meters
+    pub range: u32,
+    /// The width of the jamming cone in degrees. The cone points
+    /// from the jammer toward the enemy, perpendicular to its race
+    /// track.
+    pub beam_width: u32,
+    /// Jammed enemy EWRs detect at this percentage of their normal
+    /// range
+    pub ewr_range: u8,
+    /// Jammed enemy EWRs update their tracks at most once per this
+    /// many seconds
+    pub track_delay: u32,
+    /// The max error in meters added to the positions of tracks
+    /// reported by jammed enemy EWRs
+    pub track_error: u32,
+    /// Jammed enemy SAMs with search radars engage at this
+    /// percentage of their normal range
+    pub sam_range: u8,
+    pub plane: AiPlaneCfg,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NukeCfg {
     /// using a nuke reduces the cost of nukes for everyone by this
@@ -613,6 +1098,47 @@ pub struct MoveCfg {
     pub troop: u32,
     /// max distance for deployable moves in meters per unit cost
     pub deployable: u32,
+    /// max distance for naval action group moves in meters per unit
+    /// cost. Ships can't be moved if this is not set.
+    #[serde(default)]
+    pub naval: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NavalGroupCfg {
+    /// The ship group template, it spawns at the nearest friendly
+    /// port or carrier and sails to the target point
+    pub template: String,
+    /// How long the group stays before it is removed in hours,
+    /// forever if not set
+    pub duration: Option<u32>,
+    /// Cruising speed in meters per second
+    pub speed: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoreBombardmentCfg {
+    /// A friendly naval group must be within this many meters of
+    /// the jtac's target to fire
+    pub range: u32,
+    /// The radius of the fire mission around the target in meters
+    pub radius: u32,
+    /// How many rounds to expend
+    pub rounds: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AirdropCfg {
+    /// The transport that carries the packages
+    pub plane: AiPlaneCfg,
+    /// The max number of packages in one airdrop. The action cost is
+    /// charged per package.
+    pub max_packages: u8,
+    /// Each package dropped at a friendly objective restores this
+    /// percent of the capacity of every item in its warehouse.
+    /// Packages dropped away from a friendly objective become supply
+    /// transfer crates.
+    pub supply: u8,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -626,6 +1152,9 @@ pub enum ActionKind {
     CruiseMissileSpawn(AiPlaneCfg),
     CruiseMissileWaypoint,
     Drone(DroneCfg),
+    Recon(ReconCfg),
+    Jammer(JammerCfg),
+    JammerWaypoint,
     Nuke(NukeCfg),
     FighersWaypoint,
     AttackersWaypoint,
@@ -637,8 +1166,12 @@ pub enum ActionKind {
     Deployable(DeployableCfg),
     LogisticsRepair(AiPlaneCfg),
     LogisticsTransfer(AiPlaneCfg),
+    Airdrop(AirdropCfg),
     Move(MoveCfg),
     Rtb,
+    NavalPatrol(NavalGroupCfg),
+    ShoreBombardment(ShoreBombardmentCfg),
+    AntiShip(AiPlaneCfg),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -656,15 +1189,46 @@ impl Default for ActionGeoLimit {
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActionWindowLimit {
+    /// The max number of times the action can be used by the side
+    pub limit: u32,
+    /// within any window of this many minutes
+    pub window: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActionThrottle {
+    /// Minutes a player must wait before using the action again
+    #[serde(default)]
+    pub player_cooldown: Option<u32>,
+    /// Minutes the whole side must wait after anyone uses the action
+    #[serde(default)]
+    pub side_cooldown: Option<u32>,
+    /// Limit the number of uses in a rolling window
+    #[serde(default)]
+    pub window_limit: Option<ActionWindowLimit>,
+    /// How much of the side command budget the action uses. Only
+    /// applies if command_budget is configured.
+    #[serde(default)]
+    pub budget: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Action {
     pub kind: ActionKind,
     pub cost: u32,
     pub penalty: Option<u32>,
+    /// The max number of times the side can use this action per restart
     pub limit: Option<u32>,
     /// defines where this action is allowed to run
     #[serde(default)]
     pub geo_limit: ActionGeoLimit,
+    /// cooldowns, rolling limits, and command budget cost
+    #[serde(default)]
+    pub throttle: ActionThrottle,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -820,6 +1384,10 @@ pub struct Cfg {
     pub threatened_distance: FxHashMap<Vehicle, u32>,
     /// how long before threatened is removed if no enemy can be seen
     pub threatened_cooldown: u32,
+    /// carrier slots are closed when the carrier objective's health is
+    /// below this percentage
+    #[serde(default)]
+    pub carrier_min_health: u8,
     /// how far can a crate be from the player and still be
     /// loadable (Meters)
     pub crate_load_distance: u32,
@@ -872,6 +1440,10 @@ pub struct Cfg {
     pub airborne_jtacs: FxHashMap<Vehicle, DeployableJtac>,
     /// The jtac target priority list
     pub jtac_priority: Vec<UnitTags>,
+    /// If set jtacs order their contacts by score instead of by
+    /// jtac_priority
+    #[serde(default)]
+    pub jtac_scoring: Option<JtacScoringCfg>,
     /// Objectives that can host fixed wing even though they aren't
     /// airbases. Used by actions to choose a spawn point. E.G. You
     /// want to make an airbase a logistics hub because it's close to
@@ -884,6 +1456,61 @@ pub struct Cfg {
     /// EWR track update delay in seconds (only used when ewr_mode is Delayed)
     #[serde(default = "default_ewr_delay")]
     pub ewr_delay: u32,
+    /// If set ewrs model a radar horizon, target size, and a doppler
+    /// notch, and detect targets probabilistically, instead of seeing
+    /// everything in range with line of sight
+    #[serde(default)]
+    pub ewr_radar: Option<EwrRadarCfg>,
+    /// If set players are warned when enemy tracks are closing on
+    /// them, and when they merge
+    #[serde(default)]
+    pub ewr_threat: Option<EwrThreatCfg>,
+    /// If set ewr coverage and known enemy sam rings are drawn on the
+    /// F10 map
+    #[serde(default)]
+    pub threat_map: Option<ThreatMapCfg>,
+    /// If set the side's ewr and awacs tracks are shared with link16
+    /// capable player aircraft as F10 marks
+    #[serde(default)]
+    pub link16: Option<Link16Cfg>,
+    /// The objective adjacency graph and front line configuration
+    #[serde(default)]
+    pub front_line: Option<FrontLineCfg>,
+    /// If set objectives are captured over time instead of the
+    /// instant troops enter the zone, and capture is contested while
+    /// both sides have ground forces in the zone
+    #[serde(default)]
+    pub timed_capture: Option<TimedCaptureCfg>,
+    /// Upgrade tiers that can be purchased for an objective with
+    /// objective points
+    #[serde(default)]
+    pub objective_upgrades: Option<ObjectiveUpgradeCfg>,
+    /// An AI commander for each side that spends a budget of points
+    /// on actions
+    #[serde(default)]
+    pub ai_commander: FxHashMap<Side, AiCommanderCfg>,
+    /// AI ground offensives launched from front line objectives
+    /// toward adjacent enemy objectives. Requires front_line.
+    #[serde(default)]
+    pub ground_offensive: Option<GroundOffensiveCfg>,
+    /// Combat search and rescue. Pilots who eject away from friendly
+    /// objectives can be rescued by helicopters to restore their
+    /// life, or captured by the enemy.
+    #[serde(default)]
+    pub csar: Option<CsarCfg>,
+    /// Allow players to queue actions to run at a time, when a jtac
+    /// spots a target, or after another queued action
+    #[serde(default)]
+    pub action_queue: Option<ActionQueueCfg>,
+    /// Players flying recon life type aircraft spot enemy ground
+    /// groups they overfly, and deliver the intel when they land at a
+    /// friendly objective
+    #[serde(default)]
+    pub player_recon: Option<PlayerReconCfg>,
+    /// A budget shared by all the players on a side that actions
+    /// with a throttle budget draw from. It regenerates over time.
+    #[serde(default)]
+    pub command_budget: Option<CommandBudgetCfg>,
 }
 
 impl Cfg {
@@ -15,27 +15,46 @@ pub enum ObjectiveKind {
         #[serde(default)]
         mobile: bool,
     },
+    /// A harbor that ships sail from
+    Port,
+    /// A carrier group, its slots depend on the health of the carrier
+    Carrier,
 }
 
 impl ObjectiveKind {
     pub fn is_airbase(&self) -> bool {
         match self {
             Self::Airbase => true,
-            Self::Farp { .. } | Self::Fob | Self::Logistics => false,
+            Self::Farp { .. } | Self::Fob | Self::Logistics | Self::Port | Self::Carrier => false,
         }
     }
 
     pub fn is_farp(&self) -> bool {
         match self {
             Self::Farp { .. } => true,
-            Self::Airbase | Self::Fob | Self::Logistics => false,
+            Self::Airbase | Self::Fob | Self::Logistics | Self::Port | Self::Carrier => false,
         }
     }
 
     pub fn is_hub(&self) -> bool {
         match self {
             Self::Logistics => true,
-            Self::Airbase | Self::Farp { .. } | Self::Fob => false,
+            Self::Airbase | Self::Farp { .. } | Self::Fob | Self::Port | Self::Carrier => false,
+        }
+    }
+
+    /// Ports are bare harbors, everything else is backed by a dcs airbase warehouse
+    pub fn has_warehouse(&self) -> bool {
+        match self {
+            Self::Port => false,
+            Self::Airbase | Self::Farp { .. } | Self::Fob | Self::Logistics | Self::Carrier => true,
+        }
+    }
+
+    pub fn is_naval(&self) -> bool {
+        match self {
+            Self::Port | Self::Carrier => true,
+            Self::Airbase | Self::Farp { .. } | Self::Fob | Self::Logistics => false,
         }
     }
 
@@ -45,6 +64,8 @@ impl ObjectiveKind {
             Self::Fob => "FOB",
             Self::Farp { .. } => "FARP",
             Self::Logistics => "Logistics Hub",
+            Self::Port => "Port",
+            Self::Carrier => "Carrier",
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    cfg::{Cfg, LifeType, UnitTags, Vehicle},
+    cfg::{Cfg, LifeType, UnitTag, UnitTags, Vehicle},
     db::{
         group::{GroupId, UnitId},
         objective::{ObjectiveId, ObjectiveKind},
@@ -56,6 +56,15 @@ pub struct Pos {
 pub enum DetectionSource {
     EWR,
     Jtac,
+    Recon,
+}
+
+/// Battle damage in one category of a battle damage assessment
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BdaCount {
+    pub destroyed: u32,
+    pub damaged: u32,
+    pub remaining: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,6 +120,12 @@ pub enum Stat {
         gid: Option<GroupId>,
         action: String,
     },
+    AiCommanderAction {
+        side: Side,
+        action: String,
+        reason: String,
+        cost: u32,
+    },
     DeployTroop {
         by: Ucid,
         troop: String,
@@ -196,6 +211,13 @@ pub enum Stat {
         lives: MapS<LifeType, (DateTime<Utc>, u8)>,
     },
     Kill(Dead),
+    Bda {
+        side: Side,
+        requested_by: Option<Ucid>,
+        /// a category of None is any unit not in another category
+        by_category: SmallVec<[(Option<UnitTag>, BdaCount); 4]>,
+        scored_by: SmallVec<[(Ucid, u32); 4]>,
+    },
     Points {
         id: Ucid,
         points: i32,
@@ -19,7 +19,7 @@ use crate::{
     simple_enum, wrapped_table, Color, LuaEnv, LuaVec3, MizLua, String,
 };
 use anyhow::Result;
-use mlua::{prelude::*, Value};
+use mlua::{prelude::*, Value, Variadic};
 use serde_derive::{Deserialize, Serialize};
 use std::ops::Deref;
 
@@ -163,6 +163,15 @@ pub struct QuadSpec {
     pub read_only: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreeformSpec {
+    pub points: Vec<LuaVec3>,
+    pub color: Color,
+    pub fill_color: Color,
+    pub line_type: LineType,
+    pub read_only: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextSpec {
     pub pos: LuaVec3,
@@ -460,6 +469,28 @@ impl<'lua> Action<'lua> {
         )?)
     }
 
+    pub fn freeform_to_all(
+        &self,
+        side: SideFilter,
+        id: MarkId,
+        spec: FreeformSpec,
+        message: Option<String>,
+    ) -> Result<()> {
+        let mut args = Variadic::new();
+        args.push(7.into_lua(self.lua)?);
+        args.push(side.into_lua(self.lua)?);
+        args.push(id.into_lua(self.lua)?);
+        for p in spec.points {
+            args.push(p.into_lua(self.lua)?);
+        }
+        args.push(spec.color.into_lua(self.lua)?);
+        args.push(spec.fill_color.into_lua(self.lua)?);
+        args.push(spec.line_type.into_lua(self.lua)?);
+        args.push(spec.read_only.into_lua(self.lua)?);
+        args.push(message.into_lua(self.lua)?);
+        Ok(self.call_function("markupToAll", args)?)
+    }
+
     pub fn text_to_all(&self, side: SideFilter, id: MarkId, spec: TextSpec) -> Result<()> {
         Ok(self.call_function(
             "textToAll",